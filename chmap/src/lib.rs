@@ -6,22 +6,43 @@ use std::{
     ops::Deref,
 };
 
-use hashbrown::HashTable;
+use hashbrown::{hash_table::Entry, HashTable};
 
-#[allow(clippy::type_complexity)]
-type TablePointer<T, S> = dbuf::triomphe::OffsetArc<
-    dbuf::raw::DoubleBufferData<
-        HashTable<T>,
-        dbuf::strategy::flashmap::FlashStrategy<
-            dbuf::strategy::flash_park_token::AdaptiveParkToken,
-        >,
-        S,
-    >,
->;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "serde")]
+mod serde;
+
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "rayon")]
+pub use rayon::ParIter;
+
+type TableStrategy =
+    dbuf::strategy::flashmap::FlashStrategy<dbuf::strategy::flash_park_token::AdaptiveParkToken>;
+
+type TablePointer<T, S> =
+    dbuf::triomphe::OffsetArc<dbuf::raw::DoubleBufferData<HashTable<T>, TableStrategy, S>>;
+
+/// The error returned by [`Writer::try_publish`]
+pub type PublishError = <TableStrategy as dbuf::interface::Strategy>::SwapError;
 
 #[allow(clippy::type_complexity)]
 pub struct Writer<'env, K, V, S = RandomState> {
     writer: dbuf::op::OpWriter<TablePointer<(K, V), S>, HashTableOperation<'env, K, V, S>>,
+    max_len: Option<usize>,
+    equalize_capacity: bool,
+    metrics: MapMetrics,
+}
+
+/// Counters of how many operations were performed on a map, see [`Writer::metrics`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MapMetrics {
+    pub inserts: u64,
+    pub removes: u64,
+    pub publishes: u64,
 }
 
 pub struct Reader<K, V, S> {
@@ -50,9 +71,41 @@ pub enum HashTableOperation<'env, K, V, S> {
         key: K,
         value: V,
     },
+    /// Insert the entry only if the key isn't in the table yet
+    ///
+    /// This checks each table separately when it's applied, so both tables end up with the same
+    /// entry once the operation was applied to both of them
+    InsertIfAbsent {
+        key: K,
+        value: V,
+    },
+    /// Update the value of the entry with the given `key`, or insert the entry if the key isn't
+    /// in the table yet
+    ///
+    /// `hash` must be the hash of `key`, so that it doesn't need to be rehashed for each table.
+    /// If the key is already in the table, then only the value is replaced, so the key is never
+    /// cloned. The entry is looked up again in each table, and since both tables see the same
+    /// operations in the same order, both tables end up with the same entry.
+    Update {
+        hash: u64,
+        key: K,
+        value: V,
+    },
     Remove {
         key: K,
     },
+    /// Remove the entry with the given `hash` for which `eq` returns true
+    ///
+    /// `eq` is called on both tables, so it must give the same answer for the same key each time
+    #[allow(clippy::type_complexity)]
+    RemoveWith {
+        hash: u64,
+        eq: Box<dyn Fn(&K) -> bool + Send + 'env>,
+    },
+    /// Reserve room for at least `additional` more entries
+    Reserve {
+        additional: usize,
+    },
     #[allow(clippy::type_complexity)]
     Custom {
         f: Box<dyn FnMut(bool, &mut HashTable<(K, V)>, &S) + Send + 'env>,
@@ -63,46 +116,277 @@ impl<K, V> Writer<'_, K, V> {
     pub fn new() -> Self {
         Self::with_hasher(RandomState::new())
     }
+
+    /// Create a map where both tables can hold at least `capacity` entries without reallocating
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+
+    /// Create a map which evicts entries in [`Writer::insert_with_eviction`] to stay
+    /// within `max_len` entries
+    ///
+    /// The bound is only enforced by [`Writer::insert_with_eviction`], all other ways to add
+    /// entries (like [`Writer::insert`] or [`Extend`]) ignore it, and may grow the map past
+    /// `max_len`. see [`Writer::insert_with_eviction`] for details
+    pub fn with_bounded_capacity(max_len: usize) -> Self {
+        Self::with_bounded_capacity_and_hasher(max_len, RandomState::new())
+    }
 }
 
 impl<K, V, S> Writer<'_, K, V, S> {
     pub fn with_hasher(hasher: S) -> Self {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// Create a map where both tables can hold at least `capacity` entries without reallocating
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
         Self {
             writer: dbuf::op::OpWriter::from(dbuf::raw::Writer::new(
                 dbuf::triomphe::UniqueArc::new(dbuf::raw::DoubleBufferData::with_extras(
-                    HashTable::new(),
-                    HashTable::new(),
+                    HashTable::with_capacity(capacity),
+                    HashTable::with_capacity(capacity),
                     dbuf::strategy::flashmap::FlashStrategy::new(),
                     hasher,
                 )),
             )),
+            max_len: None,
+            equalize_capacity: false,
+            metrics: MapMetrics::default(),
+        }
+    }
+
+    /// Create a map which evicts entries in [`Writer::insert_with_eviction`] to stay
+    /// within `max_len` entries
+    ///
+    /// The bound is only enforced by [`Writer::insert_with_eviction`], all other ways to add
+    /// entries (like [`Writer::insert`] or [`Extend`]) ignore it, and may grow the map past
+    /// `max_len`. see [`Writer::insert_with_eviction`] for details
+    pub fn with_bounded_capacity_and_hasher(max_len: usize, hasher: S) -> Self {
+        Self {
+            max_len: Some(max_len),
+            ..Self::with_hasher(hasher)
         }
     }
 
+    /// Grow the write table on each publish, so that it has at least the same capacity as the
+    /// read table
+    ///
+    /// The two tables may resize at different times (for example if one of them caught up to
+    /// a [`Writer::reserve`] before the other), which makes lookups faster or slower depending on
+    /// which table a reader lands on. With this enabled, the smaller table is grown the next time
+    /// it's the write table, so both tables end up with the same capacity.
+    ///
+    /// By default this is disabled
+    pub const fn with_capacity_equalization(mut self, enabled: bool) -> Self {
+        self.equalize_capacity = enabled;
+        self
+    }
+
+    /// Counters of how many inserts, removes, and publishes were performed on this map
+    ///
+    /// Evictions are counted as removes
+    pub const fn metrics(&self) -> MapMetrics {
+        self.metrics
+    }
+
+    /// The maximum number of entries, if this map is bounded
+    ///
+    /// see [`Writer::with_bounded_capacity`] for which operations respect this bound
+    pub const fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
     pub fn reader(&self) -> Reader<K, V, S> {
         Reader {
             reader: self.writer.reader(),
         }
     }
+
+    /// The hasher used to hash the keys of this map
+    pub fn hasher(&self) -> &S {
+        self.writer.extras()
+    }
+
+    /// Look up an entry by it's hash and a custom equality check, without hashing a key
+    ///
+    /// `hash` must be computed the same way that the map hashes keys (with [`Writer::hasher`]),
+    /// otherwise the entry may not be found. `eq` is called for each entry with a matching hash.
+    pub fn find_by(&self, hash: u64, mut eq: impl FnMut(&K, &V) -> bool) -> Option<&V> {
+        let (_, v) = self.writer.get().find(hash, |(k, v)| eq(k, v))?;
+        Some(v)
+    }
+
+    /// Iterate over the entries in the write buffer
+    ///
+    /// This doesn't see pending operations which weren't applied to the write buffer yet,
+    /// see [`Writer::apply_pending`]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            raw: self.writer.get().iter(),
+        }
+    }
+
+    /// Iterate over the keys in the write buffer, see [`Writer::iter`]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Iterate over the values in the write buffer, see [`Writer::iter`]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { iter: self.iter() }
+    }
 }
 
 impl<'env, K, V, S: BuildHasher> Writer<'env, K, V, S> {
+    /// Apply all pending operations to the write buffer, without publishing them
+    ///
+    /// Writes are only recorded as pending operations, so the write buffer (which [`Writer::get`]
+    /// and [`Writer::iter`] look at) doesn't reflect them until they are applied. This finishes
+    /// the swap started by the last publish, so it may block until all readers have left the
+    /// write buffer.
+    pub fn apply_pending(&mut self)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.writer.apply_pending(&mut ())
+    }
+
+    /// Look up a key, including the writes which weren't published yet
+    ///
+    /// This applies all pending operations first (see [`Writer::apply_pending`]),
+    /// so it may block until all readers have left the write buffer.
+    pub fn get_local<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q> + Hash + Eq + Clone,
+        V: Clone,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.apply_pending();
+        self.get(key)
+    }
+
+    /// Insert an entry, replacing the old value if the key is already in the map
+    ///
+    /// The entry is only visible to readers after the next publish, and to [`Writer::get`]
+    /// after the pending operations are applied (see [`Writer::apply_pending`])
     pub fn insert(&mut self, key: K, value: V)
     where
         K: Hash + Eq + Clone,
         V: Clone,
     {
+        self.metrics.inserts += 1;
         self.writer.push(HashTableOperation::Insert { key, value })
     }
 
+    /// Insert an entry, while keeping the map within its bounded capacity
+    ///
+    /// If the map is full and doesn't contain `key`, then `evict` picks a key to be
+    /// removed before the entry is inserted. Both the removal and the insertion are
+    /// recorded as operations, so they are applied the same way to both buffers.
+    ///
+    /// If `evict` returns [`None`], or a key which isn't in the map, then nothing is
+    /// evicted and the entry is still inserted, so the map grows past its bound.
+    ///
+    /// This applies all pending operations to the write buffer (see [`dbuf::op::OpWriter::apply`]),
+    /// so it may block until all readers have left the write buffer.
+    ///
+    /// If the map isn't bounded, then this is the same as [`Writer::insert`]
+    pub fn insert_with_eviction(
+        &mut self,
+        key: K,
+        value: V,
+        evict: impl FnOnce(Iter<'_, K, V>) -> Option<K>,
+    ) where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        let Some(max_len) = self.max_len else {
+            return self.insert(key, value);
+        };
+
+        self.writer.apply_pending(&mut ());
+
+        let table = self.writer.get();
+        let hash = self.writer.extras().hash_one(&key);
+
+        if table.len() >= max_len && table.find(hash, |(k, _)| *k == key).is_none() {
+            if let Some(evicted) = evict(Iter { raw: table.iter() }) {
+                self.metrics.removes += 1;
+                self.writer
+                    .apply(HashTableOperation::Remove { key: evicted }, &mut ());
+            }
+        }
+
+        self.metrics.inserts += 1;
+        self.writer
+            .apply(HashTableOperation::Insert { key, value }, &mut ());
+    }
+
+    /// Insert an entry only if the key isn't in the map yet, otherwise the old value is kept
+    ///
+    /// see [`Writer::insert`] for when this is visible
+    pub fn insert_if_absent(&mut self, key: K, value: V)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.metrics.inserts += 1;
+        self.writer
+            .push(HashTableOperation::InsertIfAbsent { key, value })
+    }
+
+    /// Update the value of an entry, or insert the entry if the key isn't in the map yet
+    ///
+    /// Unlike [`Writer::insert`], this keeps the old key if the key is already in the map, so
+    /// the key is only cloned if the entry has to be inserted. This is useful if keys are
+    /// expensive to clone, and are usually already in the map.
+    ///
+    /// This still requires `K: Clone` and `V: Clone`, since the operation is replayed on both
+    /// tables, and if the key is missing from both of them, then the first table gets a clone
+    /// of the entry.
+    ///
+    /// see [`Writer::insert`] for when this is visible
+    pub fn update(&mut self, key: K, value: V)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        let hash = self.writer.extras().hash_one(&key);
+        self.metrics.inserts += 1;
+        self.writer
+            .push(HashTableOperation::Update { hash, key, value })
+    }
+
+    /// Remove an entry
+    ///
+    /// see [`Writer::insert`] for when this is visible
     pub fn remove(&mut self, key: K)
     where
         K: Hash + Eq + Clone,
         V: Clone,
     {
+        self.metrics.removes += 1;
         self.writer.push(HashTableOperation::Remove { key })
     }
 
+    /// Remove an entry by it's hash and a custom equality check, without needing an owned key
+    ///
+    /// `hash` must be computed the same way that the map hashes keys (with [`Writer::hasher`]),
+    /// otherwise the entry may not be found. `eq` is replayed on both tables, so it must be
+    /// deterministic, otherwise the tables may end up with different entries.
+    ///
+    /// This doesn't clone or hash any keys, so it has no bounds on `K` or `V`.
+    ///
+    /// see [`Writer::insert`] for when this is visible
+    pub fn remove_with(&mut self, hash: u64, eq: impl Fn(&K) -> bool + Send + 'env) {
+        self.metrics.removes += 1;
+        self.writer.push(HashTableOperation::RemoveWith {
+            hash,
+            eq: Box::new(eq),
+        })
+    }
+
     pub fn contains_key<Q>(&self, key: &Q) -> bool
     where
         K: Borrow<Q>,
@@ -122,6 +406,10 @@ impl<'env, K, V, S: BuildHasher> Writer<'env, K, V, S> {
         Some((k, v))
     }
 
+    /// Look up a key in the write buffer
+    ///
+    /// This doesn't see pending operations which weren't applied to the write buffer yet,
+    /// see [`Writer::get_local`]
     pub fn get<Q>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
@@ -140,13 +428,112 @@ impl<'env, K, V, S: BuildHasher> Writer<'env, K, V, S> {
         })
     }
 
+    /// Remove all entries
+    ///
+    /// This is applied in order with the other pending operations, so any
+    /// entries inserted before this call are removed as well
+    pub fn clear(&mut self)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.writer.push(HashTableOperation::Custom {
+            f: Box::new(|_, table, _hasher| table.clear()),
+        })
+    }
+
+    /// Reserve room for at least `additional` more entries in both tables
+    ///
+    /// This is applied in order with the other pending operations, so each table
+    /// reserves the same amount of room once it has caught up to this point.
+    pub fn reserve(&mut self, additional: usize)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.writer.push(HashTableOperation::Reserve { additional })
+    }
+
+    /// Shrink the capacity of both tables as much as possible
+    ///
+    /// This is applied in order with the other pending operations, so each table is
+    /// shrunk once it has caught up to this point. The entries are rehashed with the
+    /// map's hasher.
+    pub fn shrink_to_fit(&mut self)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.writer.push(HashTableOperation::Custom {
+            f: Box::new(|_, table, hasher| table.shrink_to_fit(|(key, _)| hasher.hash_one(key))),
+        })
+    }
+
+    /// Shrink the capacity of both tables, while keeping room for at least `min_capacity` entries
+    ///
+    /// see [`Writer::shrink_to_fit`] for details
+    pub fn shrink_to(&mut self, min_capacity: usize)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.writer.push(HashTableOperation::Custom {
+            f: Box::new(move |_, table, hasher| {
+                table.shrink_to(min_capacity, |(key, _)| hasher.hash_one(key))
+            }),
+        })
+    }
+
+    /// Reserve room in the write table, so that it's at least as big as the read table
+    ///
+    /// see [`Writer::with_capacity_equalization`] for details
+    fn equalize_capacity(&mut self)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        if !self.equalize_capacity {
+            return;
+        }
+
+        self.writer.apply_pending(&mut ());
+
+        let split = self.writer.split();
+        let capacity = split.read.capacity();
+
+        if split.write.capacity() < capacity {
+            // this is recorded as an operation so that it's replayed on the other table,
+            // where it's a no-op since that table already has this capacity
+            let additional = capacity - split.write.len();
+            self.writer
+                .apply(HashTableOperation::Reserve { additional }, &mut ());
+        }
+    }
+
     pub fn publish(&mut self)
     where
         K: Hash + Eq + Clone,
         V: Clone,
     {
+        self.equalize_capacity();
+        self.metrics.publishes += 1;
         self.writer.swap_buffers(&mut ());
     }
+
+    /// Publish all pending operations, returning an error if the swap couldn't be started
+    ///
+    /// If this fails, then the pending operations are kept, and will be
+    /// published by the next successful publish
+    pub fn try_publish(&mut self) -> Result<(), PublishError>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        self.equalize_capacity();
+        self.writer.try_swap_buffers(&mut ())?;
+        self.metrics.publishes += 1;
+        Ok(())
+    }
 }
 
 impl<K, V, S> Reader<K, V, S> {
@@ -157,6 +544,58 @@ impl<K, V, S> Reader<K, V, S> {
     }
 }
 
+impl<K, V, S: BuildHasher> Reader<K, V, S> {
+    /// Look up several keys at once, while only acquiring a single read guard
+    pub fn get_batch<Q>(&mut self, keys: &[Q]) -> Vec<Option<V>>
+    where
+        Q: Hash + Eq,
+        K: Borrow<Q>,
+        V: Clone,
+    {
+        let values = self.reader.with_read(|table, hasher| {
+            keys.iter()
+                .map(|key| {
+                    let hash = hasher.hash_one(key);
+                    let (_, value) = table.find(hash, |(k, _)| k.borrow() == key)?;
+                    Some(value.clone())
+                })
+                .collect()
+        });
+
+        match values {
+            Ok(values) => values,
+            Err(err) => match err {},
+        }
+    }
+
+    /// Look up a key, and clone its value
+    ///
+    /// The read guard is released before this returns, so holding onto the value
+    /// doesn't block the writer from publishing
+    pub fn get_cloned<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+        V: Clone,
+    {
+        self.load().get(key).cloned()
+    }
+
+    /// Look up a key, and clone the key and its value
+    ///
+    /// see [`Reader::get_cloned`] for details
+    pub fn get_key_value_cloned<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q> + Clone,
+        V: Clone,
+    {
+        let guard = self.load();
+        let (key, value) = guard.get_key_value(key)?;
+        Some((key.clone(), value.clone()))
+    }
+}
+
 impl<'a, K, V, S: BuildHasher> TableGuard<'a, K, V, S> {
     pub fn contains_key<Q>(&self, key: &Q) -> bool
     where
@@ -219,6 +658,19 @@ impl<K, V, S> TableGuard<'_, K, V, S> {
             raw: self.reader.iter(),
         }
     }
+
+    /// The hasher used to hash the keys of this map
+    pub fn hasher(&self) -> &S {
+        self.reader.extras()
+    }
+
+    /// Look up an entry by it's hash and a custom equality check, without hashing a key
+    ///
+    /// see [`Writer::find_by`] for details
+    pub fn find_by(&self, hash: u64, mut eq: impl FnMut(&K, &V) -> bool) -> Option<&V> {
+        let (_, v) = self.reader.find(hash, |(k, v)| eq(k, v))?;
+        Some(v)
+    }
 }
 
 impl<T: ?Sized, K, V, S> ReadGuard<'_, T, K, V, S> {}
@@ -240,12 +692,87 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
     }
 }
 
+impl<'a, K, V, S> IntoIterator for &'a TableGuard<'_, K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Keys<'a, K, V> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, _) = self.iter.next()?;
+        Some(k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+pub struct Values<'a, K, V> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.next()?;
+        Some(v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 impl<K, V, S: Default> Default for Writer<'_, K, V, S> {
     fn default() -> Self {
         Self::with_hasher(Default::default())
     }
 }
 
+/// Inserts are applied in order, so if a key appears multiple times, the last value is kept
+///
+/// The inserts aren't published, call [`Writer::publish`] to make them visible to readers
+impl<K, V, S> Extend<(K, V)> for Writer<'_, K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter()
+            .for_each(|(key, value)| self.insert(key, value));
+    }
+}
+
+/// The entries are published, so they are visible to all readers created from the map
+///
+/// If a key appears multiple times, then the last value is kept
+impl<K, V, S> FromIterator<(K, V)> for Writer<'_, K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut writer = Self::default();
+        writer.extend(iter);
+        writer.publish();
+        writer
+    }
+}
+
 impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher> dbuf::op::Operation<HashTable<(K, V)>, S, ()>
     for HashTableOperation<'_, K, V, S>
 {
@@ -259,12 +786,35 @@ impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher> dbuf::op::Operation<HashTab
                     buffer.insert_unique(hash, (key, value), |(key, _)| hasher.hash_one(key));
                 }
             }
+            HashTableOperation::InsertIfAbsent { key, value } => {
+                let hash = hasher.hash_one(&key);
+                if let Entry::Vacant(entry) =
+                    buffer.entry(hash, |(k, _)| *k == key, |(key, _)| hasher.hash_one(key))
+                {
+                    entry.insert((key, value));
+                }
+            }
+            HashTableOperation::Update { hash, key, value } => {
+                if let Some((_, old_value)) = buffer.find_mut(hash, |(k, _)| *k == key) {
+                    *old_value = value;
+                } else {
+                    buffer.insert_unique(hash, (key, value), |(key, _)| hasher.hash_one(key));
+                }
+            }
             HashTableOperation::Remove { key } => {
                 let hash = hasher.hash_one(&key);
                 if let Ok(entry) = buffer.find_entry(hash, |(k, _)| *k == key) {
                     entry.remove();
                 }
             }
+            HashTableOperation::RemoveWith { hash, eq } => {
+                if let Ok(entry) = buffer.find_entry(hash, |(k, _)| eq(k)) {
+                    entry.remove();
+                }
+            }
+            HashTableOperation::Reserve { additional } => {
+                buffer.reserve(additional, |(key, _)| hasher.hash_one(key))
+            }
             HashTableOperation::Custom { mut f } => f(false, buffer, hasher),
         }
     }
@@ -282,12 +832,37 @@ impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher> dbuf::op::Operation<HashTab
                     });
                 }
             }
+            HashTableOperation::InsertIfAbsent { key, value } => {
+                let hash = hasher.hash_one(&*key);
+                if let Entry::Vacant(entry) =
+                    buffer.entry(hash, |(k, _)| k == key, |(key, _)| hasher.hash_one(key))
+                {
+                    entry.insert((key.clone(), value.clone()));
+                }
+            }
+            HashTableOperation::Update { hash, key, value } => {
+                if let Some((_, old_value)) = buffer.find_mut(*hash, |(k, _)| k == key) {
+                    old_value.clone_from(value);
+                } else {
+                    buffer.insert_unique(*hash, (key.clone(), value.clone()), |(key, _)| {
+                        hasher.hash_one(key)
+                    });
+                }
+            }
             HashTableOperation::Remove { key } => {
                 let hash = hasher.hash_one(&*key);
                 if let Ok(entry) = buffer.find_entry(hash, |(k, _)| k == key) {
                     entry.remove();
                 }
             }
+            HashTableOperation::RemoveWith { hash, eq } => {
+                if let Ok(entry) = buffer.find_entry(*hash, |(k, _)| eq(k)) {
+                    entry.remove();
+                }
+            }
+            HashTableOperation::Reserve { additional } => {
+                buffer.reserve(*additional, |(key, _)| hasher.hash_one(key))
+            }
             HashTableOperation::Custom { f } => f(true, buffer, hasher),
         }
     }
@@ -0,0 +1,31 @@
+use rayon::iter::{plumbing::UnindexedConsumer, IntoParallelRefIterator, ParallelIterator};
+
+use crate::TableGuard;
+
+impl<K: Sync, V: Sync, S> TableGuard<'_, K, V, S> {
+    /// Iterate over the entries in parallel
+    ///
+    /// The guard is borrowed for the whole parallel computation, so the
+    /// writer cannot modify this snapshot until it is finished
+    pub fn par_iter(&self) -> ParIter<'_, K, V> {
+        ParIter {
+            raw: self.reader.par_iter(),
+        }
+    }
+}
+
+/// A parallel iterator over the entries of a [`TableGuard`], see [`TableGuard::par_iter`]
+pub struct ParIter<'a, K, V> {
+    raw: hashbrown::hash_table::rayon::ParIter<'a, (K, V)>,
+}
+
+impl<'a, K: Sync, V: Sync> ParallelIterator for ParIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.raw.map(|(k, v)| (k, v)).drive_unindexed(consumer)
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    fmt,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{TableGuard, Writer};
+
+/// Serializes the snapshot held by this guard as a map
+impl<K: Serialize, V: Serialize, S> Serialize for TableGuard<'_, K, V, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut map = serializer.serialize_map(Some(self.reader.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Deserializes a map into a fresh writer, all entries are published at once
+///
+/// If a key appears multiple times, then the last value wins
+impl<'de, K, V, S> Deserialize<'de> for Writer<'_, K, V, S>
+where
+    K: Deserialize<'de> + Hash + Eq + Clone,
+    V: Deserialize<'de> + Clone,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(WriterVisitor(PhantomData))
+    }
+}
+
+struct WriterVisitor<'env, K, V, S>(PhantomData<(&'env (), K, V, S)>);
+
+impl<'de, 'env, K, V, S> Visitor<'de> for WriterVisitor<'env, K, V, S>
+where
+    K: Deserialize<'de> + Hash + Eq + Clone,
+    V: Deserialize<'de> + Clone,
+    S: BuildHasher + Default,
+{
+    type Value = Writer<'env, K, V, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut writer = Writer::default();
+
+        while let Some((key, value)) = access.next_entry()? {
+            writer.insert(key, value);
+        }
+
+        writer.publish();
+
+        Ok(writer)
+    }
+}
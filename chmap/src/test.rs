@@ -0,0 +1,111 @@
+use std::hash::BuildHasher;
+
+use super::{Reader, Writer};
+
+/// The entries the reader currently sees, sorted by key
+fn entries<S: BuildHasher>(reader: &mut Reader<i32, i32, S>) -> Vec<(i32, i32)> {
+    let mut entries = reader
+        .load()
+        .iter()
+        .map(|(&k, &v)| (k, v))
+        .collect::<Vec<_>>();
+    entries.sort_unstable();
+    entries
+}
+
+/// Publish twice, so that both tables saw all operations, and check that they agree
+fn assert_converges<S: BuildHasher>(writer: &mut Writer<i32, i32, S>, expected: &[(i32, i32)]) {
+    let mut reader = writer.reader();
+
+    writer.publish();
+    assert_eq!(entries(&mut reader), expected);
+
+    writer.publish();
+    assert_eq!(entries(&mut reader), expected);
+}
+
+#[test]
+fn insert_if_absent() {
+    let mut writer = Writer::new();
+    writer.insert(1, 10);
+    writer.publish();
+
+    // the key is only in one of the tables at this point
+    writer.insert_if_absent(1, 11);
+    writer.insert_if_absent(2, 20);
+    writer.insert_if_absent(2, 21);
+
+    assert_converges(&mut writer, &[(1, 10), (2, 20)]);
+}
+
+#[test]
+fn update() {
+    let mut writer = Writer::new();
+    writer.insert(1, 10);
+    writer.publish();
+
+    writer.update(1, 11);
+    writer.update(2, 20);
+
+    assert_converges(&mut writer, &[(1, 11), (2, 20)]);
+}
+
+#[test]
+fn remove_with() {
+    let mut writer = Writer::new();
+    writer.extend([(1, 10), (2, 20), (3, 30)]);
+    writer.publish();
+
+    let hash = writer.hasher().hash_one(2);
+    writer.remove_with(hash, |&k| k == 2);
+    // nothing matches, so nothing is removed
+    writer.remove_with(hash, |&k| k == 3);
+
+    assert_converges(&mut writer, &[(1, 10), (3, 30)]);
+}
+
+#[test]
+fn insert_with_eviction() {
+    let mut writer = Writer::with_bounded_capacity(2);
+    writer.insert_with_eviction(1, 10, |_| unreachable!());
+    writer.insert_with_eviction(2, 20, |_| unreachable!());
+    writer.publish();
+
+    // evict the smallest key
+    writer.insert_with_eviction(3, 30, |iter| iter.map(|(&k, _)| k).min());
+    // the key is already in the map, so nothing is evicted
+    writer.insert_with_eviction(3, 31, |_| unreachable!());
+
+    assert_converges(&mut writer, &[(2, 20), (3, 31)]);
+}
+
+#[test]
+fn insert_with_eviction_without_victim() {
+    let mut writer = Writer::with_bounded_capacity(1);
+    writer.insert_with_eviction(1, 10, |_| unreachable!());
+    writer.insert_with_eviction(2, 20, |_| None);
+
+    // nothing was evicted, so the map grows past its bound
+    assert_converges(&mut writer, &[(1, 10), (2, 20)]);
+}
+
+#[test]
+fn get_local() {
+    let mut writer = Writer::new();
+    let mut reader = writer.reader();
+    writer.insert(1, 10);
+
+    {
+        // the reader is still in the write table after the publish, so writes must not wait for it
+        let _guard = reader.load();
+        writer.publish();
+        writer.insert(2, 20);
+    }
+
+    // writes are only recorded, so the write table doesn't see them yet
+    assert_eq!(writer.get(&2), None);
+
+    assert_eq!(writer.get_local(&2), Some(&20));
+    assert_eq!(writer.get(&1), Some(&10));
+    assert_eq!(entries(&mut reader), [(1, 10)]);
+}
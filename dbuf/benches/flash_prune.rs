@@ -0,0 +1,55 @@
+//! Compares how long swaps take with [`FlashStrategy`] when readers are created and dropped between
+//! swaps, for a few different prune intervals
+//!
+//! run with `cargo bench -p dbuf --features std,triomphe --bench flash_prune`
+
+use std::{hint::black_box, time::Instant};
+
+use dbuf::{
+    raw::{DoubleBufferData, Writer},
+    strategy::{flash_park_token::ThreadParkToken, flashmap::FlashStrategy},
+};
+
+const LIVE_READERS: usize = 64;
+const CHURN: usize = 16;
+const SWAPS: u32 = 10_000;
+const ROUNDS: usize = 5;
+
+fn bench(prune_interval: usize) -> f64 {
+    let mut data = DoubleBufferData::new(
+        0u64,
+        0u64,
+        FlashStrategy::<ThreadParkToken>::new_blocking().with_prune_interval(prune_interval),
+    );
+    let mut writer = Writer::new(&mut data);
+    let _live = (0..LIVE_READERS)
+        .map(|_| writer.reader())
+        .collect::<Vec<_>>();
+
+    let mut best = f64::INFINITY;
+
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+
+        for _ in 0..SWAPS {
+            // readers which come and go between swaps
+            let churn = (0..CHURN).map(|_| writer.reader()).collect::<Vec<_>>();
+            drop(black_box(churn));
+
+            *writer.get_mut() += 1;
+            writer.swap();
+        }
+
+        let elapsed = start.elapsed().as_secs_f64() * 1e9 / f64::from(SWAPS);
+        best = best.min(elapsed);
+    }
+
+    best
+}
+
+fn main() {
+    for prune_interval in [1, 2, dbuf::strategy::flashmap::DEFAULT_PRUNE_INTERVAL, 16] {
+        let ns = bench(prune_interval);
+        println!("flash_prune/interval={prune_interval:<3} time: {ns:>10.1} ns/swap");
+    }
+}
@@ -0,0 +1,63 @@
+//! Compares how long swaps take with [`SpscStrategy`] and [`FlashStrategy`] when there is a
+//! single reader, which is continuously reading on another thread
+//!
+//! run with `cargo bench -p dbuf --features std,triomphe --bench spsc`
+
+use std::{
+    hint::black_box,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use dbuf::{
+    interface::BlockingStrategy,
+    raw::{DoubleBufferData, Writer},
+    strategy::{flash_park_token::ThreadParkToken, flashmap::FlashStrategy, spsc::SpscStrategy},
+};
+
+const SWAPS: u32 = 10_000;
+const ROUNDS: usize = 5;
+
+fn bench<S: BlockingStrategy<SwapError: core::fmt::Debug> + Send + Sync>(strategy: S) -> f64
+where
+    S::ReaderId: Send,
+{
+    let mut data = DoubleBufferData::new(0u64, 0u64, strategy);
+    let mut writer = Writer::new(&mut data);
+    let mut reader = writer.reader();
+    let done = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            while !done.load(Ordering::Relaxed) {
+                black_box(*reader.read());
+            }
+        });
+
+        let mut best = f64::INFINITY;
+
+        for _ in 0..ROUNDS {
+            let start = Instant::now();
+
+            for _ in 0..SWAPS {
+                *writer.get_mut() += 1;
+                writer.swap();
+            }
+
+            let elapsed = start.elapsed().as_secs_f64() * 1e9 / f64::from(SWAPS);
+            best = best.min(elapsed);
+        }
+
+        done.store(true, Ordering::Relaxed);
+
+        best
+    })
+}
+
+fn main() {
+    let ns = bench(SpscStrategy::<ThreadParkToken>::new_blocking());
+    println!("spsc/spsc  time: {ns:>10.1} ns/swap");
+
+    let ns = bench(FlashStrategy::<ThreadParkToken>::new_blocking());
+    println!("spsc/flash time: {ns:>10.1} ns/swap");
+}
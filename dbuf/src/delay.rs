@@ -6,6 +6,22 @@
 //! enable writes to be wait-free for the most part, since all readers
 //! will have finished their reads before the next batch is written.
 //!
+//! # One swap at a time
+//!
+//! A [`DelayWriter`] holds at most one in progress swap, and starting a swap while one
+//! is in progress is a no-op. Swaps can't be pipelined, for two reasons:
+//!
+//! * there are only two buffers, and the writer can't modify the write buffer until the
+//!   in progress swap is finished. So a second swap would publish the buffer readers saw before
+//!   the first swap, and readers would go back to an older state.
+//! * strategies that track residual readers by the parity of the swap state (like
+//!   [`FlashStrategy`](crate::strategy::flashmap::FlashStrategy)) can't tell apart a reader
+//!   which started reading two swaps ago from one that started reading just now. So a reader
+//!   may be counted as a residual reader twice, and the second swap never finishes.
+//!
+//! Pipelining swaps would need a third buffer, so the writer has somewhere to write while
+//! the readers are draining out of the other two.
+//!
 //! # Worked Example
 //!
 //! Here is the worked example from the crate level docs adapted for [`DelayWriter`]
@@ -89,12 +105,35 @@ impl<P: DoubleBufferWriterPointer> From<raw::Writer<P>> for DelayWriter<P> {
     }
 }
 
+impl<P: DoubleBufferWriterPointer> Debug for DelayWriter<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DelayWriter")
+            .field("writer", &self.writer)
+            .field("has_swap", &self.has_swap())
+            .finish()
+    }
+}
+
 impl<P: DoubleBufferWriterPointer> DelayWriter<P> {
     /// Construct a new delay writer
     pub const fn from_writer(writer: raw::Writer<P>) -> Self {
         Self { writer, swap: None }
     }
 
+    /// Create a new reader that points to the same buffers as this writer
+    ///
+    /// This may be called while a swap is in progress. Until the swap is finished, the new
+    /// reader reads either buffer, depending on the strategy:
+    ///
+    /// * it already reads the buffer that is being published, and the swap doesn't wait on it
+    /// * or it reads the old buffer, and the swap waits for it's reads to finish like any other reader
+    ///
+    /// Either way, once the swap is finished the reader sees the published buffer, and it's
+    /// tracked by later swaps just like a reader that was created before the swap.
+    pub fn reader(&self) -> raw::Reader<P::Reader> {
+        self.writer.reader()
+    }
+
     /// Try to start a new swap
     ///
     /// If there is already an ongoing swap, this is a no-op
@@ -128,6 +167,23 @@ impl<P: DoubleBufferWriterPointer> DelayWriter<P> {
         self.try_start_swap().expect("start swap must not fail")
     }
 
+    /// Start a swap, and return a guard which finishes it when dropped
+    ///
+    /// If there is already an ongoing swap, then the guard finishes that swap instead
+    ///
+    /// If the guard is leaked, then the swap stays in progress, and will be finished
+    /// by the next call to [`Self::finish_swap`] or [`Self::afinish_swap`]
+    ///
+    /// If there the strategy fails to swap, then this function panics
+    pub fn swap_scope(&mut self) -> SwapGuard<'_, P>
+    where
+        P::Strategy: BlockingStrategy,
+        SwapError<P::Strategy>: Debug,
+    {
+        self.start_swap();
+        SwapGuard { writer: self }
+    }
+
     /// Finish an ongoing swap, and return a reference to the underlying writer
     ///
     /// If there is no ongoing swap, then this is a no-op
@@ -143,9 +199,41 @@ impl<P: DoubleBufferWriterPointer> DelayWriter<P> {
         &mut self.writer
     }
 
+    /// Cancel an ongoing swap, so that the read buffer goes back to what it was before
+    /// [`Self::start_swap`] was called
+    ///
+    /// Strategies can't undo a swap, so this finishes the ongoing swap and then immediately
+    /// swaps back. This means that this may block on readers *twice*, once for each swap.
+    /// And readers may briefly observe the write buffer while this is running.
+    ///
+    /// If there is no ongoing swap, then this is a no-op
+    ///
+    /// If there the strategy fails to swap back, then this function panics
+    ///
+    /// Returns true if a swap was canceled, and false if there was no ongoing swap
+    pub fn cancel_swap(&mut self) -> bool
+    where
+        P::Strategy: BlockingStrategy,
+        SwapError<P::Strategy>: Debug,
+    {
+        let Some(swap) = self.swap.take() else {
+            return false;
+        };
+
+        // SAFETY: this swap is the latest swap
+        unsafe { self.writer.finish_swap(swap) };
+        self.writer.swap();
+
+        true
+    }
+
     /// Finish an ongoing swap, and return a reference to the underlying writer
     ///
     /// If there is no ongoing swap, then this is a no-op
+    ///
+    /// This is cancellation safe. If the future is dropped before it completes, then the swap
+    /// stays in progress, and the next call to [`Self::afinish_swap`] or [`Self::finish_swap`]
+    /// resumes it, and registers the new waker with the strategy.
     pub async fn afinish_swap(&mut self) -> &mut raw::Writer<P>
     where
         P::Strategy: AsyncStrategy,
@@ -182,6 +270,19 @@ impl<P: DoubleBufferWriterPointer> DelayWriter<P> {
         }
     }
 
+    /// check if the writer is not in the middle of a swap, without an exclusive reference
+    ///
+    /// if there is no in progress swap, then return `Some(true)`, otherwise
+    /// see [`raw::Writer::peek_swap_finished`]
+    #[inline]
+    pub fn peek_swap_finished(&self) -> Option<bool> {
+        if self.swap.is_some() {
+            self.writer.peek_swap_finished()
+        } else {
+            Some(true)
+        }
+    }
+
     /// check if there is an in progress swap
     #[inline]
     pub const fn has_swap(&self) -> bool {
@@ -217,7 +318,7 @@ impl<P: DoubleBufferWriterPointer> DelayWriter<P> {
     }
 
     /// get the underlying writer, returns None if there is an ongoing swap
-    pub fn get_writer_mut(&mut self) -> Option<&mut raw::Writer<P>> {
+    pub const fn get_writer_mut(&mut self) -> Option<&mut raw::Writer<P>> {
         match self.swap {
             Some(_) => None,
             None => Some(&mut self.writer),
@@ -232,3 +333,52 @@ impl<P: DoubleBufferWriterPointer> ops::Deref for DelayWriter<P> {
         &self.writer
     }
 }
+
+/// A guard for an in progress swap, returned by [`DelayWriter::swap_scope`]
+///
+/// This finishes the swap when dropped
+pub struct SwapGuard<'a, P: DoubleBufferWriterPointer>
+where
+    P::Strategy: BlockingStrategy,
+{
+    writer: &'a mut DelayWriter<P>,
+}
+
+impl<'a, P: DoubleBufferWriterPointer> SwapGuard<'a, P>
+where
+    P::Strategy: BlockingStrategy,
+{
+    /// check if the swap has finished, without blocking
+    #[inline]
+    pub fn is_swap_finished(&mut self) -> bool {
+        self.writer.is_swap_finished()
+    }
+
+    /// Finish the swap, and return a reference to the underlying writer
+    pub fn finish(self) -> &'a mut raw::Writer<P> {
+        let this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used again, and it's drop glue is suppressed
+        let writer = unsafe { core::ptr::read(&this.writer) };
+        writer.finish_swap()
+    }
+}
+
+impl<P: DoubleBufferWriterPointer> ops::Deref for SwapGuard<'_, P>
+where
+    P::Strategy: BlockingStrategy,
+{
+    type Target = raw::Writer<P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.writer.writer
+    }
+}
+
+impl<P: DoubleBufferWriterPointer> Drop for SwapGuard<'_, P>
+where
+    P::Strategy: BlockingStrategy,
+{
+    fn drop(&mut self) {
+        self.writer.finish_swap();
+    }
+}
@@ -12,9 +12,21 @@ mod triomphe;
 #[cfg(feature = "alloc")]
 mod std_arc;
 
+#[cfg(feature = "alloc")]
+pub use std_arc::{StrongArc, StrongReader};
+
 #[cfg(feature = "alloc")]
 mod std_rc;
 
+#[cfg(feature = "split-arc")]
+mod split_arc;
+
+#[cfg(feature = "std")]
+mod local;
+
+#[cfg(feature = "std")]
+pub use local::{LocalBuffer, LocalPtr};
+
 // SAFETY: &mut is exclusive, and it is undefined behavior for it to alias with any other pointer
 // &mut _ -> &_ cast preserves which value it points to
 unsafe impl<'a, T, S: Strategy, Extras: ?Sized> IntoDoubleBufferWriterPointer
@@ -0,0 +1,189 @@
+use core::{
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+};
+use std::thread::LocalKey;
+
+use crate::{
+    interface::{DoubleBufferReaderPointer, DoubleBufferWriterPointer, Strategy},
+    raw::{DoubleBufferData, Writer},
+};
+
+/// A [`DoubleBufferData`] which is stored in a thread local, see [`LocalPtr`]
+pub struct LocalBuffer<T, S, Extras = ()> {
+    data: UnsafeCell<DoubleBufferData<T, S, Extras>>,
+    has_writer: Cell<bool>,
+    pointers: Cell<usize>,
+}
+
+/// A pointer to a [`LocalBuffer`] stored in a thread local
+///
+/// This is a plain reference into the thread local, so upgrading it to access the
+/// buffers (i.e. reading) just borrows the pointer, and doesn't touch any reference counts.
+/// The pointers are counted with a non-atomic counter when they are created or dropped,
+/// so cloning a reader is cheap too. This makes it a good fit for tight single-threaded read loops.
+///
+/// Pointers may not be sent to other threads, so all the readers stay on the thread that owns the
+/// buffers. If the thread local is destroyed while a pointer is still alive (for example, because it
+/// was stored in another thread local with a later destructor), then the process is aborted.
+///
+/// ```rust
+/// use dbuf::{LocalBuffer, LocalPtr};
+/// use dbuf::raw::{DoubleBufferData, Writer};
+/// use dbuf::strategy::simple::SimpleStrategy;
+///
+/// thread_local! {
+///     static BUFFERS: LocalBuffer<i32, SimpleStrategy> =
+///         LocalBuffer::new(DoubleBufferData::new(0, 1, SimpleStrategy::new()));
+/// }
+///
+/// let mut writer: Writer<LocalPtr<i32, SimpleStrategy>> = LocalPtr::writer(&BUFFERS).unwrap();
+/// let mut reader = writer.reader();
+///
+/// // there may be only one writer for the buffers
+/// assert!(LocalPtr::writer(&BUFFERS).is_none());
+///
+/// *writer.get_mut() = 10;
+/// writer.swap();
+/// assert_eq!(*reader.read(), 10);
+/// ```
+pub struct LocalPtr<T: 'static, S: 'static, Extras: 'static = ()> {
+    buffer: &'static LocalBuffer<T, S, Extras>,
+    // the thread local may not be accessed from other threads
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T, S, Extras> LocalBuffer<T, S, Extras> {
+    /// Create a new buffer to be stored in a thread local
+    pub const fn new(data: DoubleBufferData<T, S, Extras>) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+            has_writer: Cell::new(false),
+            pointers: Cell::new(0),
+        }
+    }
+}
+
+impl<T, S, Extras> Drop for LocalBuffer<T, S, Extras> {
+    fn drop(&mut self) {
+        // the pointers only borrow the thread local, so if any of them are still alive
+        // they would dangle. There is no way to recover from that, so just abort
+        if self.pointers.get() != 0 {
+            std::process::abort()
+        }
+    }
+}
+
+impl<T, S: Strategy, Extras> LocalPtr<T, S, Extras> {
+    /// Create the writer for the buffers in `key`
+    ///
+    /// Returns `None` if a writer was already created for these buffers on this thread,
+    /// even if that writer was dropped since then (its readers may still be alive)
+    ///
+    /// # Panics
+    ///
+    /// If the thread local is being destroyed or was already destroyed, see [`LocalKey::with`]
+    pub fn writer(key: &'static LocalKey<LocalBuffer<T, S, Extras>>) -> Option<Writer<Self>> {
+        key.with(|buffer| {
+            if buffer.has_writer.replace(true) {
+                return None;
+            }
+
+            // SAFETY: the thread local lives until the thread exits, and pointers can't leave this thread.
+            // All pointers are dropped before the thread local is destroyed, since `LocalBuffer::drop`
+            // aborts otherwise. So the buffer outlives all pointers created from it.
+            let buffer = unsafe { &*(buffer as *const LocalBuffer<T, S, Extras>) };
+
+            // SAFETY: this is the first writer for this buffer, and pointers are only created from this writer.
+            // So there are no other references to the data and this `&mut` doesn't alias anything.
+            let data = unsafe { &mut *buffer.data.get() };
+            // SAFETY: the writer id is dropped before the pointer, and the pointer keeps the strategy alive
+            let id = unsafe { data.strategy.create_writer_id() };
+
+            // SAFETY: the id was just created for this strategy, and no other writer
+            // may ever be created for these buffers
+            Some(unsafe { Writer::from_raw_parts(id, Self::new(buffer)) })
+        })
+    }
+}
+
+impl<T, S, Extras> LocalPtr<T, S, Extras> {
+    fn new(buffer: &'static LocalBuffer<T, S, Extras>) -> Self {
+        // each pointer takes up memory, so this can't overflow before running out of memory
+        // but if it would, we can't recover from that
+        let Some(pointers) = buffer.pointers.get().checked_add(1) else {
+            std::process::abort()
+        };
+        buffer.pointers.set(pointers);
+
+        Self {
+            buffer,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl<T, S, Extras> Clone for LocalPtr<T, S, Extras> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.buffer)
+    }
+}
+
+impl<T, S, Extras> Drop for LocalPtr<T, S, Extras> {
+    #[inline]
+    fn drop(&mut self) {
+        // this pointer is counted, so the count can't be zero
+        #[allow(clippy::arithmetic_side_effects)]
+        self.buffer.pointers.set(self.buffer.pointers.get() - 1);
+    }
+}
+
+impl<T, S, Extras> core::ops::Deref for LocalPtr<T, S, Extras> {
+    type Target = DoubleBufferData<T, S, Extras>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the only `&mut` to the data was used to create the writer id before
+        // any pointers were created, so the data is only shared from now on
+        unsafe { &*self.buffer.data.get() }
+    }
+}
+
+// SAFETY: Self::deref does not change which [`DoubleBufferData`] it points to
+// Self::reader -> try_reader will return a pointer to the same buffer
+unsafe impl<T, S: Strategy, Extras> DoubleBufferWriterPointer for LocalPtr<T, S, Extras> {
+    type Reader = Self;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+
+    #[inline]
+    fn reader(&self) -> Self::Reader {
+        self.clone()
+    }
+}
+
+// SAFETY: as long as the only usage of this type is through try_writer;
+// * multiple calls to try_writer must yield the same writer
+//   try_writer always returns self
+// * once try_writer returns [`Err`], it must never return [`Ok`] again
+//   try_writer never returns [`Err`]
+unsafe impl<T, S: Strategy, Extras> DoubleBufferReaderPointer for LocalPtr<T, S, Extras> {
+    type Writer = Self;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+    type UpgradeError = core::convert::Infallible;
+    type MaybeBorrowed<'a>
+        = &'a Self
+    where
+        Self: 'a;
+
+    #[inline]
+    fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError> {
+        Ok(self)
+    }
+}
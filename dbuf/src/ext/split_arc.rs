@@ -0,0 +1,109 @@
+use crate::{
+    interface::{
+        DoubleBufferReaderPointer, DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer,
+        Strategy,
+    },
+    raw::DoubleBufferData,
+};
+
+use split_arc::{SplitArc, UniqueSplitArc};
+
+#[cfg(test)]
+mod test;
+
+/// The reader half of a [`SplitArc`]
+///
+/// A [`SplitArc`] can only be split once, so only one of these may be live at a time.
+/// Cloning it (and calling [`Writer::reader`](crate::raw::Writer::reader) while
+/// another reader half is alive) produces a dangling reader, which can never be upgraded.
+pub struct SplitArcReader<T> {
+    ptr: Option<SplitArc<T>>,
+}
+
+impl<T> Clone for SplitArcReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { ptr: None }
+    }
+}
+
+// SAFETY: UniqueSplitArc is guaranteed to not be aliased
+// and will point to the same value as the SplitArc created from UniqueSplitArc::shareable
+unsafe impl<T, S: Strategy, Extras> IntoDoubleBufferWriterPointer
+    for UniqueSplitArc<DoubleBufferData<T, S, Extras>>
+{
+    type Writer = SplitArc<DoubleBufferData<T, S, Extras>>;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+
+    fn into_writer(self) -> Self::Writer {
+        self.shareable()
+    }
+}
+
+// SAFETY: Self::deref does not change which [`DoubleBufferData`] it points to
+// Self::reader -> try_reader will return the split half, which points to the same value
+// and if the split failed then the reader has no pointer, which Self::is_dangling_reader reports
+unsafe impl<T, S: Strategy, Extras> DoubleBufferWriterPointer
+    for SplitArc<DoubleBufferData<T, S, Extras>>
+{
+    type Reader = SplitArcReader<DoubleBufferData<T, S, Extras>>;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+
+    #[inline]
+    fn reader(&self) -> Self::Reader {
+        SplitArcReader {
+            ptr: self.try_split(),
+        }
+    }
+
+    #[inline]
+    fn is_dangling_reader(reader: &Self::Reader) -> bool {
+        // the split fails if another reader half is still alive
+        reader.ptr.is_none()
+    }
+}
+
+// SAFETY: as long as the only usage of this type is through try_writer;
+// * multiple calls to try_writer must yield the same writer
+//  try_writer always returns the split half, which never changes
+// * once try_writer returns [`Err`], it must never return [`Ok`] again
+//  the split half is never split again, so once the writer half is dropped
+//  SplitArc::is_split will always return false
+unsafe impl<T, S: Strategy, Extras> DoubleBufferReaderPointer
+    for SplitArcReader<DoubleBufferData<T, S, Extras>>
+{
+    type Writer = SplitArc<DoubleBufferData<T, S, Extras>>;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+
+    type UpgradeError = SplitArcUpgradeError;
+    type MaybeBorrowed<'a>
+        = &'a Self::Writer
+    where
+        Self: 'a;
+
+    #[inline]
+    fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError> {
+        match self.ptr {
+            Some(ref ptr) if ptr.is_split() => Ok(ptr),
+            _ => Err(SplitArcUpgradeError),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SplitArcUpgradeError;
+
+impl core::fmt::Debug for SplitArcUpgradeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Cannot upgrade a dangling split arc")
+    }
+}
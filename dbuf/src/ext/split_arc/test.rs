@@ -0,0 +1,73 @@
+use split_arc::{SplitArc, UniqueSplitArc};
+
+use super::SplitArcUpgradeError;
+
+use crate::{
+    raw::{DoubleBufferData, Writer},
+    strategy::{flash_park_token::ThreadParkToken, hazad_flash::HazardFlashStrategy},
+};
+
+type Buffer = DoubleBufferData<i32, HazardFlashStrategy<ThreadParkToken>>;
+
+fn new_writer() -> Writer<SplitArc<Buffer>> {
+    Writer::new(UniqueSplitArc::new(DoubleBufferData::new(
+        0,
+        1,
+        HazardFlashStrategy::new_blocking(),
+    )))
+}
+
+#[test]
+fn smoke() {
+    let mut writer = new_writer();
+    let mut reader = writer.reader();
+
+    assert_eq!(*reader.read(), 0);
+    writer.swap();
+    assert_eq!(*reader.read(), 1);
+}
+
+#[test]
+fn read_after_writer_dropped() {
+    let writer = new_writer();
+    let mut reader = writer.reader();
+
+    drop(writer);
+
+    // the reader's half is no longer split, and it's never split again
+    assert_eq!(reader.try_read().err(), Some(SplitArcUpgradeError));
+    assert_eq!(reader.try_read().err(), Some(SplitArcUpgradeError));
+}
+
+#[test]
+fn clone_is_dangling() {
+    let mut writer = new_writer();
+    let mut reader = writer.reader();
+    let mut clone = reader.clone();
+
+    assert_eq!(clone.try_read().err(), Some(SplitArcUpgradeError));
+
+    // the clone doesn't prevent the original reader from reading
+    writer.swap();
+    assert_eq!(*reader.read(), 1);
+    assert_eq!(clone.try_read().err(), Some(SplitArcUpgradeError));
+
+    // the clone never takes over the split, even after the original reader is dropped
+    drop(reader);
+    assert_eq!(clone.try_read().err(), Some(SplitArcUpgradeError));
+}
+
+#[test]
+fn reader_while_split() {
+    let writer = new_writer();
+    let reader = writer.reader();
+
+    // there is already a reader half, so the second reader is dangling
+    let mut second = writer.reader();
+    assert_eq!(second.try_read().err(), Some(SplitArcUpgradeError));
+
+    // once the reader half is dropped, the writer can split again
+    drop(reader);
+    let mut third = writer.reader();
+    assert_eq!(*third.read(), 0);
+}
@@ -1,9 +1,10 @@
 use crate::{
     interface::{
-        DoubleBufferReaderPointer, DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer,
+        upgrade_and_create_reader_id, DanglingReaderPointer, DoubleBufferReaderPointer,
+        DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer, IntoInnerWriterPointer, ReaderId,
         Strategy,
     },
-    raw::DoubleBufferData,
+    raw::{DoubleBufferData, Reader},
 };
 
 use alloc::sync::{Arc, Weak};
@@ -67,6 +68,147 @@ unsafe impl<T, S: Strategy, Extras: ?Sized> DoubleBufferReaderPointer
     fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError> {
         self.upgrade().ok_or(ArcUpgradeError)
     }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &ReaderId<S>) -> ReaderId<S> {
+        // strategies which don't need access to the strategy can skip the upgrade
+        // SAFETY: the reader id is valid (guaranteed by caller)
+        match unsafe { S::create_detached_reader_id(reader) } {
+            Some(id) => id,
+            // SAFETY: guaranteed by caller
+            None => unsafe { upgrade_and_create_reader_id(self, reader) },
+        }
+    }
+}
+
+// SAFETY: `Weak::new` never points to an allocation, so it can never be upgraded
+unsafe impl<T, S: Strategy, Extras> DanglingReaderPointer for Weak<DoubleBufferData<T, S, Extras>> {
+    #[inline]
+    fn dangling() -> Self {
+        Weak::new()
+    }
+}
+
+// SAFETY: `Arc::try_unwrap` only succeeds if this is the only strong pointer, and once
+// it succeeded, the readers (which are `Weak`s) can never be upgraded again
+unsafe impl<T, S: Strategy, Extras> IntoInnerWriterPointer for Arc<DoubleBufferData<T, S, Extras>> {
+    type Unique<U> = ArcBox<DoubleBufferData<U, S, Extras>>;
+
+    #[inline]
+    fn try_into_inner(self) -> Result<DoubleBufferData<T, S, Extras>, Self> {
+        // readers hold weak pointers, and their ids may point into the strategy
+        // so the strategy can't be moved out while there are any readers
+        if Arc::weak_count(&self) != 0 {
+            return Err(self);
+        }
+
+        Arc::try_unwrap(self)
+    }
+
+    #[inline]
+    fn new_unique<U>(data: DoubleBufferData<U, S, Extras>) -> Self::Unique<U> {
+        ArcBox::new(data)
+    }
+}
+
+/// A strong pointer to the buffers, which keeps them alive even after the writer is dropped
+///
+/// see [`Reader::into_strong`] for details
+pub struct StrongArc<T, S, Extras: ?Sized = ()>(Arc<DoubleBufferData<T, S, Extras>>);
+
+/// A reader which keeps the buffers alive, so reads never fail, see [`Reader::into_strong`]
+pub type StrongReader<T, S, Extras = ()> = Reader<StrongArc<T, S, Extras>>;
+
+impl<T, S: Strategy, Extras: ?Sized> Reader<Weak<DoubleBufferData<T, S, Extras>>> {
+    /// Convert this reader into one which holds a strong reference to the buffers
+    ///
+    /// The strong reader keeps the buffers alive even after the writer is dropped, so its
+    /// reads never fail. Returns the reader back if the writer was already dropped.
+    ///
+    /// Once the writer is dropped, there are no more swaps. So the strong reader only ever sees
+    /// the buffer that was published last, and the buffers are freed once all strong readers are
+    /// dropped.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    /// use dbuf::rc_box::ArcBox;
+    ///
+    /// let mut writer: Writer<Arc<DoubleBufferData<i32, SimpleStrategy>>> =
+    ///     Writer::new(ArcBox::new(DoubleBufferData::new(0, 1, SimpleStrategy::new())));
+    /// let reader = writer.reader();
+    ///
+    /// *writer.get_mut() = 10;
+    /// writer.swap();
+    ///
+    /// let mut reader = reader.into_strong().unwrap();
+    /// drop(writer);
+    /// assert_eq!(*reader.read(), 10);
+    /// ```
+    pub fn into_strong(self) -> Result<StrongReader<T, S, Extras>, Self> {
+        let Some(arc) = self.pointer().upgrade() else {
+            return Err(self);
+        };
+
+        let (id, _) = self.into_raw_parts();
+        // SAFETY: the reader id is valid for the strategy behind the weak pointer,
+        // and the arc was upgraded from it, so it points to the same strategy
+        Ok(unsafe { Reader::from_raw_parts(id, StrongArc(arc)) })
+    }
+}
+
+impl<T, S, Extras: ?Sized> Clone for StrongArc<T, S, Extras> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, S, Extras: ?Sized> core::ops::Deref for StrongArc<T, S, Extras> {
+    type Target = DoubleBufferData<T, S, Extras>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// SAFETY: Self::deref does not change which [`DoubleBufferData`] it points to
+// Self::reader -> try_reader will return a pointer to the same buffer
+unsafe impl<T, S: Strategy, Extras: ?Sized> DoubleBufferWriterPointer for StrongArc<T, S, Extras> {
+    type Reader = Self;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+
+    #[inline]
+    fn reader(&self) -> Self::Reader {
+        self.clone()
+    }
+}
+
+// SAFETY: as long as the only usage of this type is through try_writer;
+// * multiple calls to try_writer must yield the same writer
+//   try_writer always returns self
+// * once try_writer returns [`Err`], it must never return [`Ok`] again
+//   try_writer never returns [`Err`]
+unsafe impl<T, S: Strategy, Extras: ?Sized> DoubleBufferReaderPointer for StrongArc<T, S, Extras> {
+    type Writer = Self;
+
+    type Strategy = S;
+    type Buffer = T;
+    type Extras = Extras;
+    type UpgradeError = core::convert::Infallible;
+    type MaybeBorrowed<'a>
+        = &'a Self
+    where
+        Self: 'a;
+
+    #[inline]
+    fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError> {
+        Ok(self)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
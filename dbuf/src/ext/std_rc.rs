@@ -1,6 +1,7 @@
 use crate::{
     interface::{
-        DoubleBufferReaderPointer, DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer,
+        upgrade_and_create_reader_id, DanglingReaderPointer, DoubleBufferReaderPointer,
+        DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer, IntoInnerWriterPointer, ReaderId,
         Strategy,
     },
     raw::DoubleBufferData,
@@ -67,6 +68,46 @@ unsafe impl<T, S: Strategy, Extras: ?Sized> DoubleBufferReaderPointer
     fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError> {
         self.upgrade().ok_or(RcUpgradeError)
     }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &ReaderId<S>) -> ReaderId<S> {
+        // strategies which don't need access to the strategy can skip the upgrade
+        // SAFETY: the reader id is valid (guaranteed by caller)
+        match unsafe { S::create_detached_reader_id(reader) } {
+            Some(id) => id,
+            // SAFETY: guaranteed by caller
+            None => unsafe { upgrade_and_create_reader_id(self, reader) },
+        }
+    }
+}
+
+// SAFETY: `Weak::new` never points to an allocation, so it can never be upgraded
+unsafe impl<T, S: Strategy, Extras> DanglingReaderPointer for Weak<DoubleBufferData<T, S, Extras>> {
+    #[inline]
+    fn dangling() -> Self {
+        Weak::new()
+    }
+}
+
+// SAFETY: `Rc::try_unwrap` only succeeds if this is the only strong pointer, and once
+// it succeeded, the readers (which are `Weak`s) can never be upgraded again
+unsafe impl<T, S: Strategy, Extras> IntoInnerWriterPointer for Rc<DoubleBufferData<T, S, Extras>> {
+    type Unique<U> = RcBox<DoubleBufferData<U, S, Extras>>;
+
+    #[inline]
+    fn try_into_inner(self) -> Result<DoubleBufferData<T, S, Extras>, Self> {
+        // readers hold weak pointers, and their ids may point into the strategy
+        // so the strategy can't be moved out while there are any readers
+        if Rc::weak_count(&self) != 0 {
+            return Err(self);
+        }
+
+        Rc::try_unwrap(self)
+    }
+
+    #[inline]
+    fn new_unique<U>(data: DoubleBufferData<U, S, Extras>) -> Self::Unique<U> {
+        RcBox::new(data)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -1,9 +1,9 @@
 use crate::{
     interface::{
         DoubleBufferReaderPointer, DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer,
-        Strategy,
+        IntoInnerWriterPointer, Strategy,
     },
-    raw::DoubleBufferData,
+    raw::{DoubleBufferData, Reader},
 };
 
 use triomphe::{Arc, OffsetArc, UniqueArc};
@@ -68,3 +68,35 @@ unsafe impl<T, S: Strategy, Extras> DoubleBufferReaderPointer
         Ok(self)
     }
 }
+
+// SAFETY: `Arc::try_unwrap` only succeeds if this is the only pointer to the buffers,
+// and the readers are strong pointers, so there are no readers left
+unsafe impl<T, S: Strategy, Extras> IntoInnerWriterPointer
+    for OffsetArc<DoubleBufferData<T, S, Extras>>
+{
+    type Unique<U> = UniqueArc<DoubleBufferData<U, S, Extras>>;
+
+    #[inline]
+    fn try_into_inner(self) -> Result<DoubleBufferData<T, S, Extras>, Self> {
+        Arc::try_unwrap(Arc::from_raw_offset(self)).map_err(Arc::into_raw_offset)
+    }
+
+    #[inline]
+    fn new_unique<U>(data: DoubleBufferData<U, S, Extras>) -> Self::Unique<U> {
+        UniqueArc::new(data)
+    }
+}
+
+impl<T, S: Strategy, Extras> Reader<OffsetArc<DoubleBufferData<T, S, Extras>>> {
+    /// Clone this reader, and return the strong count of the shared [`OffsetArc`] right after
+    /// the clone
+    ///
+    /// Every reader keeps the buffers alive, so all readers (and the writer) must be dropped
+    /// before the buffers are freed. Note that other threads may clone or drop readers
+    /// concurrently, so the count may be outdated as soon as it is returned.
+    pub fn clone_explicit(&self) -> (Self, usize) {
+        let reader = self.clone();
+        let count = reader.pointer().with_arc(Arc::count);
+        (reader, count)
+    }
+}
@@ -1,4 +1,4 @@
-use core::{ops, task::Context};
+use core::{borrow::Borrow, ops, task::Context};
 
 use crate::raw::MaybeBorrowed;
 
@@ -16,6 +16,16 @@ pub(crate) type SwapError<S> = <S as Strategy>::SwapError;
 /// must all point to the same [`DoubleBufferData`](crate::raw::DoubleBufferData)
 ///
 /// the writer produced by into_writer must not be aliased
+///
+/// # Pinning
+///
+/// There is no implementation for `Pin<Box<DoubleBufferData<..>>>`, since the
+/// [`Writer`](crate::raw::Writer) hands out `&mut Buffer` (see
+/// [`Writer::get_mut`](crate::raw::Writer::get_mut)), which would allow moving a pinned
+/// `!Unpin` buffer out from behind the pin. For `Unpin` buffers pinning doesn't add
+/// anything, so use one of the other pointers instead. Note that none of the pointers
+/// move the `DoubleBufferData` once the writer is created, but that isn't a pinning
+/// guarantee for the buffers themselves.
 pub unsafe trait IntoDoubleBufferWriterPointer:
     ops::DerefMut<Target = crate::raw::DoubleBufferData<Self::Buffer, Self::Strategy, Self::Extras>>
 {
@@ -38,9 +48,10 @@ pub unsafe trait IntoDoubleBufferWriterPointer:
 /// # Safety
 ///
 /// Self::deref  must not change which [`DoubleBufferData`](crate::raw::DoubleBufferData) it points to.
+/// Self::is_dangling_reader must return true if the reader pointer doesn't point to the same
+/// [`DoubleBufferData`](crate::raw::DoubleBufferData) as this pointer.
 pub unsafe trait DoubleBufferWriterPointer:
-    Clone
-    + ops::Deref<Target = crate::raw::DoubleBufferData<Self::Buffer, Self::Strategy, Self::Extras>>
+    ops::Deref<Target = crate::raw::DoubleBufferData<Self::Buffer, Self::Strategy, Self::Extras>>
 {
     type Reader: DoubleBufferReaderPointer<
         Writer = Self,
@@ -54,6 +65,18 @@ pub unsafe trait DoubleBufferWriterPointer:
     type Extras: ?Sized;
 
     fn reader(&self) -> Self::Reader;
+
+    /// Check if a reader pointer created by [`Self::reader`] is dangling, i.e. it can never
+    /// be upgraded
+    ///
+    /// Dangling readers get an invalid reader id (see [`Strategy::create_invalid_reader_id`]).
+    /// By default this returns false, which is correct for pointers where [`Self::reader`]
+    /// always points to the same buffers.
+    #[inline]
+    fn is_dangling_reader(reader: &Self::Reader) -> bool {
+        let _ = reader;
+        false
+    }
 }
 
 /// A pointer doesn't usually doesn't hold ownership over a
@@ -82,6 +105,90 @@ pub unsafe trait DoubleBufferReaderPointer: Clone {
         Self: 'a;
 
     fn try_writer(&self) -> Result<Self::MaybeBorrowed<'_>, Self::UpgradeError>;
+
+    /// Creates a reader id for this pointer from the reader id of the pointer it was cloned from
+    ///
+    /// By default this upgrades the pointer to get access to the strategy, pointers
+    /// which are expensive to upgrade may skip the upgrade if the strategy supports
+    /// [`Strategy::create_detached_reader_id`]
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid for the strategy behind this pointer, or this pointer is dangling
+    #[inline]
+    unsafe fn create_reader_id_from_reader(
+        &self,
+        reader: &ReaderId<Self::Strategy>,
+    ) -> ReaderId<Self::Strategy> {
+        // SAFETY: guaranteed by caller
+        unsafe { upgrade_and_create_reader_id(self, reader) }
+    }
+}
+
+/// A reader pointer which can be created without pointing to any buffers
+///
+/// This is used by [`Reader::new_invalid`](crate::raw::Reader::new_invalid)
+///
+/// # Safety
+///
+/// [`DoubleBufferReaderPointer::try_writer`] must always return [`Err`] for the pointer
+/// returned by [`Self::dangling`], and all it's clones
+pub unsafe trait DanglingReaderPointer: DoubleBufferReaderPointer {
+    /// Create a pointer which doesn't point to any buffers
+    fn dangling() -> Self;
+}
+
+/// A writer pointer which can take back ownership of the
+/// [`DoubleBufferData`](crate::raw::DoubleBufferData), if no one else can access it
+///
+/// This is used by [`Writer::try_map`](crate::raw::Writer::try_map)
+///
+/// # Safety
+///
+/// [`Self::try_into_inner`] may only return [`Ok`] if there are no other pointers to the
+/// [`DoubleBufferData`](crate::raw::DoubleBufferData), including reader pointers. Reader ids
+/// may point into the strategy, so the strategy must not be moved while any reader exists.
+pub unsafe trait IntoInnerWriterPointer:
+    Sized + DoubleBufferWriterPointer<Extras: Sized>
+{
+    /// The unique pointer which is used to create a writer for buffers of type `T`
+    type Unique<T>: IntoDoubleBufferWriterPointer<
+        Buffer = T,
+        Strategy = Self::Strategy,
+        Extras = Self::Extras,
+    >;
+
+    /// Take ownership of the [`DoubleBufferData`](crate::raw::DoubleBufferData), if this is
+    /// the only pointer which can access it, otherwise return the pointer back
+    #[allow(clippy::type_complexity)]
+    fn try_into_inner(
+        self,
+    ) -> Result<crate::raw::DoubleBufferData<Self::Buffer, Self::Strategy, Self::Extras>, Self>;
+
+    /// Move a [`DoubleBufferData`](crate::raw::DoubleBufferData) into a new unique pointer
+    fn new_unique<T>(
+        data: crate::raw::DoubleBufferData<T, Self::Strategy, Self::Extras>,
+    ) -> Self::Unique<T>;
+}
+
+/// Upgrade the pointer and create a reader id from the given reader id,
+/// returns an invalid reader id if the upgrade fails
+///
+/// # Safety
+///
+/// The reader id must be valid for the strategy behind this pointer, or this pointer is dangling
+pub(crate) unsafe fn upgrade_and_create_reader_id<P: DoubleBufferReaderPointer>(
+    ptr: &P,
+    reader: &ReaderId<P::Strategy>,
+) -> ReaderId<P::Strategy> {
+    match ptr.try_writer() {
+        Ok(ptr) => {
+            let ptr: &P::Writer = ptr.borrow();
+            // SAFETY: the pointer isn't dangling, so the reader id is valid (guaranteed by caller)
+            unsafe { ptr.strategy.create_reader_id_from_reader(reader) }
+        }
+        Err(_) => P::Strategy::create_invalid_reader_id(),
+    }
 }
 
 /// The synchronization strategy of the double buffer
@@ -92,6 +199,8 @@ pub unsafe trait DoubleBufferReaderPointer: Clone {
 /// between a call to [`Self::acquire_read_guard`] and [`Self::release_read_guard`]
 ///
 /// * finish_swap must not return if there is an active read
+/// * if [`Self::no_active_readers`] returns true, then there must not be any reader ids
+///   which could acquire a read guard (other than invalid reader ids)
 pub unsafe trait Strategy {
     type WriterId;
     type ReaderId;
@@ -127,11 +236,40 @@ pub unsafe trait Strategy {
     /// * If the output reader id is dropped, it must be dropped before the strategy is dropped
     unsafe fn create_reader_id_from_reader(&self, reader: &Self::ReaderId) -> Self::ReaderId;
 
+    /// Creates a valid reader id from the provided reader id, without access to the strategy
+    ///
+    /// This is only possible for strategies whose reader ids don't refer to the strategy,
+    /// all other strategies return [`None`]
+    ///
+    /// # Safety
+    ///
+    /// * The input reader id must be valid for this strategy
+    /// * If the output reader id is dropped, it must be dropped before the strategy is dropped
+    #[inline]
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        let _ = reader;
+        None
+    }
+
     /// Creates an invalid reader id
     ///
     /// This is useful if you need *some* reader id, but it won't be used by anyone
     fn create_invalid_reader_id() -> Self::ReaderId;
 
+    /// Reset the strategy to a clean state, so that it can be reused for a new writer
+    ///
+    /// This is useful to reuse a [`DoubleBufferData`](crate::raw::DoubleBufferData) after the writer
+    /// and all readers were dropped, for example in an object pool. All writer ids and reader
+    /// ids created by this strategy are invalid after this call.
+    ///
+    /// What a clean state means depends on the strategy, see the strategy's docs. Strategies
+    /// which reset their swap state go back to the initial buffers, so readers see the
+    /// first buffer passed to [`DoubleBufferData::new`](crate::raw::DoubleBufferData::new) again.
+    ///
+    /// By default this does nothing
+    #[inline]
+    fn reset(&mut self) {}
+
     // accessors
 
     /// Returns true if the number of successful calls to [`Self::try_start_swap`] is odd
@@ -181,6 +319,97 @@ pub unsafe trait Strategy {
     /// this must be the latest swap created by this strategy and writer id
     unsafe fn is_swap_finished(&self, writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool;
 
+    /// Returns the number of readers that the latest swap is still waiting on
+    ///
+    /// Returns [`None`] if the strategy doesn't track this
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    /// the swap must have been created by this strategy and this writer id
+    /// this must be the latest swap created by this strategy and writer id
+    #[inline]
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        let _ = (writer, swap);
+        None
+    }
+
+    /// Returns true if the latest swap is finished (or if no swap was started), without
+    /// access to the swap
+    ///
+    /// This can only be offered by strategies where [`Self::is_swap_finished`] is a pure check,
+    /// which doesn't need any state from the swap and doesn't modify the strategy. For example,
+    /// [`AtomicStrategy`](crate::strategy::atomic::AtomicStrategy) commits the swap in
+    /// [`Self::is_swap_finished`], and only the swap knows which buffer to swap to, so it
+    /// can't offer this.
+    ///
+    /// Returns [`None`] if the strategy can't check this, which is the default
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    #[inline]
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        let _ = writer;
+        None
+    }
+
+    /// Returns the generation of the buffer that the guard is reading, which is the number of swaps
+    /// which happened before that buffer was published
+    ///
+    /// Returns [`None`] if the strategy doesn't track this, which is the default
+    ///
+    /// The generation of the buffers seen by a single reader id must never decrease.
+    /// The generation may wrap around once it overflows (which will take hundreds of
+    /// years of continuous swapping, for a 64-bit `usize`)
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    /// The guard must have been created by this strategy and this reader id
+    /// The guard must be the latest guard created by that reader id
+    #[inline]
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        let _ = (reader, guard);
+        None
+    }
+
+    /// Returns true if there are no readers which could access either buffer
+    ///
+    /// Since new readers can only be created from the writer id or another reader,
+    /// this stays true until the writer id is used to create a new reader id
+    ///
+    /// By default, this conservatively returns false
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    #[inline]
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        let _ = writer;
+        false
+    }
+
+    /// Returns an estimate of how many reader ids exist for this strategy
+    ///
+    /// This is racy, readers may be created or dropped concurrently, so it should only be
+    /// used as a hint (for example, for backpressure). Invalid reader ids aren't counted.
+    ///
+    /// Returns [`None`] if the strategy doesn't track this, which is the default
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    #[inline]
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        let _ = writer;
+        None
+    }
+
     // reader registration
 
     /// Acquires a read guard. This ensures that the writer does not have write access to the
@@ -195,6 +424,45 @@ pub unsafe trait Strategy {
     /// The reader id must be valid
     unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard;
 
+    /// Like [`Self::acquire_read_guard`], but returns [`None`] instead of panicking if the
+    /// strategy detects that there is an unreleased [`Self::ReadGuard`] for this reader id
+    ///
+    /// If this returns [`None`], then no guard was acquired. By default this calls
+    /// [`Self::acquire_read_guard`]
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    #[inline]
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: guaranteed by caller
+        Some(unsafe { self.acquire_read_guard(reader) })
+    }
+
+    /// Like [`Self::acquire_read_guard`], but returns [`None`] if acquiring the guard
+    /// would block for longer than `timeout`
+    ///
+    /// If this returns [`None`], then no guard was acquired. By default this calls
+    /// [`Self::acquire_read_guard`], which is correct for strategies where acquiring
+    /// a read guard never blocks
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    #[inline]
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        let _ = timeout;
+        // SAFETY: guaranteed by caller
+        Some(unsafe { self.acquire_read_guard(reader) })
+    }
+
     /// Release a read guard. This allows the writer to write the buffer that this guard protects
     /// if there are no other active reads to that buffer
     ///
@@ -248,6 +516,95 @@ pub unsafe trait BlockingStrategy: Strategy {
     unsafe fn finish_swap(&self, writer: &mut Self::WriterId, swap: Self::Swap);
 }
 
+/// A strategy which can lock out all readers
+///
+/// # Safety
+///
+/// If [`Self::try_lock_readers`] returns true, then there must not be any active reads
+/// and [`Strategy::acquire_read_guard`] must not return until [`Self::unlock_readers`] is called
+pub unsafe trait ReaderLock: Strategy {
+    /// Try to lock out all readers, fails if there are any active reads
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    /// the readers must not already be locked
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool;
+
+    /// Unlock the readers
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    /// the readers must be locked
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId);
+}
+
+/// One of the two buffers, relative to the writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BufferSide {
+    /// The buffer that readers currently see
+    Read,
+    /// The buffer that the writer currently writes to
+    Write,
+}
+
+/// A strategy which tracks which buffer each active reader is in
+///
+/// # Safety
+///
+/// If [`Self::is_buffer_empty`] returns true, then there must not be any active reads
+/// of the given buffer at the time of the call
+pub unsafe trait BufferOccupancy: Strategy {
+    /// Returns true if there are no active reads of the given buffer
+    ///
+    /// # Safety
+    ///
+    /// the writer id must be valid
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool;
+}
+
+/// A strategy which allows readers to wait for the writer to swap the buffers
+///
+/// A reader is woken once there was a successful call to [`Strategy::try_start_swap`] after
+/// the reader's last call to [`Strategy::acquire_read_guard`] (or the reader's creation, if it
+/// never acquired a guard)
+pub trait NotifyReaders: Strategy {
+    /// Block until the buffers are swapped
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    unsafe fn wait_for_swap(&self, reader: &mut Self::ReaderId);
+
+    /// Block until the buffers are swapped, or the timeout elapses
+    ///
+    /// Returns true if the buffers were swapped
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    unsafe fn wait_for_swap_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> bool;
+
+    /// Poll until the buffers are swapped
+    ///
+    /// If the buffers weren't swapped yet, then the waker in `ctx` is woken
+    /// after the next swap, or when the strategy is dropped
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid
+    unsafe fn poll_for_swap(
+        &self,
+        reader: &mut Self::ReaderId,
+        ctx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<()>;
+}
+
 pub(crate) fn create_invalid_reader_id<S: Strategy>() -> S::ReaderId {
     S::create_invalid_reader_id()
 }
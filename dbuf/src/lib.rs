@@ -39,6 +39,9 @@
 //! swap right after a batch is complete and complete the swap much later
 //! when you write the next batch.
 //!
+//! If you don't need to pick the pointer and strategy types yourself, [`simple_api`] provides
+//! a ready to use writer/reader pair.
+//!
 //! ## Supported Pointer Types
 //!
 //! The types here are listed as `shared pointer`/`unique pointer`
@@ -64,6 +67,8 @@
 //! all outstanding reads are complete. (does not require all readers to be dropped).
 //!
 //! Expensive to copy [`raw::Reader`] in a tight loop, since it requires an atomic increment/decrement.
+//! A reader can be converted into a [`StrongReader`], which keeps the buffers alive after the
+//! writer is dropped, see [`raw::Reader::into_strong`].
 //!
 //! But it allows the data to be destroyed as soon as the writer is dropped and
 //! all outstanding reads are complete. (does not require all readers to be dropped).
@@ -72,6 +77,16 @@
 //!
 //! Cheap to copy [`raw::Reader`], but all readers must be dropped before the buffers are freed.
 //!
+//! * [`split_arc::SplitArc`]/[`split_arc::UniqueSplitArc`]
+//!
+//! No reference counting, but only a single live [`raw::Reader`] may exist at a time.
+//! Any other readers (i.e. clones of the reader) are dangling and will fail to read.
+//!
+//! * [`LocalPtr`]/[`LocalBuffer`]
+//!
+//! No atomic reference counting, the buffers live in a thread local and all readers must stay
+//! on that thread.
+//!
 //! ### Custom Pointer types
 //!
 //! But you can implement the triple trait combo of [`interface::IntoDoubleBufferWriterPointer`],
@@ -83,16 +98,24 @@
 //! This crate provides a few default strategy types
 //!
 //! * [`strategy::simple::SimpleStrategy`] - A non-thread-safe strategy which just keeps two
-//!     counters for how many readers are in each buffer, it checks these counts whenever you try to
-//!     swap the buffers, and errors if there are any readers in the other buffer.
+//!   counters for how many readers are in each buffer, it checks these counts whenever you try to
+//!   swap the buffers, and errors if there are any readers in the other buffer.
 //! * [`strategy::simple_async::SimpleAsyncStrategy`] - A non-thread-safe strategy which just keeps two
-//!     counters for how many readers are in each buffer. It waits until there are no more readers in
-//!     the other buffer before swapping.
-//! * [`strategy::atomic::AtomicStrategy`] - A thread-safe strategy which just keeps two
-//!     counters for how many readers are in each buffer, it checks these counts whenever you try to
-//!     swap the buffers, and errors if there are any readers in the other buffer.
+//!   counters for how many readers are in each buffer. It waits until there are no more readers in
+//!   the other buffer before swapping.
+//! * [`strategy::atomic::AtomicStrategy`] - A thread-safe strategy which keeps a single atomic
+//!   counter for how many readers are in the read buffer, it only commits a swap once there
+//!   are no readers left in the read buffer.
 //! * [`strategy::flashmap::FlashStrategy`] - A thread-safe strategy that is based off of the
-//!     [`flashmap`](https://docs.rs/flashmap) crate. see module level docs for details.
+//!   [`flashmap`](https://docs.rs/flashmap) crate. see module level docs for details.
+//! * [`strategy::static_slots::StaticStrategy`] - A thread-safe strategy that uses the same
+//!   algorithm as [`strategy::flashmap::FlashStrategy`], but with a fixed number of inline
+//!   reader slots. So it doesn't need to allocate.
+//! * [`strategy::null::NullStrategy`] - A strategy which never swaps the buffers, so readers
+//!   and the writer never share a buffer and reads don't need any synchronization.
+//! * [`strategy::spsc::SpscStrategy`] - A thread-safe strategy that only supports a single
+//!   reader. With only one reader, it doesn't need any per-reader state, so it doesn't need to
+//!   allocate, and swaps only touch a single atomic.
 //!
 //! ## Worked Example
 //!
@@ -204,6 +227,8 @@ pub mod delay;
 #[cfg(feature = "alloc")]
 pub mod op;
 pub mod raw;
+#[cfg(feature = "alloc")]
+pub mod simple_api;
 
 #[doc(hidden)]
 pub mod macros;
@@ -211,7 +236,13 @@ pub mod macros;
 #[cfg(feature = "alloc")]
 mod vec_drain;
 
+#[cfg(feature = "std")]
+pub use ext::{LocalBuffer, LocalPtr};
+#[cfg(feature = "alloc")]
+pub use ext::{StrongArc, StrongReader};
 #[cfg(feature = "alloc")]
 pub use rc_box;
+#[cfg(feature = "split-arc")]
+pub use split_arc;
 #[cfg(feature = "triomphe")]
 pub use triomphe;
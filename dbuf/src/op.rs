@@ -2,13 +2,22 @@
 
 use crate::{
     delay::DelayWriter,
-    interface::{AsyncStrategy, BlockingStrategy, DoubleBufferWriterPointer, Strategy},
+    interface::{AsyncStrategy, BlockingStrategy, DoubleBufferWriterPointer, Strategy, SwapError},
     raw,
 };
 
 use alloc::vec::Vec;
 use sync_wrapper::SyncWrapper;
 
+mod combinator;
+mod vec;
+
+#[cfg(test)]
+mod test;
+
+pub use combinator::{ForEach, Then};
+pub use vec::VecOp;
+
 pub struct OpWriter<
     P: DoubleBufferWriterPointer,
     O,
@@ -16,7 +25,12 @@ pub struct OpWriter<
 > {
     writer: DelayWriter<P, S>,
     op_log: Vec<sync_wrapper::SyncWrapper<O>>,
+    // the ops before the water line were applied to the read buffer, but not the write buffer
     water_line: usize,
+    // the number of ops after the water line which were already applied to the write buffer
+    applied: usize,
+    // the number of pending ops that `push_bounded` allows before publishing them
+    auto_publish: usize,
 }
 
 pub trait Operation<T: ?Sized, E: ?Sized, P: ?Sized>: Sized {
@@ -25,6 +39,44 @@ pub trait Operation<T: ?Sized, E: ?Sized, P: ?Sized>: Sized {
     fn apply_once(mut self, buffer: &mut T, extra: &E, params: &mut P) {
         self.apply(buffer, extra, params)
     }
+
+    /// Like [`Self::apply`], but also gets access to the other buffer
+    ///
+    /// `read` is the buffer that readers currently see, this is useful for operations
+    /// that copy parts of the other buffer forward. By default this ignores `read`.
+    fn apply_with_other(&mut self, buffer: &mut T, read: &T, extra: &E, params: &mut P) {
+        let _ = read;
+        self.apply(buffer, extra, params)
+    }
+
+    /// Like [`Self::apply_once`], but also gets access to the other buffer
+    ///
+    /// see [`Self::apply_with_other`] for details
+    fn apply_once_with_other(self, buffer: &mut T, read: &T, extra: &E, params: &mut P) {
+        let _ = read;
+        self.apply_once(buffer, extra, params)
+    }
+
+    /// Returns true if this operation is idempotent
+    ///
+    /// An operation is idempotent if the state of the buffer after it is applied doesn't depend on
+    /// the state of the buffer before it was applied. For example, `*buffer = value.clone()` is
+    /// idempotent, but `buffer.push(value.clone())` isn't. The `read` buffer passed to
+    /// [`Self::apply_with_other`] also counts as the starting state, so idempotent operations must not
+    /// depend on it either.
+    ///
+    /// If an operation is idempotent then all of the operations before it are skipped when
+    /// catching up the write buffer, since their effects would be overwritten anyways. Those
+    /// operations are dropped without being applied to that buffer. Each operation is still applied
+    /// at most once per buffer.
+    ///
+    /// Returning true for an operation which isn't idempotent will cause the buffers to diverge
+    ///
+    /// By default, this returns false
+    #[inline]
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 impl<P: DoubleBufferWriterPointer, O> From<raw::Writer<P>> for OpWriter<P, O> {
@@ -45,17 +97,87 @@ impl<P: DoubleBufferWriterPointer, O> OpWriter<P, O> {
             writer,
             op_log: Vec::new(),
             water_line: 0,
+            applied: 0,
+            auto_publish: usize::MAX,
         }
     }
 
+    /// Set how many pending operations [`Self::push_bounded`] allows before it
+    /// publishes them
+    ///
+    /// By default there is no limit
+    pub const fn with_auto_publish(mut self, threshold: usize) -> Self {
+        self.auto_publish = threshold;
+        self
+    }
+
     pub fn swap_buffers<Params: ?Sized>(&mut self, params: &mut Params)
     where
         P::Strategy: BlockingStrategy + Strategy<SwapError = core::convert::Infallible>,
         O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        self.swap_buffers_with(params, |_| ())
+    }
+
+    /// Apply all pending operations, swap the buffers, and then call `on_publish` with
+    /// the buffer that readers now see
+    ///
+    /// `on_publish` is called exactly once, before the next swap is started. This is
+    /// useful to notify other parts of your application about the new buffer.
+    pub fn swap_buffers_with<Params: ?Sized>(
+        &mut self,
+        params: &mut Params,
+        on_publish: impl FnOnce(&P::Buffer),
+    ) where
+        P::Strategy: BlockingStrategy + Strategy<SwapError = core::convert::Infallible>,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        match self.try_swap_buffers_with(params, on_publish) {
+            Ok(()) => (),
+            Err(err) => match err {},
+        }
+    }
+
+    /// Apply all pending operations, and then try to swap the buffers
+    ///
+    /// If the strategy fails to start the swap, then the error is returned and the pending
+    /// operations stay in the write buffer. They will be published by the next successful swap.
+    pub fn try_swap_buffers<Params: ?Sized>(
+        &mut self,
+        params: &mut Params,
+    ) -> Result<(), SwapError<P::Strategy>>
+    where
+        P::Strategy: BlockingStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        self.try_swap_buffers_with(params, |_| ())
+    }
+
+    /// Apply all pending operations, try to swap the buffers, and then call `on_publish` with
+    /// the buffer that readers now see
+    ///
+    /// `on_publish` is only called if the swap succeeds, see [`Self::swap_buffers_with`]
+    /// and [`Self::try_swap_buffers`] for details
+    pub fn try_swap_buffers_with<Params: ?Sized>(
+        &mut self,
+        params: &mut Params,
+        on_publish: impl FnOnce(&P::Buffer),
+    ) -> Result<(), SwapError<P::Strategy>>
+    where
+        P::Strategy: BlockingStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
     {
         let writer = self.writer.finish_swap();
-        swap_buffers(writer, &mut self.op_log, &mut self.water_line, params);
-        self.writer.start_swap();
+        flush(
+            writer,
+            &mut self.op_log,
+            &mut self.water_line,
+            &mut self.applied,
+            params,
+        );
+        self.try_publish()?;
+        on_publish(self.writer.split().read);
+        Ok(())
     }
 
     pub async fn aswap_buffers<Params: ?Sized>(&mut self, params: &mut Params)
@@ -64,9 +186,95 @@ impl<P: DoubleBufferWriterPointer, O> OpWriter<P, O> {
         O: Operation<P::Buffer, P::Extras, Params>,
     {
         let writer = self.writer.afinish_swap().await;
+        flush(
+            writer,
+            &mut self.op_log,
+            &mut self.water_line,
+            &mut self.applied,
+            params,
+        );
+        self.publish();
+    }
+
+    /// Push an operation and immediately apply it to the write buffer
+    ///
+    /// This finishes any ongoing swap, and then applies all pending operations, so the
+    /// write buffer reflects every pushed operation before they are published.
+    /// The operations are still replayed on the other buffer after the next swap.
+    pub fn apply<Params: ?Sized>(&mut self, op: O, params: &mut Params)
+    where
+        P::Strategy: BlockingStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        self.push(op);
+        self.apply_pending(params);
+    }
 
-        swap_buffers(writer, &mut self.op_log, &mut self.water_line, params);
-        self.writer.start_swap();
+    /// Push an operation and immediately apply it to the write buffer
+    ///
+    /// see [`Self::apply`] for details
+    pub async fn aapply<Params: ?Sized>(&mut self, op: O, params: &mut Params)
+    where
+        P::Strategy: AsyncStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        self.push(op);
+        self.aapply_pending(params).await;
+    }
+
+    /// Apply all pending operations to the write buffer, without publishing them
+    ///
+    /// This finishes any ongoing swap
+    pub fn apply_pending<Params: ?Sized>(&mut self, params: &mut Params)
+    where
+        P::Strategy: BlockingStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        let writer = self.writer.finish_swap();
+        flush(
+            writer,
+            &mut self.op_log,
+            &mut self.water_line,
+            &mut self.applied,
+            params,
+        );
+    }
+
+    /// Apply all pending operations to the write buffer, without publishing them
+    ///
+    /// see [`Self::apply_pending`] for details
+    pub async fn aapply_pending<Params: ?Sized>(&mut self, params: &mut Params)
+    where
+        P::Strategy: AsyncStrategy,
+        O: Operation<P::Buffer, P::Extras, Params>,
+    {
+        let writer = self.writer.afinish_swap().await;
+        flush(
+            writer,
+            &mut self.op_log,
+            &mut self.water_line,
+            &mut self.applied,
+            params,
+        );
+    }
+
+    fn publish(&mut self)
+    where
+        P::Strategy: Strategy<SwapError = core::convert::Infallible>,
+    {
+        match self.try_publish() {
+            Ok(()) => (),
+            Err(err) => match err {},
+        }
+    }
+
+    fn try_publish(&mut self) -> Result<(), SwapError<P::Strategy>> {
+        self.writer.try_start_swap()?;
+        // every op is now in the write buffer, which is now the read buffer
+        // so they all need to be applied to the other buffer one last time
+        self.water_line = self.op_log.len();
+        self.applied = 0;
+        Ok(())
     }
 
     #[inline]
@@ -74,6 +282,48 @@ impl<P: DoubleBufferWriterPointer, O> OpWriter<P, O> {
         self.op_log.push(SyncWrapper::new(op))
     }
 
+    /// Push an operation, and publish all pending operations once there are more than the
+    /// threshold set by [`Self::with_auto_publish`]
+    ///
+    /// Publishing needs the `Params` of the operations, so this only works if they don't need
+    /// any (`Params = ()`) and publishing can't fail. Publishing swaps the buffers with
+    /// [`Self::swap_buffers`], so this may block until all readers leave the write buffer.
+    ///
+    /// The published operations are kept until they are replayed on the other buffer by the
+    /// next swap, so the log holds at most about twice the threshold.
+    ///
+    /// ```rust
+    /// use dbuf::op::{OpWriter, VecOp};
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::hazad_flash::HazardFlashStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(Vec::new(), Vec::new(), HazardFlashStrategy::new());
+    /// let mut writer = OpWriter::from(Writer::new(&mut data)).with_auto_publish(2);
+    /// let mut reader = writer.reader();
+    ///
+    /// writer.push_bounded(VecOp::Push(1));
+    /// writer.push_bounded(VecOp::Push(2));
+    /// assert_eq!(*reader.read(), []);
+    ///
+    /// // this is over the threshold, so all three operations are published
+    /// writer.push_bounded(VecOp::Push(3));
+    /// assert_eq!(*reader.read(), [1, 2, 3]);
+    /// ```
+    pub fn push_bounded(&mut self, op: O)
+    where
+        P::Strategy: BlockingStrategy + Strategy<SwapError = core::convert::Infallible>,
+        O: Operation<P::Buffer, P::Extras, ()>,
+    {
+        self.push(op);
+
+        // the ops before the water line were already published
+        let pending = self.op_log.len().saturating_sub(self.water_line);
+
+        if pending > self.auto_publish {
+            self.swap_buffers(&mut ());
+        }
+    }
+
     #[inline]
     pub fn reserve(&mut self, additional: usize) {
         self.op_log.reserve(additional)
@@ -95,7 +345,8 @@ impl<P: DoubleBufferWriterPointer, O> Extend<O> for OpWriter<P, O> {
     }
 }
 
-fn swap_buffers<
+/// apply all operations which haven't been applied to the write buffer yet
+fn flush<
     P: DoubleBufferWriterPointer,
     O: Operation<P::Buffer, P::Extras, Params>,
     Params: ?Sized,
@@ -103,30 +354,68 @@ fn swap_buffers<
     writer: &mut raw::Writer<P>,
     op_log: &mut Vec<sync_wrapper::SyncWrapper<O>>,
     water_line: &mut usize,
+    applied: &mut usize,
     params: &mut Params,
-) where
-    P::Strategy: Strategy<SwapError = core::convert::Infallible>,
-{
+) {
     let split = writer.split_mut();
     let buffer = split.write;
+    let read = split.read;
     let extras = split.extras;
 
-    let water_line = &mut SetOnDrop::new(water_line).0;
+    let old_water_line = *water_line;
+    // the ops before `skip_until` are overwritten by an idempotent op, so they don't need to be applied
+    let skip_until = last_idempotent(op_log, old_water_line, *applied);
+
+    {
+        let water_line = &mut SetOnDrop::new(water_line).0;
+        #[allow(clippy::arithmetic_side_effects)]
+        for (i, op) in crate::vec_drain::drain_until(op_log, ..*water_line).enumerate() {
+            *water_line -= 1;
+            let op = op.into_inner();
+            if i >= skip_until {
+                op.apply_once_with_other(buffer, read, extras, params);
+            }
+        }
+    }
+
+    let applied = &mut SetOnDrop::new(applied).0;
+    let skip_until = skip_until.saturating_sub(old_water_line).max(*applied);
     #[allow(clippy::arithmetic_side_effects)]
-    for op in crate::vec_drain::drain_until(op_log, ..*water_line) {
-        *water_line -= 1;
-        op.into_inner().apply_once(buffer, extras, params);
+    for (i, op) in op_log.iter_mut().enumerate().skip(skip_until) {
+        op.get_mut().apply_with_other(buffer, read, extras, params);
+        // the skipped ops are only counted as applied once the idempotent op after them was applied
+        *applied = i + 1;
     }
+}
+
+/// find the index of the last idempotent op which still needs to be applied to the write buffer,
+/// or zero if there are none
+fn last_idempotent<O: Operation<T, E, Params>, T: ?Sized, E: ?Sized, Params: ?Sized>(
+    op_log: &mut [sync_wrapper::SyncWrapper<O>],
+    water_line: usize,
+    applied: usize,
+) -> usize {
+    let is_idempotent = |op: &mut sync_wrapper::SyncWrapper<O>| op.get_mut().is_idempotent();
+
+    // the ops between the water line and `pending` were already applied to the write buffer
+    #[allow(clippy::arithmetic_side_effects)]
+    let pending = water_line + applied;
 
-    for op in op_log.iter_mut() {
-        op.get_mut().apply(buffer, extras, params);
+    #[allow(clippy::arithmetic_side_effects)]
+    if let Some(i) = op_log[pending..].iter_mut().rposition(is_idempotent) {
+        return i + pending;
     }
+
+    op_log[..water_line]
+        .iter_mut()
+        .rposition(is_idempotent)
+        .unwrap_or(0)
 }
 
 struct SetOnDrop<'a>(usize, &'a mut usize);
 
 impl<'a> SetOnDrop<'a> {
-    pub fn new(value: &'a mut usize) -> Self {
+    pub const fn new(value: &'a mut usize) -> Self {
         Self(*value, value)
     }
 }
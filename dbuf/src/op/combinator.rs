@@ -0,0 +1,118 @@
+use super::Operation;
+
+/// An [`Operation`] which applies `A` and then `B`
+///
+/// This is idempotent if either of the operations is idempotent
+///
+/// ```rust
+/// use dbuf::op::{OpWriter, Then, VecOp};
+/// use dbuf::raw::{DoubleBufferData, Writer};
+/// use dbuf::strategy::simple::SimpleStrategy;
+///
+/// let mut data = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+/// let mut writer = OpWriter::from(Writer::new(&mut data));
+/// let mut reader = writer.reader();
+///
+/// writer.push(Then(VecOp::Push(1), VecOp::Push(2)));
+/// writer.push(Then(VecOp::Truncate(1), VecOp::Insert(0, 0)));
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [0, 1]);
+///
+/// // the other buffer catches up when the operations are replayed
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [0, 1]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Then<A, B>(pub A, pub B);
+
+impl<T: ?Sized, E: ?Sized, P: ?Sized, A, B> Operation<T, E, P> for Then<A, B>
+where
+    A: Operation<T, E, P>,
+    B: Operation<T, E, P>,
+{
+    #[inline]
+    fn apply(&mut self, buffer: &mut T, extra: &E, params: &mut P) {
+        self.0.apply(buffer, extra, params);
+        self.1.apply(buffer, extra, params);
+    }
+
+    #[inline]
+    fn apply_once(self, buffer: &mut T, extra: &E, params: &mut P) {
+        self.0.apply_once(buffer, extra, params);
+        self.1.apply_once(buffer, extra, params);
+    }
+
+    #[inline]
+    fn apply_with_other(&mut self, buffer: &mut T, read: &T, extra: &E, params: &mut P) {
+        self.0.apply_with_other(buffer, read, extra, params);
+        self.1.apply_with_other(buffer, read, extra, params);
+    }
+
+    #[inline]
+    fn apply_once_with_other(self, buffer: &mut T, read: &T, extra: &E, params: &mut P) {
+        self.0.apply_once_with_other(buffer, read, extra, params);
+        self.1.apply_once_with_other(buffer, read, extra, params);
+    }
+
+    #[inline]
+    fn is_idempotent(&self) -> bool {
+        // if `A` is idempotent, then the buffer only depends on `A` before `B` runs
+        // if `B` is idempotent, then the buffer doesn't depend on `A` at all
+        self.0.is_idempotent() || self.1.is_idempotent()
+    }
+}
+
+/// An [`Operation`] which calls `f` with each item
+///
+/// The items are cloned for the first buffer, and moved into `f` for the second buffer
+///
+/// ```rust
+/// use dbuf::op::{ForEach, OpWriter};
+/// use dbuf::raw::{DoubleBufferData, Writer};
+/// use dbuf::strategy::simple::SimpleStrategy;
+///
+/// let mut data = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+/// let mut writer = OpWriter::from(Writer::new(&mut data));
+/// let mut reader = writer.reader();
+///
+/// writer.push(ForEach::new([1, 2, 3], |buffer: &mut Vec<i32>, item| buffer.push(item)));
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [1, 2, 3]);
+///
+/// // the other buffer catches up when the operations are replayed
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForEach<I, F> {
+    items: I,
+    f: F,
+}
+
+impl<I, F> ForEach<I, F> {
+    /// Call `f` with each item of `items`
+    #[inline]
+    pub const fn new(items: I, f: F) -> Self {
+        Self { items, f }
+    }
+}
+
+impl<T: ?Sized, E: ?Sized, P: ?Sized, I, F> Operation<T, E, P> for ForEach<I, F>
+where
+    I: IntoIterator + Clone,
+    F: FnMut(&mut T, I::Item),
+{
+    #[inline]
+    fn apply(&mut self, buffer: &mut T, _extra: &E, _params: &mut P) {
+        for item in self.items.clone() {
+            (self.f)(buffer, item)
+        }
+    }
+
+    #[inline]
+    fn apply_once(mut self, buffer: &mut T, _extra: &E, _params: &mut P) {
+        for item in self.items {
+            (self.f)(buffer, item)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use super::{OpWriter, Operation, VecOp};
+
+use crate::{
+    raw::{DoubleBufferData, Writer},
+    strategy::simple::SimpleStrategy,
+};
+
+enum Op {
+    Vec(VecOp<i32>),
+    /// Add to every value, this depends on the old values so it isn't idempotent
+    AddAll(i32),
+}
+
+impl Operation<Vec<i32>, (), ()> for Op {
+    fn apply(&mut self, buffer: &mut Vec<i32>, extra: &(), params: &mut ()) {
+        match self {
+            Self::Vec(op) => op.apply(buffer, extra, params),
+            Self::AddAll(x) => buffer
+                .iter_mut()
+                .for_each(|value| *value = value.wrapping_add(*x)),
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        match self {
+            Self::Vec(op) => Operation::<Vec<i32>, (), ()>::is_idempotent(op),
+            Self::AddAll(_) => false,
+        }
+    }
+}
+
+const fn push(x: i32) -> Op {
+    Op::Vec(VecOp::Push(x))
+}
+
+const CLEAR: Op = Op::Vec(VecOp::Truncate(0));
+
+type TestWriter<'a> = OpWriter<&'a DoubleBufferData<Vec<i32>, SimpleStrategy>, Op>;
+
+/// publish everything, then replay it on the other buffer and check that both buffers match
+fn assert_converges(writer: &mut TestWriter<'_>, expected: &[i32]) {
+    writer.try_swap_buffers(&mut ()).unwrap();
+    writer.apply_pending(&mut ());
+
+    let split = writer.split();
+    assert_eq!(split.read, expected);
+    assert_eq!(split.write, expected);
+}
+
+#[test]
+fn idempotent_in_drained_region() {
+    let mut state = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+    let mut writer = OpWriter::from(Writer::new(&mut state));
+
+    writer.extend([push(1), CLEAR, push(2), Op::AddAll(10)]);
+    writer.try_swap_buffers(&mut ()).unwrap();
+    assert_eq!(*writer.split().read, [12]);
+
+    // the ops above are now only in the read buffer, so the clear is in the drained region
+    writer.push(Op::AddAll(1));
+    assert_converges(&mut writer, &[13]);
+}
+
+#[test]
+fn idempotent_in_pending_region() {
+    let mut state = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+    let mut writer = OpWriter::from(Writer::new(&mut state));
+
+    writer.extend([push(1), push(2)]);
+    writer.try_swap_buffers(&mut ()).unwrap();
+
+    writer.extend([push(3), Op::AddAll(1), CLEAR, push(4), Op::AddAll(1)]);
+    assert_converges(&mut writer, &[5]);
+}
+
+#[test]
+fn apply_pending_then_idempotent() {
+    let mut state = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+    let mut writer = OpWriter::from(Writer::new(&mut state));
+
+    writer.extend([push(1), Op::AddAll(1)]);
+    writer.apply_pending(&mut ());
+    writer.push(Op::AddAll(1));
+    writer.apply_pending(&mut ());
+    assert_eq!(*writer.split().write, [3]);
+
+    writer.extend([CLEAR, push(7)]);
+    assert_converges(&mut writer, &[7]);
+
+    // an idempotent op which was already applied must not be skipped over again
+    writer.extend([push(1), CLEAR, push(2)]);
+    writer.apply_pending(&mut ());
+    writer.push(Op::AddAll(1));
+    writer.apply_pending(&mut ());
+    assert_eq!(*writer.split().write, [3]);
+
+    writer.push(Op::AddAll(1));
+    assert_converges(&mut writer, &[4]);
+}
+
+#[test]
+fn retry_after_failed_swap() {
+    let mut state = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+    let mut writer = OpWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    writer.push(push(1));
+    writer.try_swap_buffers(&mut ()).unwrap();
+
+    let guard = reader.read();
+    writer.extend([push(2), CLEAR, push(3), Op::AddAll(1)]);
+    assert!(writer.try_swap_buffers(&mut ()).is_err());
+    assert_eq!(*guard, [1]);
+    drop(guard);
+
+    writer.push(Op::AddAll(1));
+    writer.try_swap_buffers(&mut ()).unwrap();
+    assert_eq!(*reader.read(), [5]);
+
+    writer.push(CLEAR);
+    assert_converges(&mut writer, &[]);
+}
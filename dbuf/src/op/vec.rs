@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+
+use super::Operation;
+
+/// A ready-made [`Operation`] for double buffered [`Vec`]s
+///
+/// Each operation is replayed on both buffers, so they end up identical as long as they
+/// started out identical. To keep that true, index based operations never panic when the
+/// index is out of bounds:
+///
+/// * [`VecOp::Insert`] past the end of the `Vec` pushes the value
+/// * [`VecOp::Remove`] past the end of the `Vec` does nothing
+/// * [`VecOp::Pop`] on an empty `Vec` does nothing
+/// * [`VecOp::Truncate`] past the end of the `Vec` does nothing (just like [`Vec::truncate`])
+///
+/// ```rust
+/// use dbuf::op::{OpWriter, VecOp};
+/// use dbuf::raw::{DoubleBufferData, Writer};
+/// use dbuf::strategy::simple::SimpleStrategy;
+///
+/// let mut data = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+/// let mut writer = OpWriter::from(Writer::new(&mut data));
+/// let mut reader = writer.reader();
+///
+/// writer.extend([VecOp::Push(1), VecOp::Push(3), VecOp::Insert(1, 2), VecOp::Remove(10)]);
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [1, 2, 3]);
+///
+/// writer.extend([VecOp::Pop, VecOp::Insert(10, 4)]);
+/// writer.try_swap_buffers(&mut ()).unwrap();
+/// assert_eq!(*reader.read(), [1, 2, 4]);
+///
+/// // the other buffer catches up when the operations are replayed
+/// writer
+///     .try_swap_buffers_with(&mut (), |published| assert_eq!(*published, [1, 2, 4]))
+///     .unwrap();
+/// assert_eq!(*reader.read(), [1, 2, 4]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VecOp<T> {
+    /// Push a value to the end of the `Vec`
+    Push(T),
+    /// Remove the last value of the `Vec`
+    Pop,
+    /// Insert a value at the given index, shifting all later values back
+    Insert(usize, T),
+    /// Remove the value at the given index, shifting all later values forward
+    Remove(usize),
+    /// Shorten the `Vec` to the given length
+    Truncate(usize),
+}
+
+impl<T> VecOp<T> {
+    fn apply_with(self, buffer: &mut Vec<T>) {
+        match self {
+            Self::Push(value) => buffer.push(value),
+            Self::Pop => {
+                buffer.pop();
+            }
+            Self::Insert(index, value) => buffer.insert(index.min(buffer.len()), value),
+            Self::Remove(index) => {
+                if index < buffer.len() {
+                    buffer.remove(index);
+                }
+            }
+            Self::Truncate(len) => buffer.truncate(len),
+        }
+    }
+}
+
+impl<T: Clone, E: ?Sized, P: ?Sized> Operation<Vec<T>, E, P> for VecOp<T> {
+    fn apply(&mut self, buffer: &mut Vec<T>, _extra: &E, _params: &mut P) {
+        let op = match self {
+            Self::Push(value) => Self::Push(value.clone()),
+            Self::Pop => Self::Pop,
+            Self::Insert(index, value) => Self::Insert(*index, value.clone()),
+            Self::Remove(index) => Self::Remove(*index),
+            Self::Truncate(len) => Self::Truncate(*len),
+        };
+
+        op.apply_with(buffer)
+    }
+
+    fn apply_once(self, buffer: &mut Vec<T>, _extra: &E, _params: &mut P) {
+        self.apply_with(buffer)
+    }
+
+    #[inline]
+    fn is_idempotent(&self) -> bool {
+        // clearing the `Vec` doesn't depend on what was in it before
+        matches!(self, Self::Truncate(0))
+    }
+}
@@ -5,18 +5,42 @@
 use core::{borrow::Borrow, cell::UnsafeCell};
 
 mod reader;
+#[cfg(feature = "postcard")]
+mod snapshot;
+#[cfg(feature = "futures")]
+mod stream;
 mod writer;
 
-pub use reader::{Reader, ReaderGuard};
-pub use writer::Writer;
+#[cfg(test)]
+mod test;
+
+pub use reader::{
+    ExtrasProjection, OwnedReaderGuard, ProjectedReader, ProjectedReaderGuard, Projector,
+    ReadError, Reader, ReaderGuard,
+};
+#[cfg(feature = "std")]
+pub use reader::{ReadTimeout, SharedReader};
+#[cfg(feature = "std")]
+pub use writer::SharedWriter;
+#[cfg(feature = "postcard")]
+pub use snapshot::EncodeSnapshotError;
+#[cfg(feature = "futures")]
+pub use stream::SnapshotStream;
+pub use writer::{ExtrasMut, SwapInProgress, SwapReport, SwappingWriter, Writer};
 
 /// The payload of a double buffer, this holds the two buffers
 /// the strategy, and some extra data. (the extra data is for you
 /// to use however you want).
+///
+/// The extra data isn't a public field, since it may be mutated by [`ExtrasMut`] while
+/// the data is shared. Use [`Writer::extras`] or [`ReaderGuard::extras`] to read it, and
+/// [`DoubleBufferData::get_extras_mut`] or [`Writer::try_extras_mut`] to modify it.
 pub struct DoubleBufferData<T, S, Extras: ?Sized = ()> {
     buffers: DoubleBufferCell<T>,
     pub strategy: S,
-    pub extras: Extras,
+    // the extras may only be mutated through a shared reference by [`ExtrasMut`]
+    // which locks out all readers
+    extras: UnsafeCell<Extras>,
 }
 
 #[repr(transparent)]
@@ -24,6 +48,22 @@ struct DoubleBufferCell<T> {
     parts: [UnsafeCell<T>; 2],
 }
 
+// SAFETY: the only non-`Sync` fields are the `UnsafeCell`s, everything else is accessed through
+// a shared reference, which is fine because `S: Sync`.
+//
+// * the read buffer is only accessed through a `&T`, which may be held by readers on any number
+//   of threads at once, so `T: Sync` is required
+// * the write buffer is only accessed through a `&mut T` by the writer, which may be on a different
+//   thread than the last readers of that buffer, so `T: Send` is required. The `Strategy` contract
+//   guarantees that the writer never accesses a buffer while there is an active read in it, and
+//   since `S: Sync` it must establish that across threads.
+// * the extras are accessed through a `&Extras` by any thread (requires `Extras: Sync`), and through
+//   a `&mut Extras` by `ExtrasMut` (requires `Extras: Send`) which locks out all readers first
+unsafe impl<T: Send + Sync, S: Sync, Extras: ?Sized + Send + Sync> Sync
+    for DoubleBufferData<T, S, Extras>
+{
+}
+
 impl<T> DoubleBufferCell<T> {
     const fn get(&self, swapped: bool) -> (*const T, *mut T) {
         (
@@ -39,6 +79,30 @@ impl<T, S> DoubleBufferData<T, S> {
     pub const fn new(back: T, front: T, strategy: S) -> Self {
         Self::with_extras(back, front, strategy, ())
     }
+
+    /// Create a new payload where both buffers start out as `initial`
+    ///
+    /// `initial` is cloned into the front buffer, and moved into the back buffer
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::from_clone(vec![1, 2, 3], SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// assert_eq!(*reader.read(), [1, 2, 3]);
+    /// writer.swap();
+    /// assert_eq!(*reader.read(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn from_clone(initial: T, strategy: S) -> Self
+    where
+        T: Clone,
+    {
+        Self::with_extras_from_clone(initial, strategy, ())
+    }
 }
 
 impl<T, S, Extras> DoubleBufferData<T, S, Extras> {
@@ -49,9 +113,79 @@ impl<T, S, Extras> DoubleBufferData<T, S, Extras> {
                 parts: [UnsafeCell::new(front), UnsafeCell::new(back)],
             },
             strategy,
-            extras,
+            extras: UnsafeCell::new(extras),
         }
     }
+
+    /// Create a new payload with the given extra value, where both buffers start out as `initial`
+    ///
+    /// see [`DoubleBufferData::from_clone`] for details
+    #[inline]
+    pub fn with_extras_from_clone(initial: T, strategy: S, extras: Extras) -> Self
+    where
+        T: Clone,
+    {
+        let front = initial.clone();
+        Self::with_extras(initial, front, strategy, extras)
+    }
+
+    /// Map both buffers to a new type, keeping the strategy and extras
+    ///
+    /// Each buffer stays in the same place, so any swaps recorded by the strategy
+    /// still apply to the mapped buffers. The back buffer is mapped first.
+    ///
+    /// see [`Writer::try_map`] to map the buffers of a writer
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> DoubleBufferData<U, S, Extras> {
+        let [front, back] = self.buffers.parts;
+        let back = f(back.into_inner());
+        let front = f(front.into_inner());
+        DoubleBufferData::with_extras(back, front, self.strategy, self.extras.into_inner())
+    }
+}
+
+/// Both buffers are created with [`T::default`](Default::default), they aren't cloned from each other
+impl<T: Default, S: Default, Extras: Default> Default for DoubleBufferData<T, S, Extras> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_extras(T::default(), T::default(), S::default(), Extras::default())
+    }
+}
+
+impl<T, S, Extras: ?Sized> DoubleBufferData<T, S, Extras> {
+    /// Get exclusive references to both buffers
+    ///
+    /// The buffers are returned in the same order that they were passed to [`DoubleBufferData::new`],
+    /// this doesn't account for any swaps which happened since then.
+    ///
+    /// This is safe because `&mut self` ensures that there are no writers or readers using the buffers
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(Vec::new(), Vec::new(), SimpleStrategy::new());
+    /// let (back, front) = data.buffers_mut();
+    /// back.extend([1, 2, 3]);
+    /// front.extend([1, 2, 3]);
+    ///
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    /// assert_eq!(*reader.read(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub const fn buffers_mut(&mut self) -> (&mut T, &mut T) {
+        let [front, back] = &mut self.buffers.parts;
+        (back.get_mut(), front.get_mut())
+    }
+
+    /// Get an exclusive reference to the extra value
+    ///
+    /// This replaces the old public `extras` field, see [`DoubleBufferData`] for how to
+    /// access the extras while the data is shared
+    #[inline]
+    pub const fn get_extras_mut(&mut self) -> &mut Extras {
+        self.extras.get_mut()
+    }
 }
 
 /// This is a type that may be owned or borrowed, like a `Cow`, but this
@@ -76,9 +210,60 @@ pub struct Split<'a, T, Extras: ?Sized> {
 }
 
 /// The values stored in the buffers, returned by [`Writer::split_mut`]
+///
+/// The write buffer may be narrowed down to a part of it with [`SplitMut::map_write`],
+/// that part is the `W` parameter.
 #[non_exhaustive]
-pub struct SplitMut<'a, T, Extras: ?Sized> {
+pub struct SplitMut<'a, T, Extras: ?Sized, W: ?Sized = T> {
     pub read: &'a T,
-    pub write: &'a mut T,
+    pub write: &'a mut W,
     pub extras: &'a Extras,
 }
+
+impl<'a, T, Extras: ?Sized, W: ?Sized> SplitMut<'a, T, Extras, W> {
+    /// Narrow the write buffer down to a part of it
+    ///
+    /// The read buffer and extras are kept as is, and stay borrowed for the same lifetime `'a`
+    /// as the new write reference.
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new((0, 'a'), (1, 'b'), SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    ///
+    /// let split = writer.split_mut().map_write(|(_, c)| c);
+    /// *split.write = split.read.1;
+    /// assert_eq!(*writer.get(), (1, 'a'));
+    /// ```
+    #[inline]
+    pub fn map_write<U: ?Sized>(
+        self,
+        f: impl FnOnce(&'a mut W) -> &'a mut U,
+    ) -> SplitMut<'a, T, Extras, U> {
+        SplitMut {
+            read: self.read,
+            write: f(self.write),
+            extras: self.extras,
+        }
+    }
+
+    /// Try to narrow the write buffer down to a part of it
+    ///
+    /// If `f` fails, then the error is returned. The write reference is moved into `f`, so
+    /// it can't be returned along with the error, call [`Writer::split_mut`] again to get it back.
+    ///
+    /// see [`SplitMut::map_write`] for details
+    #[inline]
+    pub fn try_map_write<U: ?Sized, E>(
+        self,
+        f: impl FnOnce(&'a mut W) -> Result<&'a mut U, E>,
+    ) -> Result<SplitMut<'a, T, Extras, U>, E> {
+        Ok(SplitMut {
+            read: self.read,
+            write: f(self.write)?,
+            extras: self.extras,
+        })
+    }
+}
@@ -1,8 +1,8 @@
 use core::{borrow::Borrow, marker::PhantomData, mem::ManuallyDrop, ops, ptr::NonNull};
 
 use crate::interface::{
-    self as iface, create_invalid_reader_id, DoubleBufferReaderPointer, DoubleBufferWriterPointer,
-    ReaderId, Strategy,
+    self as iface, DanglingReaderPointer, DoubleBufferReaderPointer, DoubleBufferWriterPointer,
+    NotifyReaders, ReaderId, Strategy,
 };
 
 /// A reader into a double buffer
@@ -19,7 +19,10 @@ pub struct Reader<P, S: Strategy = <P as DoubleBufferReaderPointer>::Strategy> {
 pub struct ReaderGuard<'a, T: ?Sized, P: DoubleBufferWriterPointer> {
     ptr: RawReference<'a, T>,
     extras: RawReference<'a, P::Extras>,
-    raw: RawReaderGuard<'a, P>,
+    generation: Option<u64>,
+    swapped: bool,
+    // this is None if this guard was reborrowed from another guard
+    raw: Option<RawReaderGuard<'a, P>>,
 }
 
 struct RawReference<'a, T: ?Sized> {
@@ -69,6 +72,14 @@ impl<P: DoubleBufferWriterPointer> Drop for RawReaderGuard<'_, P> {
     }
 }
 
+impl<P: DoubleBufferReaderPointer> core::fmt::Debug for Reader<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Reader")
+            .field("is_writer_alive", &self.is_writer_alive())
+            .finish_non_exhaustive()
+    }
+}
+
 impl<P: DoubleBufferReaderPointer> Reader<P> {
     /// Create a new reader from an id and pointer
     #[inline]
@@ -76,40 +87,257 @@ impl<P: DoubleBufferReaderPointer> Reader<P> {
         Self { id, ptr }
     }
 
+    /// Split the reader into it's id and pointer
+    #[inline]
+    #[allow(unused)]
+    pub(crate) fn into_raw_parts(self) -> (ReaderId<P::Strategy>, P) {
+        (self.id, self.ptr)
+    }
+
+    /// Get the underlying reader pointer
+    ///
+    /// This is useful to build other abstractions on top of readers, for example to check
+    /// if the writer is still alive, or to upgrade the pointer and look at the strategy.
+    ///
+    /// The reader's id is only valid for the strategy behind this pointer. Using the pointer in
+    /// unsafe code to bypass the invariants of the id (for example, by reading the buffers
+    /// without a read guard, or by calling the strategy with the wrong id) is unsound.
+    #[inline]
+    pub const fn pointer(&self) -> &P {
+        &self.ptr
+    }
+
+    /// Get the underlying reader pointer, and drop the reader's id
+    ///
+    /// The pointer no longer has an id to go with it, so it can't be used to read the buffers.
+    /// see [`Self::pointer`] for details
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use std::sync::Arc;
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    /// use dbuf::rc_box::ArcBox;
+    ///
+    /// let writer: Writer<Arc<DoubleBufferData<u8, SimpleStrategy>>> =
+    ///     Writer::new(ArcBox::new(DoubleBufferData::new(0, 1, SimpleStrategy::new())));
+    /// let ptr = writer.reader().into_pointer();
+    /// assert!(ptr.upgrade().is_some());
+    ///
+    /// drop(writer);
+    /// assert!(ptr.upgrade().is_none());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn into_pointer(self) -> P {
+        self.ptr
+    }
+
+    /// Check if the writer is still alive, without acquiring a read guard
+    ///
+    /// Note that the writer may be dropped right after this returns true
+    #[inline]
+    pub fn is_writer_alive(&self) -> bool {
+        self.ptr.try_writer().is_ok()
+    }
+
     /// Try to access the read buffer, if it fails then returns an error
     ///
     /// see the pointer's docs for when upgrading the pointer can fail
     pub fn try_read(&mut self) -> Result<ReaderGuard<'_, P::Buffer, P::Writer>, P::UpgradeError> {
         let ptr = self.ptr.try_writer()?;
-        let data = ptr.borrow();
-        // SAFETY: the reader id is valid (this is an invariant of Self)
-        let guard = unsafe { data.strategy.acquire_read_guard(&mut self.id) };
-        // SAFETY: the guard was created from the given reader id, and is the latest guard
-        let swapped = unsafe { data.strategy.is_swapped(&mut self.id, &guard) };
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        Ok(unsafe { acquire_guard::<P>(&mut self.id, ptr) })
+    }
 
-        let (reader, _) = data.buffers.get(swapped);
+    /// Try to access the read buffer, without panicking if the strategy detects a leaked read guard
+    ///
+    /// Some strategies panic on [`Self::try_read`] if a read guard from this reader was
+    /// leaked (for example with [`core::mem::forget`]), since the reader is still registered
+    /// as reading. This returns [`ReadError::LeakedGuard`] instead. Strategies which can't detect
+    /// leaked guards behave the same as [`Self::try_read`].
+    #[allow(clippy::type_complexity)]
+    pub fn try_read_checked(
+        &mut self,
+    ) -> Result<ReaderGuard<'_, P::Buffer, P::Writer>, ReadError<P::UpgradeError>> {
+        let ptr = self.ptr.try_writer().map_err(ReadError::Upgrade)?;
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        unsafe { try_acquire_guard::<P>(&mut self.id, ptr) }.ok_or(ReadError::LeakedGuard)
+    }
 
-        let extras = core::ptr::addr_of!(data.extras);
+    /// Try to access the read buffer, giving up if acquiring the read guard takes longer than `timeout`
+    ///
+    /// Acquiring a read guard may block, for example while the writer locks out all readers
+    /// (see [`ReaderLock`](crate::interface::ReaderLock)). If the guard can't be acquired
+    /// before the timeout elapses, then this returns [`ReadTimeout::Elapsed`].
+    ///
+    /// Upgrading the pointer isn't retried, since once it fails it will never succeed again.
+    #[cfg(feature = "std")]
+    #[allow(clippy::type_complexity)]
+    pub fn read_timeout(
+        &mut self,
+        timeout: core::time::Duration,
+    ) -> Result<ReaderGuard<'_, P::Buffer, P::Writer>, ReadTimeout<P::UpgradeError>> {
+        let ptr = self.ptr.try_writer().map_err(ReadTimeout::Upgrade)?;
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        unsafe { acquire_guard_timeout::<P>(&mut self.id, ptr, timeout) }
+            .ok_or(ReadTimeout::Elapsed)
+    }
 
-        Ok(ReaderGuard {
-            ptr: RawReference {
-                // SAFETY: the pointer from ptr.buffers.get are always non-null
-                ptr: unsafe { NonNull::new_unchecked(reader.cast_mut()) },
-                lt: PhantomData,
-            },
-            extras: RawReference {
-                // SAFETY: references are always non-null, and extras is derived from a reference
-                ptr: unsafe { NonNull::new_unchecked(extras.cast_mut()) },
-                lt: PhantomData,
-            },
-            raw: RawReaderGuard {
-                guard: ManuallyDrop::new(guard),
-                reader_id: &mut self.id,
-                writer: ptr,
-            },
+    /// Block until the writer swaps the buffers after this reader's last read
+    ///
+    /// If the writer already swapped the buffers since the last read, then this returns immediately.
+    /// Note that if the writer never swaps the buffers again, then this blocks forever. See
+    /// [`Self::wait_for_swap_timeout`] for a version that gives up after some time.
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    pub fn wait_for_swap(&mut self) -> Result<(), P::UpgradeError>
+    where
+        P::Strategy: NotifyReaders,
+    {
+        let ptr = self.ptr.try_writer()?;
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        unsafe { ptr.borrow().strategy.wait_for_swap(&mut self.id) };
+        Ok(())
+    }
+
+    /// Block until the writer swaps the buffers after this reader's last read, or the timeout elapses
+    ///
+    /// Returns true if the buffers were swapped, see [`Self::wait_for_swap`] for details
+    pub fn wait_for_swap_timeout(
+        &mut self,
+        timeout: core::time::Duration,
+    ) -> Result<bool, P::UpgradeError>
+    where
+        P::Strategy: NotifyReaders,
+    {
+        let ptr = self.ptr.try_writer()?;
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        Ok(unsafe {
+            ptr.borrow()
+                .strategy
+                .wait_for_swap_timeout(&mut self.id, timeout)
         })
     }
 
+    /// Poll until the writer swaps the buffers after this reader's last read
+    ///
+    /// This is the async version of [`Self::wait_for_swap`]. The waker is woken after
+    /// the next swap, or once the buffers are dropped.
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    pub fn poll_swap(
+        &mut self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), P::UpgradeError>>
+    where
+        P::Strategy: NotifyReaders,
+    {
+        let ptr = match self.ptr.try_writer() {
+            Ok(ptr) => ptr,
+            Err(err) => return core::task::Poll::Ready(Err(err)),
+        };
+        // SAFETY: ptr was upgraded from self.ptr, which the reader id is valid for
+        unsafe { ptr.borrow().strategy.poll_for_swap(&mut self.id, ctx) }.map(Ok)
+    }
+
+    /// Run the closure with access to the read buffer and extras, using a single read guard
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    pub fn with_read<R>(
+        &mut self,
+        f: impl FnOnce(&P::Buffer, &P::Extras) -> R,
+    ) -> Result<R, P::UpgradeError> {
+        let guard = self.try_read()?;
+        Ok(f(&guard, guard.extras()))
+    }
+
+    /// Clone the read buffer, the read guard is released as soon as the clone is made
+    ///
+    /// This keeps the buffer locked for as little time as possible, so it's the recommended
+    /// way to read if you need to hold on to the value. See [`ReaderGuard::to_owned`]
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    ///
+    /// ```rust
+    /// use dbuf::raw::{Writer, DoubleBufferData};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(vec![0], vec![1], SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// let value = reader.read_cloned().unwrap();
+    /// // the reader isn't reading anymore, so the swap succeeds
+    /// writer.try_swap().unwrap();
+    /// assert_eq!(value, [0]);
+    /// ```
+    pub fn read_cloned(&mut self) -> Result<P::Buffer, P::UpgradeError>
+    where
+        P::Buffer: Clone,
+    {
+        Ok(self.try_read()?.to_owned())
+    }
+
+    /// Read the buffer, returning a guard which isn't tied to this reader
+    ///
+    /// The returned guard holds it's own reader registration and a strong pointer to the buffers,
+    /// so it can be moved into another task or thread. The read guard is released once the
+    /// returned guard is dropped.
+    pub fn read_owned(&self) -> Result<OwnedReaderGuard<P::Buffer, P>, P::UpgradeError>
+    where
+        P::Writer: Clone,
+    {
+        let writer: P::Writer = self.ptr.try_writer()?.borrow().clone();
+        // SAFETY: the reader id is valid for the strategy behind self.ptr (invariant of Self)
+        // and writer was upgraded from self.ptr
+        let mut id = unsafe { writer.strategy.create_reader_id_from_reader(&self.id) };
+        // SAFETY: the reader id was just created from this strategy
+        let guard = unsafe { writer.strategy.acquire_read_guard(&mut id) };
+        // SAFETY: the guard was created from the given reader id, and is the latest guard
+        let swapped = unsafe { writer.strategy.is_swapped(&mut id, &guard) };
+
+        let (buffer, _) = writer.buffers.get(swapped);
+
+        Ok(OwnedReaderGuard {
+            // SAFETY: the pointer from buffers.get are always non-null
+            value: unsafe { NonNull::new_unchecked(buffer.cast_mut()) },
+            guard: ManuallyDrop::new(guard),
+            id,
+            writer,
+        })
+    }
+
+    /// Read the buffer and project it, returning a guard which isn't tied to this reader
+    ///
+    /// see [`Self::read_owned`] for details
+    pub fn read_map_owned<U: ?Sized>(
+        &self,
+        f: impl for<'t> FnOnce(&'t P::Buffer) -> &'t U,
+    ) -> Result<OwnedReaderGuard<U, P>, P::UpgradeError>
+    where
+        P::Writer: Clone,
+    {
+        // map after the guard is constructed, so that the read guard is released if f panics
+        Ok(self.read_owned()?.map(f))
+    }
+
+    /// Read the buffer and try to project it, returning a guard which isn't tied to this reader
+    ///
+    /// If the projection fails, then the unmapped guard is returned along with the error.
+    /// see [`Self::read_owned`] for details
+    #[allow(clippy::type_complexity)]
+    pub fn try_read_map_owned<U: ?Sized, E>(
+        &self,
+        f: impl for<'t> FnOnce(&'t P::Buffer) -> Result<&'t U, E>,
+    ) -> Result<Result<OwnedReaderGuard<U, P>, (OwnedReaderGuard<P::Buffer, P>, E)>, P::UpgradeError>
+    where
+        P::Writer: Clone,
+    {
+        Ok(self.read_owned()?.try_map(f))
+    }
+
     /// Try to access the read buffer
     ///
     /// # Panic
@@ -119,29 +347,302 @@ impl<P: DoubleBufferReaderPointer> Reader<P> {
     where
         P::UpgradeError: core::fmt::Debug,
     {
-        fn read_failed<T: core::fmt::Debug>(err: &T) -> ! {
-            panic!("Cannot access a dropped double buffer: {err:?}")
-        }
-
         match self.try_read() {
             Ok(guard) => guard,
             Err(err) => read_failed(&err),
         }
     }
+
+    /// Try to access the read buffer, returns None if upgrading the pointer fails
+    ///
+    /// This is useful when the writer going away is expected, for example with `Weak` pointers
+    #[inline]
+    pub fn read_opt(&mut self) -> Option<ReaderGuard<'_, P::Buffer, P::Writer>> {
+        self.try_read().ok()
+    }
+
+    /// Try to access the read buffer, if upgrading the pointer fails then
+    /// the error is replaced with the result of `fallback`
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(1, 2, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// let value = reader.read_or(|| 0).map_or_else(|fallback| fallback, |guard| *guard);
+    /// assert_eq!(value, 1);
+    /// ```
+    #[inline]
+    pub fn read_or<R, F: FnOnce() -> R>(
+        &mut self,
+        fallback: F,
+    ) -> Result<ReaderGuard<'_, P::Buffer, P::Writer>, R> {
+        self.try_read().map_err(|_| fallback())
+    }
+
+    /// Convert this reader into a reader which only sees a part of the extras
+    ///
+    /// see [`ExtrasProjection`] for details
+    #[inline]
+    pub const fn map_pointer<F: ExtrasProjection<P::Extras>>(self) -> ProjectedReader<P, F> {
+        ProjectedReader {
+            reader: self,
+            projection: PhantomData,
+        }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn read_failed<T: core::fmt::Debug>(err: &T) -> ! {
+    panic!("Cannot access a dropped double buffer: {err:?}")
+}
+
+/// The error returned by [`Reader::try_read_checked`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError<E> {
+    /// Upgrading the reader's pointer failed
+    Upgrade(E),
+    /// The strategy detected that a read guard from this reader was leaked
+    LeakedGuard,
+}
+
+/// The error returned by [`Reader::read_timeout`]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadTimeout<E> {
+    /// Upgrading the reader's pointer failed
+    Upgrade(E),
+    /// The read guard couldn't be acquired before the timeout elapsed
+    Elapsed,
+}
+
+/// A reader which can be shared between threads, and read through a shared reference
+///
+/// [`Reader::read`] takes `&mut self` because each reader id may only have one active read guard
+/// at a time, strategies track each reader's guard in the reader id instead of counting how many
+/// guards are active. So a `&Reader` can't be used to read, since two threads could then each
+/// acquire a guard from the same reader id.
+///
+/// This wrapper keeps the reader behind a mutex, which is held for the entire read. So there is
+/// still only one guard per reader id, and because the guard can't outlive the lock, the buffer
+/// is only accessed inside of a closure. This is useful when a reader is shared rarely, and
+/// cloning readers is expensive, otherwise prefer one reader per thread, since reads through this
+/// wrapper contend on the mutex.
+///
+/// ```rust
+/// use dbuf::raw::{DoubleBufferData, SharedReader, Writer};
+/// use dbuf::strategy::simple::SimpleStrategy;
+///
+/// let mut data = DoubleBufferData::new(10, 20, SimpleStrategy::new());
+/// let mut writer = Writer::new(&mut data);
+/// let reader = SharedReader::new(writer.reader());
+///
+/// assert_eq!(reader.with_read(|value, ()| *value), Ok(10));
+/// ```
+#[cfg(feature = "std")]
+pub struct SharedReader<P: DoubleBufferReaderPointer> {
+    reader: std::sync::Mutex<Reader<P>>,
+}
+
+#[cfg(feature = "std")]
+impl<P: DoubleBufferReaderPointer> From<Reader<P>> for SharedReader<P> {
+    #[inline]
+    fn from(reader: Reader<P>) -> Self {
+        Self::new(reader)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: DoubleBufferReaderPointer> SharedReader<P> {
+    /// Share the given reader
+    pub const fn new(reader: Reader<P>) -> Self {
+        Self {
+            reader: std::sync::Mutex::new(reader),
+        }
+    }
+
+    /// Run the closure with access to the read buffer and extras, while holding the lock
+    ///
+    /// Other threads reading through this wrapper block until the closure returns,
+    /// and the writer can't finish a swap until then either.
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    pub fn with_read<R>(
+        &self,
+        f: impl FnOnce(&P::Buffer, &P::Extras) -> R,
+    ) -> Result<R, P::UpgradeError> {
+        self.lock().with_read(f)
+    }
+
+    /// Clone the underlying reader, so it can be used without the lock
+    pub fn reader(&self) -> Reader<P> {
+        self.lock().clone()
+    }
+
+    /// Get the underlying reader
+    pub fn into_inner(self) -> Reader<P> {
+        self.reader
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Reader<P>> {
+        // the reader is always in a valid state, even if a closure panicked,
+        // since the read guard gets released while unwinding
+        self.reader
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Acquire a read guard using the given reader id and writer pointer
+///
+/// # Safety
+///
+/// The reader id must be valid for the strategy behind `ptr`
+unsafe fn acquire_guard<'a, P: DoubleBufferReaderPointer>(
+    id: &'a mut ReaderId<P::Strategy>,
+    ptr: P::MaybeBorrowed<'a>,
+) -> ReaderGuard<'a, P::Buffer, P::Writer> {
+    // SAFETY: the reader id is valid (guaranteed by caller)
+    let guard = unsafe { ptr.borrow().strategy.acquire_read_guard(id) };
+    // SAFETY: the guard was just acquired from the reader id
+    unsafe { new_guard::<P>(id, ptr, guard) }
+}
+
+/// Acquire a read guard using the given reader id and writer pointer,
+/// returns None if the strategy detected a leaked read guard
+///
+/// # Safety
+///
+/// The reader id must be valid for the strategy behind `ptr`
+unsafe fn try_acquire_guard<'a, P: DoubleBufferReaderPointer>(
+    id: &'a mut ReaderId<P::Strategy>,
+    ptr: P::MaybeBorrowed<'a>,
+) -> Option<ReaderGuard<'a, P::Buffer, P::Writer>> {
+    // SAFETY: the reader id is valid (guaranteed by caller)
+    let guard = unsafe { ptr.borrow().strategy.try_acquire_read_guard(id) }?;
+    // SAFETY: the guard was just acquired from the reader id
+    Some(unsafe { new_guard::<P>(id, ptr, guard) })
+}
+
+/// Acquire a read guard using the given reader id and writer pointer,
+/// returns None if the guard couldn't be acquired before the timeout elapsed
+///
+/// # Safety
+///
+/// The reader id must be valid for the strategy behind `ptr`
+#[cfg(feature = "std")]
+unsafe fn acquire_guard_timeout<'a, P: DoubleBufferReaderPointer>(
+    id: &'a mut ReaderId<P::Strategy>,
+    ptr: P::MaybeBorrowed<'a>,
+    timeout: core::time::Duration,
+) -> Option<ReaderGuard<'a, P::Buffer, P::Writer>> {
+    // SAFETY: the reader id is valid (guaranteed by caller)
+    let guard = unsafe {
+        ptr.borrow()
+            .strategy
+            .acquire_read_guard_timeout(id, timeout)
+    }?;
+    // SAFETY: the guard was just acquired from the reader id
+    Some(unsafe { new_guard::<P>(id, ptr, guard) })
+}
+
+/// Create a reader guard from a strategy's read guard
+///
+/// # Safety
+///
+/// The reader id must be valid for the strategy behind `ptr`, and `guard`
+/// must be the latest guard acquired from that reader id
+unsafe fn new_guard<'a, P: DoubleBufferReaderPointer>(
+    id: &'a mut ReaderId<P::Strategy>,
+    ptr: P::MaybeBorrowed<'a>,
+    guard: iface::ReaderGuard<P::Strategy>,
+) -> ReaderGuard<'a, P::Buffer, P::Writer> {
+    let data = ptr.borrow();
+    // SAFETY: the guard was created from the given reader id, and is the latest guard
+    let swapped = unsafe { data.strategy.is_swapped(id, &guard) };
+    // SAFETY: the guard was created from the given reader id, and is the latest guard
+    let generation = unsafe { data.strategy.generation(id, &guard) };
+
+    let (reader, _) = data.buffers.get(swapped);
+
+    let extras = data.extras.get();
+
+    ReaderGuard {
+        ptr: RawReference {
+            // SAFETY: the pointer from ptr.buffers.get are always non-null
+            ptr: unsafe { NonNull::new_unchecked(reader.cast_mut()) },
+            lt: PhantomData,
+        },
+        extras: RawReference {
+            // SAFETY: UnsafeCell::get is always non-null, since it is derived from a reference
+            ptr: unsafe { NonNull::new_unchecked(extras) },
+            lt: PhantomData,
+        },
+        generation,
+        swapped,
+        raw: Some(RawReaderGuard {
+            guard: ManuallyDrop::new(guard),
+            reader_id: id,
+            writer: ptr,
+        }),
+    }
 }
 
 impl<P: DoubleBufferReaderPointer> Clone for Reader<P> {
     #[inline]
     fn clone(&self) -> Self {
-        let id = match self.ptr.try_writer() {
-            // SAFETY: the reader id is valid (this is an invariant of Self)
-            Ok(ptr) => unsafe { ptr.borrow().strategy.create_reader_id_from_reader(&self.id) },
-            Err(_) => create_invalid_reader_id::<P::Strategy>(),
-        };
+        let ptr = self.ptr.clone();
+
+        // create the id from the new pointer, since not all pointers can be cloned into another live pointer
+        // SAFETY: the reader id is valid for the strategy behind self.ptr (this is an invariant of Self)
+        // and ptr is a clone of self.ptr
+        let id = unsafe { ptr.create_reader_id_from_reader(&self.id) };
 
         // SAFETY: id is valid for the strategy inside ptr
         // or the ptr is dead and the reader id is invalid
-        unsafe { Self::from_raw_parts(id, self.ptr.clone()) }
+        unsafe { Self::from_raw_parts(id, ptr) }
+    }
+}
+
+impl<P: DanglingReaderPointer> Reader<P> {
+    /// Create a placeholder reader which doesn't point to any buffers
+    ///
+    /// Every read from this reader fails (and all clones of it), since there is no writer.
+    /// This is useful as a placeholder before the real reader is available.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use std::sync::Weak;
+    /// use dbuf::raw::{DoubleBufferData, Reader};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut reader = Reader::<Weak<DoubleBufferData<i32, SimpleStrategy>>>::new_invalid();
+    /// assert!(!reader.is_writer_alive());
+    /// assert!(reader.try_read().is_err());
+    /// # }
+    /// ```
+    pub fn new_invalid() -> Self {
+        // SAFETY: the pointer is dangling, so an invalid reader id is fine
+        unsafe {
+            Self::from_raw_parts(
+                iface::create_invalid_reader_id::<P::Strategy>(),
+                P::dangling(),
+            )
+        }
+    }
+}
+
+impl<P: DanglingReaderPointer> Default for Reader<P> {
+    #[inline]
+    fn default() -> Self {
+        Self::new_invalid()
     }
 }
 
@@ -150,20 +651,117 @@ impl<T: ?Sized, P: DoubleBufferWriterPointer> ops::Deref for ReaderGuard<'_, T,
 
     #[inline]
     fn deref(&self) -> &Self::Target {
-        // SAFETY: self.raw ensures that the writer doesn't have access to self.ptr
+        // SAFETY: self.raw (or the guard this was reborrowed from) ensures that the writer
+        // doesn't have access to self.ptr
         // so there is no race with the writer, and readers cannot race with each other
         // self.ptr is non-null, well aligned, allocated and valid for reads
         unsafe { self.ptr.ptr.as_ref() }
     }
 }
 
+impl<T: ?Sized, P: DoubleBufferWriterPointer> AsRef<T> for ReaderGuard<'_, T, P> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized, P: DoubleBufferWriterPointer> Borrow<T> for ReaderGuard<'_, T, P> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
 impl<'a, T: ?Sized, P: DoubleBufferWriterPointer> ReaderGuard<'a, T, P> {
     pub const fn extras(&self) -> &P::Extras {
         // SAFETY: extras is derived from a reference, which is bound to the lifetime
-        // 'a, so it is still valid.
+        // 'a, so it is still valid. And the extras are only mutated by `ExtrasMut` while
+        // there are no active read guards, so there can't be a race with the writer
         unsafe { self.extras.ptr.as_ref() }
     }
 
+    /// The generation of the buffer this guard is reading, if the strategy tracks it
+    ///
+    /// The generation is the number of swaps which happened before the buffer was published,
+    /// so if two guards from the same double buffer have the same generation then they saw the
+    /// same buffer, and the writer didn't swap in between. The generations seen by a single
+    /// reader never decrease.
+    ///
+    /// See [`Strategy::generation`] for details
+    #[inline]
+    pub const fn generation(&self) -> Option<u64> {
+        self.generation
+    }
+
+    /// Returns true if this guard is reading the buffer that readers see after an odd number of swaps
+    ///
+    /// This is the same for all guards which read the same physical buffer. This is mostly
+    /// useful for tests and debugging, to check that readers are reading the buffer you expect.
+    #[inline]
+    pub const fn is_swapped(&self) -> bool {
+        self.swapped
+    }
+
+    /// A raw pointer to the value this guard is reading
+    ///
+    /// Two guards from the same double buffer are reading the same physical buffer if their pointers
+    /// are equal, this is mostly useful for tests and debugging. If the guard was mapped,
+    /// then this points to the mapped value instead of the whole buffer.
+    ///
+    /// The pointer is only guaranteed to point to the value while the guard is alive. After the guard
+    /// is dropped, the writer may start writing to it, so it must not be dereferenced.
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut a = writer.reader();
+    /// let mut b = writer.reader();
+    ///
+    /// let (a, b) = (a.read(), b.read());
+    /// assert_eq!(a.as_ptr(), b.as_ptr());
+    /// assert_eq!(a.as_ptr(), writer.split().read as *const i32);
+    /// ```
+    #[inline]
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr.ptr.as_ptr()
+    }
+
+    /// Clone the buffer out of the guard
+    ///
+    /// The guard can be dropped right after this, so the writer doesn't have to wait on
+    /// this reader while you use the value. See [`Reader::read_cloned`]
+    #[inline]
+    pub fn to_owned(&self) -> T
+    where
+        T: Clone,
+    {
+        T::clone(self)
+    }
+
+    /// Get another guard into the same buffer, without acquiring another read guard
+    ///
+    /// The new guard borrows from `self`, so the buffer stays locked until both are dropped
+    #[inline]
+    pub const fn reborrow(&self) -> ReaderGuard<'_, T, P> {
+        ReaderGuard {
+            ptr: RawReference {
+                ptr: self.ptr.ptr,
+                lt: PhantomData,
+            },
+            extras: RawReference {
+                ptr: self.extras.ptr,
+                lt: PhantomData,
+            },
+            generation: self.generation,
+            swapped: self.swapped,
+            raw: None,
+        }
+    }
+
     /// Try to map the [`ReaderGuard`] to another value
     pub fn try_map<U: ?Sized, E>(
         self,
@@ -176,6 +774,8 @@ impl<'a, T: ?Sized, P: DoubleBufferWriterPointer> ReaderGuard<'a, T, P> {
                     lt: PhantomData,
                 },
                 extras: self.extras,
+                generation: self.generation,
+                swapped: self.swapped,
                 raw: self.raw,
             }),
             Err(err) => Err((self, err)),
@@ -202,6 +802,8 @@ impl<'a, T: ?Sized, P: DoubleBufferWriterPointer> ReaderGuard<'a, T, P> {
                     lt: PhantomData,
                 },
                 extras: self.extras,
+                generation: self.generation,
+                swapped: self.swapped,
                 raw: self.raw,
             }),
             Err(err) => Err((self, err)),
@@ -218,4 +820,316 @@ impl<'a, T: ?Sized, P: DoubleBufferWriterPointer> ReaderGuard<'a, T, P> {
             Err((_, err)) => match err {},
         }
     }
+
+    /// Start a chain of fallible projections, see [`Projector`]
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Missing {
+    ///     Outer,
+    ///     Inner,
+    /// }
+    ///
+    /// let mut data = DoubleBufferData::new(vec![vec![1, 2]], vec![], SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// let guard = reader.read()
+    ///     .project::<Missing>()
+    ///     .then(|outer| outer.first().ok_or(Missing::Outer))
+    ///     .then(|inner| inner.get(1).ok_or(Missing::Inner))
+    ///     .finish()
+    ///     .ok()
+    ///     .unwrap();
+    /// assert_eq!(*guard, 2);
+    /// drop(guard);
+    ///
+    /// let result = reader.read()
+    ///     .project::<Missing>()
+    ///     .then(|outer| outer.first().ok_or(Missing::Outer))
+    ///     .then(|inner| inner.get(2).ok_or(Missing::Inner))
+    ///     .finish();
+    /// let Err((guard, err)) = result else { unreachable!() };
+    /// // the original guard is returned on failure
+    /// assert_eq!(*guard, [vec![1, 2]]);
+    /// assert_eq!(err, Missing::Inner);
+    /// ```
+    #[inline]
+    pub const fn project<E>(self) -> Projector<'a, T, T, E, P> {
+        Projector {
+            value: Ok(self.ptr.ptr),
+            guard: self,
+        }
+    }
+}
+
+/// A chain of fallible projections of a [`ReaderGuard`], returned by [`ReaderGuard::project`]
+///
+/// Each step maps the current value with [`Projector::then`], and the first error short-circuits
+/// the rest of the chain. [`Projector::finish`] returns either the projected guard, or the
+/// original guard and the error, like [`ReaderGuard::try_map`].
+pub struct Projector<'a, T: ?Sized, U: ?Sized, E, P: DoubleBufferWriterPointer> {
+    guard: ReaderGuard<'a, T, P>,
+    // this always points into the value behind `guard`
+    value: Result<NonNull<U>, E>,
+}
+
+impl<'a, T: ?Sized, U: ?Sized, E, P: DoubleBufferWriterPointer> Projector<'a, T, U, E, P> {
+    /// Project the current value, if none of the previous steps failed
+    ///
+    /// The error of each step is converted into the error of the whole chain
+    #[inline]
+    pub fn then<V: ?Sized, F>(
+        self,
+        f: impl FnOnce(&U) -> Result<&V, F>,
+    ) -> Projector<'a, T, V, E, P>
+    where
+        F: Into<E>,
+    {
+        let value = match self.value {
+            // SAFETY: value points into the value behind self.guard, which is still alive
+            // so the writer doesn't have access to it
+            Ok(value) => f(unsafe { value.as_ref() })
+                .map(NonNull::from)
+                .map_err(Into::into),
+            Err(err) => Err(err),
+        };
+
+        Projector {
+            guard: self.guard,
+            value,
+        }
+    }
+
+    /// Finish the chain, and get the projected guard
+    ///
+    /// If any step failed, then the original guard and the error are returned
+    #[inline]
+    pub fn finish(self) -> Result<ReaderGuard<'a, U, P>, (ReaderGuard<'a, T, P>, E)> {
+        match self.value {
+            Ok(value) => Ok(ReaderGuard {
+                ptr: RawReference {
+                    ptr: value,
+                    lt: PhantomData,
+                },
+                extras: self.guard.extras,
+                generation: self.guard.generation,
+                swapped: self.guard.swapped,
+                raw: self.guard.raw,
+            }),
+            Err(err) => Err((self.guard, err)),
+        }
+    }
+}
+
+/// An owned guard into the double buffer, returned by [`Reader::read_map_owned`]
+///
+/// As long as this guard is alive, the writer cannot write to the corresponding buffer.
+pub struct OwnedReaderGuard<U: ?Sized, P: DoubleBufferReaderPointer> {
+    value: NonNull<U>,
+    guard: ManuallyDrop<iface::ReaderGuard<P::Strategy>>,
+    // the reader id must be dropped before the writer, since the writer keeps the strategy alive
+    id: ReaderId<P::Strategy>,
+    writer: P::Writer,
+}
+
+// SAFETY: OwnedReaderGuard is semantically a `&U`, together with the reader registration
+// and the pointer which keeps the buffers alive
+unsafe impl<U: ?Sized, P: DoubleBufferReaderPointer> Send for OwnedReaderGuard<U, P>
+where
+    U: Sync,
+    P::Writer: Send,
+    ReaderId<P::Strategy>: Send,
+    iface::ReaderGuard<P::Strategy>: Send,
+{
+}
+
+// SAFETY: OwnedReaderGuard is semantically a `&U`, together with the reader registration
+// and the pointer which keeps the buffers alive. Only `&U` can be accessed through a shared reference
+unsafe impl<U: ?Sized, P: DoubleBufferReaderPointer> Sync for OwnedReaderGuard<U, P>
+where
+    U: Sync,
+    P::Writer: Sync,
+    ReaderId<P::Strategy>: Sync,
+    iface::ReaderGuard<P::Strategy>: Sync,
+{
+}
+
+impl<U: ?Sized, P: DoubleBufferReaderPointer> OwnedReaderGuard<U, P> {
+    /// Get an extra data stored along-side the buffers
+    #[inline]
+    pub fn extras(&self) -> &P::Extras {
+        // SAFETY: the extras are only mutated by `ExtrasMut` while
+        // there are no active read guards, so there can't be a race with the writer
+        unsafe { &*self.writer.extras.get() }
+    }
+
+    /// Map the [`OwnedReaderGuard`] to another value
+    pub fn map<V: ?Sized>(self, f: impl for<'t> FnOnce(&'t U) -> &'t V) -> OwnedReaderGuard<V, P> {
+        match self.try_map::<_, core::convert::Infallible>(move |t| Ok(f(t))) {
+            Ok(guard) => guard,
+            Err((_, err)) => match err {},
+        }
+    }
+
+    /// Try to map the [`OwnedReaderGuard`] to another value
+    ///
+    /// If `f` fails, then the guard is returned along with the error
+    pub fn try_map<V: ?Sized, E>(
+        self,
+        f: impl for<'t> FnOnce(&'t U) -> Result<&'t V, E>,
+    ) -> Result<OwnedReaderGuard<V, P>, (Self, E)> {
+        let value = match f(&self) {
+            Ok(value) => NonNull::from(value),
+            Err(err) => return Err((self, err)),
+        };
+
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: this is never used or dropped again, so all of the fields are moved
+        // exactly once into the new guard
+        Ok(unsafe {
+            OwnedReaderGuard {
+                value,
+                guard: core::ptr::read(&this.guard),
+                id: core::ptr::read(&this.id),
+                writer: core::ptr::read(&this.writer),
+            }
+        })
+    }
+}
+
+impl<U: ?Sized, P: DoubleBufferReaderPointer> ops::Deref for OwnedReaderGuard<U, P> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: self.guard ensures that the writer doesn't have access to self.value
+        // so there is no race with the writer, and readers cannot race with each other
+        // self.writer keeps the buffers alive
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<U: ?Sized, P: DoubleBufferReaderPointer> Drop for OwnedReaderGuard<U, P> {
+    fn drop(&mut self) {
+        // SAFETY: self.guard isn't dropped before this
+        let guard = unsafe { ManuallyDrop::take(&mut self.guard) };
+        // SAFETY: the reader id was created from the strategy behind self.writer, which
+        // keeps the strategy alive
+        unsafe { self.writer.strategy.release_read_guard(&mut self.id, guard) }
+    }
+}
+
+/// A projection from the extras of a double buffer to a part of the extras
+///
+/// This is implemented on a (usually zero-sized) marker type, which is then used as
+/// the type parameter of [`ProjectedReader`]. The projection is run every time a read guard
+/// is acquired, so it should be a cheap field access.
+pub trait ExtrasProjection<Extras: ?Sized> {
+    /// The projected part of the extras
+    type Output: ?Sized;
+
+    /// Project the extras
+    fn project(extras: &Extras) -> &Self::Output;
+}
+
+/// A reader which only has access to a part of the extras, created by [`Reader::map_pointer`]
+pub struct ProjectedReader<P: DoubleBufferReaderPointer, F> {
+    reader: Reader<P>,
+    projection: PhantomData<fn() -> F>,
+}
+
+/// A guard into the double buffer, which only has access to a part of the extras
+///
+/// This is returned by [`ProjectedReader::try_read`]
+pub struct ProjectedReaderGuard<'a, T: ?Sized, P: DoubleBufferWriterPointer, E: ?Sized> {
+    guard: ReaderGuard<'a, T, P>,
+    extras: RawReference<'a, E>,
+}
+
+impl<P: DoubleBufferReaderPointer, F> Clone for ProjectedReader<P, F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            projection: PhantomData,
+        }
+    }
+}
+
+impl<P: DoubleBufferReaderPointer, F: ExtrasProjection<P::Extras>> ProjectedReader<P, F> {
+    /// Get the underlying reader
+    #[inline]
+    pub const fn get_ref(&self) -> &Reader<P> {
+        &self.reader
+    }
+
+    /// Get the underlying reader, which has access to all of the extras
+    #[inline]
+    pub fn into_inner(self) -> Reader<P> {
+        self.reader
+    }
+
+    /// Try to access the read buffer, if it fails then returns an error
+    ///
+    /// see the pointer's docs for when upgrading the pointer can fail
+    #[allow(clippy::type_complexity)]
+    pub fn try_read(
+        &mut self,
+    ) -> Result<ProjectedReaderGuard<'_, P::Buffer, P::Writer, F::Output>, P::UpgradeError> {
+        let guard = self.reader.try_read()?;
+        let extras = RawReference {
+            ptr: NonNull::from(F::project(guard.extras())),
+            lt: PhantomData,
+        };
+        Ok(ProjectedReaderGuard { guard, extras })
+    }
+
+    /// Try to access the read buffer
+    ///
+    /// # Panic
+    ///
+    /// If upgrading the pointer fails, this will panic
+    pub fn read(&mut self) -> ProjectedReaderGuard<'_, P::Buffer, P::Writer, F::Output>
+    where
+        P::UpgradeError: core::fmt::Debug,
+    {
+        match self.try_read() {
+            Ok(guard) => guard,
+            Err(err) => read_failed(&err),
+        }
+    }
+}
+
+impl<T: ?Sized, P: DoubleBufferWriterPointer, E: ?Sized> ops::Deref
+    for ProjectedReaderGuard<'_, T, P, E>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<'a, T: ?Sized, P: DoubleBufferWriterPointer, E: ?Sized> ProjectedReaderGuard<'a, T, P, E> {
+    /// Get the projected part of the extras
+    #[inline]
+    pub const fn extras(&self) -> &E {
+        // SAFETY: extras was projected from the extras of self.guard, which keeps them alive
+        // and ensures that there can't be a race with the writer
+        unsafe { self.extras.ptr.as_ref() }
+    }
+
+    /// Map the [`ProjectedReaderGuard`] to another value
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&T) -> &U) -> ProjectedReaderGuard<'a, U, P, E> {
+        ProjectedReaderGuard {
+            guard: self.guard.map(f),
+            extras: self.extras,
+        }
+    }
 }
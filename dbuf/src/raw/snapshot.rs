@@ -0,0 +1,57 @@
+use core::fmt;
+
+use serde::Serialize;
+
+use crate::interface::DoubleBufferReaderPointer;
+
+use super::Reader;
+
+/// The error returned by [`Reader::encode_snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeSnapshotError<E> {
+    /// Upgrading the reader's pointer failed, so there was nothing to encode
+    Upgrade(E),
+    /// Serializing the read buffer failed
+    Encode(postcard::Error),
+}
+
+impl<E: fmt::Display> fmt::Display for EncodeSnapshotError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upgrade(err) => write!(f, "could not read the buffer: {err}"),
+            Self::Encode(err) => write!(f, "could not encode the buffer: {err}"),
+        }
+    }
+}
+
+impl<P: DoubleBufferReaderPointer> Reader<P>
+where
+    P::Buffer: Serialize,
+{
+    /// Serialize the read buffer into `buf` with [`postcard`], returning the used part of `buf`
+    ///
+    /// The read guard is held for the entire encode, so the encoded bytes always come from a
+    /// single consistent snapshot of the buffer. This also means that the writer can't finish
+    /// a swap until the encode is done, so encoding a large buffer will block swaps for a while.
+    /// If that's a problem, clone the buffer out of the guard, and encode the clone instead.
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::from_clone([1u8, 2, 3], SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// let mut buf = [0; 16];
+    /// let encoded = reader.encode_snapshot(&mut buf).unwrap();
+    /// assert_eq!(postcard::from_bytes::<[u8; 3]>(encoded).unwrap(), [1, 2, 3]);
+    /// ```
+    pub fn encode_snapshot<'b>(
+        &mut self,
+        buf: &'b mut [u8],
+    ) -> Result<&'b mut [u8], EncodeSnapshotError<P::UpgradeError>> {
+        let guard = self.try_read().map_err(EncodeSnapshotError::Upgrade)?;
+        postcard::to_slice(&*guard, buf).map_err(EncodeSnapshotError::Encode)
+    }
+}
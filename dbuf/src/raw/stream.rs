@@ -0,0 +1,87 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::interface::{DoubleBufferReaderPointer, NotifyReaders};
+
+use super::Reader;
+
+/// A stream of snapshots of the read buffer, returned by [`Reader::into_snapshot_stream`]
+///
+/// The first poll yields the current read buffer, and after that each poll waits until the
+/// writer swaps the buffers before yielding a clone of the new read buffer.
+///
+/// # Backpressure
+///
+/// Swaps are never queued up. If the writer swaps the buffers multiple times between polls,
+/// then the stream only yields the latest read buffer, so a slow consumer skips over the
+/// intermediate buffers.
+///
+/// The stream ends once the reader can no longer upgrade its pointer to the buffers.
+pub struct SnapshotStream<P: DoubleBufferReaderPointer> {
+    reader: Reader<P>,
+    started: bool,
+}
+
+// the reader is never pinned
+impl<P: DoubleBufferReaderPointer> Unpin for SnapshotStream<P> {}
+
+impl<P: DoubleBufferReaderPointer> Reader<P>
+where
+    P::Strategy: NotifyReaders,
+    P::Buffer: Clone,
+{
+    /// Convert this reader into a stream of snapshots of the read buffer
+    ///
+    /// see [`SnapshotStream`] for details
+    pub const fn into_snapshot_stream(self) -> SnapshotStream<P> {
+        SnapshotStream {
+            reader: self,
+            started: false,
+        }
+    }
+}
+
+impl<P: DoubleBufferReaderPointer> SnapshotStream<P> {
+    /// Get the underlying reader
+    pub const fn get_ref(&self) -> &Reader<P> {
+        &self.reader
+    }
+
+    /// Convert this stream back into the underlying reader
+    pub fn into_reader(self) -> Reader<P> {
+        self.reader
+    }
+}
+
+impl<P: DoubleBufferReaderPointer> Stream for SnapshotStream<P>
+where
+    P::Strategy: NotifyReaders,
+    P::Buffer: Clone,
+{
+    type Item = P::Buffer;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.started {
+            match this.reader.poll_swap(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(_)) => return Poll::Ready(None),
+                Poll::Ready(Ok(())) => (),
+            }
+        }
+
+        this.started = true;
+
+        // reading marks every swap up to this point as seen, which coalesces
+        // all swaps since the last poll into this one snapshot
+        Poll::Ready(match this.reader.try_read() {
+            Ok(guard) => Some(P::Buffer::clone(&guard)),
+            Err(_) => None,
+        })
+    }
+}
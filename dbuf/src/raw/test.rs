@@ -0,0 +1,215 @@
+#![cfg(all(feature = "std", not(loom)))]
+
+use crate::{
+    interface::{
+        BlockingStrategy, DoubleBufferReaderPointer, DoubleBufferWriterPointer, SwapError,
+    },
+    raw::{DoubleBufferData, Reader, Writer},
+};
+
+const READERS: usize = 3;
+const SWAPS: u64 = 200;
+
+type Buffer = [u64; 16];
+
+/// Readers on other threads must only ever see fully written buffers,
+/// and must see the swaps in the order they were published
+fn cross_thread<P>(mut writer: Writer<P>)
+where
+    P: DoubleBufferWriterPointer<Buffer = Buffer>,
+    P::Strategy: BlockingStrategy,
+    <P::Reader as DoubleBufferReaderPointer>::UpgradeError: core::fmt::Debug,
+    SwapError<P::Strategy>: core::fmt::Debug,
+    Reader<P::Reader>: Send,
+{
+    std::thread::scope(|scope| {
+        for _ in 0..READERS {
+            let mut reader = writer.reader();
+            scope.spawn(move || {
+                let mut last = 0;
+                while last != SWAPS {
+                    let buffer = reader.read();
+                    let value = buffer[0];
+                    assert!(
+                        buffer.iter().all(|&x| x == value),
+                        "torn read: {:?}",
+                        *buffer
+                    );
+                    assert!(last <= value, "went back from {last} to {value}");
+                    last = value;
+                }
+            });
+        }
+
+        for i in 1..=SWAPS {
+            *writer.get_mut() = [i; 16];
+            writer.swap();
+        }
+    });
+}
+
+fn with_ref<S>(strategy: S)
+where
+    S: BlockingStrategy + Sync,
+    S::ReaderId: Send,
+    SwapError<S>: core::fmt::Debug,
+{
+    let mut state = DoubleBufferData::new([0; 16], [0; 16], strategy);
+    cross_thread(Writer::new(&mut state));
+}
+
+fn with_arc<S>(strategy: S)
+where
+    S: BlockingStrategy + Send + Sync,
+    S::ReaderId: Send,
+    SwapError<S>: core::fmt::Debug,
+{
+    let state = rc_box::ArcBox::new(DoubleBufferData::new([0; 16], [0; 16], strategy));
+    cross_thread(Writer::new(state));
+}
+
+#[cfg(feature = "triomphe")]
+fn with_triomphe<S>(strategy: S)
+where
+    S: BlockingStrategy + Send + Sync,
+    S::ReaderId: Send,
+    SwapError<S>: core::fmt::Debug,
+{
+    let state = triomphe::UniqueArc::new(DoubleBufferData::new([0; 16], [0; 16], strategy));
+    cross_thread(Writer::new(state));
+}
+
+macro_rules! cross_thread_tests {
+    ($($(#[$meta:meta])* $name:ident => $strategy:expr,)*) => {$(
+        $(#[$meta])*
+        mod $name {
+            #[test]
+            fn reference() {
+                super::with_ref($strategy)
+            }
+
+            #[test]
+            fn std_arc() {
+                super::with_arc($strategy)
+            }
+
+            #[test]
+            #[cfg(feature = "triomphe")]
+            fn triomphe() {
+                super::with_triomphe($strategy)
+            }
+        }
+    )*};
+}
+
+cross_thread_tests! {
+    atomic => crate::strategy::atomic::AtomicStrategy::new_blocking(),
+    #[cfg(feature = "triomphe")]
+    flashmap => crate::strategy::flashmap::FlashStrategy::new_blocking(),
+    #[cfg(feature = "triomphe")]
+    evmap => crate::strategy::evmap::EvMapStrategy::new(),
+    hazard_flash => crate::strategy::hazad_flash::HazardFlashStrategy::new_blocking(),
+    hazard_evmap => crate::strategy::hazard_evmap::HazardEvMapStrategy::new_blocking(),
+    static_slots => crate::strategy::static_slots::StaticStrategy::<4, _>::new_blocking(),
+    strict => crate::strategy::strict::StrictStrategy::new(
+        crate::strategy::hazad_flash::HazardFlashStrategy::new_blocking(),
+    ),
+    instrument => crate::strategy::instrument::InstrumentedStrategy::new(
+        crate::strategy::hazad_flash::HazardFlashStrategy::new_blocking(),
+    ),
+    notify_readers => crate::strategy::notify_readers::NotifyReadersStrategy::new(
+        crate::strategy::hazad_flash::HazardFlashStrategy::new_blocking(),
+    ),
+    journal => crate::strategy::journal::JournaledStrategy::new(
+        crate::strategy::hazad_flash::HazardFlashStrategy::new_blocking(),
+        alloc::vec::Vec::new(),
+    ),
+}
+
+#[test]
+fn wait_buffer_empty() {
+    use crate::{interface::BufferSide, strategy::simple::SimpleStrategy};
+    use core::time::Duration;
+
+    let mut state = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    let guard = reader.read();
+    assert!(!writer.is_buffer_empty(BufferSide::Read));
+    assert!(writer.is_buffer_empty(BufferSide::Write));
+
+    // the reader is on this thread, so it can't leave the buffer while the writer waits
+    assert!(!writer.wait_buffer_empty(BufferSide::Read, Duration::from_millis(10)));
+    assert!(writer.wait_buffer_empty(BufferSide::Write, Duration::from_millis(10)));
+
+    drop(guard);
+    assert!(writer.wait_buffer_empty(BufferSide::Read, Duration::ZERO));
+}
+
+#[test]
+fn wait_buffer_empty_cross_thread() {
+    use crate::{interface::BufferSide, strategy::atomic::AtomicStrategy};
+    use core::time::Duration;
+    use std::sync::mpsc;
+
+    let mut state = DoubleBufferData::new(0, 1, AtomicStrategy::new_blocking());
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    let (started, start) = mpsc::channel();
+    let (released, release) = mpsc::channel::<()>();
+
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            let guard = reader.read();
+            started.send(()).unwrap();
+            release.recv().unwrap();
+            drop(guard);
+        });
+
+        start.recv().unwrap();
+        assert!(!writer.is_buffer_empty(BufferSide::Read));
+
+        released.send(()).unwrap();
+        assert!(writer.wait_buffer_empty(BufferSide::Read, Duration::from_secs(60)));
+    });
+}
+
+#[test]
+fn shared_writer() {
+    use crate::{raw::SharedWriter, strategy::hazad_flash::HazardFlashStrategy};
+
+    const THREADS: usize = 4;
+    const ITERS: usize = 100;
+
+    let mut state = DoubleBufferData::new(0, 0, HazardFlashStrategy::new_blocking());
+    let writer = SharedWriter::new(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    std::thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let mut reader = writer.reader();
+
+                for _ in 0..ITERS {
+                    let before = *reader.read();
+
+                    let mut writer = writer.lock();
+                    *writer.get_mut() += 1;
+                    writer.swap();
+                    *writer.get_mut() += 1;
+                    drop(writer);
+
+                    assert!(*reader.read() > before);
+                }
+            });
+        }
+    });
+
+    assert_eq!(*reader.read(), THREADS * ITERS);
+    assert!(writer.try_lock().is_some());
+
+    let mut writer = writer.into_inner();
+    assert_eq!(*writer.get_mut(), THREADS * ITERS);
+}
@@ -1,6 +1,7 @@
 use crate::interface::{
-    self as iface, AsyncStrategy, BlockingStrategy, DoubleBufferWriterPointer,
-    IntoDoubleBufferWriterPointer, Strategy, WriterId,
+    self as iface, create_invalid_reader_id, AsyncStrategy, BlockingStrategy, BufferOccupancy,
+    BufferSide, DoubleBufferWriterPointer, IntoDoubleBufferWriterPointer, IntoInnerWriterPointer,
+    ReaderLock, Strategy, WriterId,
 };
 
 use super::{reader::Reader, Split, SplitMut};
@@ -15,6 +16,20 @@ pub struct Writer<
 > {
     id: WriterId<S>,
     ptr: P,
+    // the number of swaps started by this writer, see `Writer::swap_generation`
+    swaps: u64,
+}
+
+impl<P: DoubleBufferWriterPointer> core::fmt::Debug for Writer<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // SAFETY: the writer id is valid
+        let is_swapped = unsafe { self.ptr.strategy.is_swapped_writer(&self.id) };
+
+        f.debug_struct("Writer")
+            .field("is_swapped", &is_swapped)
+            .field("swap_generation", &self.swaps)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Create a new [`Writer`]
@@ -24,7 +39,7 @@ pub fn new_writer<T: IntoDoubleBufferWriterPointer>(mut ptr: T) -> Writer<T::Wri
     let id = unsafe { ptr.strategy.create_writer_id() };
     let ptr = ptr.into_writer();
 
-    Writer { id, ptr }
+    Writer { id, ptr, swaps: 0 }
 }
 
 impl<P: DoubleBufferWriterPointer> Writer<P> {
@@ -33,12 +48,238 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
         new_writer(ptr)
     }
 
+    /// Create a writer from a writer id and pointer, which were taken from [`Self::into_raw_parts`]
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let writer = Writer::new(&mut data);
+    ///
+    /// let (id, ptr) = writer.into_raw_parts();
+    /// // SAFETY: the id and pointer came from the same writer
+    /// let mut writer = unsafe { Writer::from_raw_parts(id, ptr) };
+    /// assert_eq!(*writer.get_mut(), 1);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// * the id must be valid for the strategy behind `ptr`, and it must be the only valid
+    ///   writer id for that strategy (so it must have been taken from a writer of the same buffers)
+    /// * the pointer must have exclusive write access to the buffers, so there must be no other writer for
+    ///   these buffers (i.e. no other writer id may be used with these buffers while this writer is alive)
+    /// * the id must be dropped before `ptr` (the writer ensures that, as long as it isn't torn down again)
+    ///
+    /// The [`Self::swap_generation`] of the new writer starts at zero
+    #[inline]
+    pub const unsafe fn from_raw_parts(id: WriterId<P::Strategy>, ptr: P) -> Self {
+        Self { id, ptr, swaps: 0 }
+    }
+
+    /// Split this writer into it's writer id and pointer
+    ///
+    /// The returned id should be dropped before the pointer, see [`Self::from_raw_parts`]
+    /// for how to put the writer back together.
+    #[inline]
+    pub fn into_raw_parts(self) -> (WriterId<P::Strategy>, P) {
+        (self.id, self.ptr)
+    }
+
     /// Create a new reader that points to the same buffers as this writer
     pub fn reader(&self) -> Reader<P::Reader> {
-        // SAFETY: the writer id is valid
-        let id = unsafe { self.ptr.strategy.create_reader_id_from_writer(&self.id) };
-        // SAFETY: the reader id was just created, so it's valid
-        unsafe { Reader::from_raw_parts(id, self.ptr.reader()) }
+        let ptr = self.ptr.reader();
+
+        // some pointers may not be able to hand out another live reader pointer,
+        // in which case the reader must get an invalid reader id
+        let id = if P::is_dangling_reader(&ptr) {
+            create_invalid_reader_id::<P::Strategy>()
+        } else {
+            // SAFETY: the writer id is valid
+            unsafe { self.ptr.strategy.create_reader_id_from_writer(&self.id) }
+        };
+
+        // SAFETY: id is valid for the strategy inside ptr
+        // or the ptr is dead and the reader id is invalid
+        unsafe { Reader::from_raw_parts(id, ptr) }
+    }
+
+    /// Give up write access, and convert this writer into a reader of the same buffers
+    ///
+    /// Note that if the reader pointer doesn't keep the buffers alive (for example, the
+    /// reader pointer of an `Arc` is a `Weak`), then the returned reader can only read
+    /// while some other pointer keeps the buffers alive.
+    pub fn into_reader(self) -> Reader<P::Reader> {
+        let reader = self.reader();
+
+        let Self { id, ptr, .. } = self;
+        // the writer id must be dropped before the pointer, since the pointer may
+        // be keeping the strategy alive
+        drop(id);
+        drop(ptr);
+
+        reader
+    }
+
+    /// Map both buffers to a new type, and create a writer for the mapped buffers
+    ///
+    /// This only succeeds if the writer is the only pointer to the buffers, so there must not
+    /// be any readers left (including readers which can no longer read, like
+    /// [`Weak`](alloc::sync::Weak) readers). Otherwise the writer is returned back.
+    ///
+    /// The mapped buffers are moved into a new allocation, create new readers from the returned
+    /// writer. The strategy and extras are moved over as is, and [`Self::swap_generation`] is kept.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use std::sync::Arc;
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    /// use dbuf::rc_box::ArcBox;
+    ///
+    /// let mut writer: Writer<Arc<DoubleBufferData<Vec<u8>, SimpleStrategy>>> =
+    ///     Writer::new(ArcBox::new(DoubleBufferData::new(vec![1], vec![2], SimpleStrategy::new())));
+    /// let mut old = writer.reader();
+    /// writer.swap();
+    ///
+    /// assert_eq!(*old.read(), [2]);
+    /// // the old reader still exists, so the buffers can't be mapped
+    /// let writer = writer.try_map(|buf| buf.len()).unwrap_err();
+    /// drop(old);
+    ///
+    /// let mut writer = writer.try_map(|buf| buf.len() + usize::from(buf[0])).unwrap();
+    /// let mut reader = writer.reader();
+    /// assert_eq!(*reader.read(), 3);
+    /// assert_eq!(*writer.get(), 2);
+    /// # }
+    /// ```
+    pub fn try_map<U>(
+        self,
+        f: impl FnMut(P::Buffer) -> U,
+    ) -> Result<Writer<<P::Unique<U> as IntoDoubleBufferWriterPointer>::Writer>, Self>
+    where
+        P: IntoInnerWriterPointer,
+    {
+        let Self { id, ptr, swaps } = self;
+
+        let data = match ptr.try_into_inner() {
+            Ok(data) => data,
+            Err(ptr) => return Err(Self { id, ptr, swaps }),
+        };
+
+        // the writer id must be dropped before the strategy, and it's replaced by
+        // the writer id of the new writer
+        drop(id);
+
+        let mut writer = Writer::new(P::new_unique(data.map(f)));
+        writer.swaps = swaps;
+        Ok(writer)
+    }
+
+    /// Map both buffers to a new type, and create a writer for the mapped buffers
+    ///
+    /// see [`Self::try_map`] for details
+    ///
+    /// # Panics
+    ///
+    /// If the writer isn't the only pointer to the buffers
+    pub fn map<U>(
+        self,
+        f: impl FnMut(P::Buffer) -> U,
+    ) -> Writer<<P::Unique<U> as IntoDoubleBufferWriterPointer>::Writer>
+    where
+        P: IntoInnerWriterPointer,
+    {
+        match self.try_map(f) {
+            Ok(writer) => writer,
+            Err(_) => panic!("tried to map the buffers while they are shared"),
+        }
+    }
+
+    /// The number of swaps which were successfully started by this writer
+    ///
+    /// This is counted by the writer, and starts at zero when the writer is created. So it's
+    /// useful to correlate logs across the writer and readers, even with strategies which don't
+    /// track [`ReaderGuard::generation`](super::ReaderGuard::generation).
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// assert_eq!(writer.swap_generation(), 0);
+    ///
+    /// writer.swap();
+    /// writer.swap();
+    /// assert_eq!(writer.swap_generation(), 2);
+    ///
+    /// let mut reader = writer.reader();
+    /// let guard = reader.read();
+    /// // the swap fails, so it isn't counted
+    /// assert!(writer.try_swap().is_err());
+    /// assert_eq!(writer.swap_generation(), 2);
+    /// ```
+    #[inline]
+    pub const fn swap_generation(&self) -> u64 {
+        self.swaps
+    }
+
+    /// An estimate of how many readers exist for these buffers, if the strategy tracks it
+    ///
+    /// This is racy, since readers may be created or dropped on other threads at any time.
+    /// So it's only a hint, for example to avoid publishing while there is a huge fan-out
+    /// of readers. see [`Strategy::estimated_reader_count`] for details
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "std", feature = "triomphe"))]
+    /// # {
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::flashmap::FlashStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, FlashStrategy::new_blocking());
+    /// let writer = Writer::new(&mut data);
+    /// let a = writer.reader();
+    /// let b = a.clone();
+    /// assert_eq!(writer.estimated_reader_count(), Some(2));
+    ///
+    /// drop((a, b));
+    /// assert_eq!(writer.estimated_reader_count(), Some(0));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn estimated_reader_count(&self) -> Option<usize> {
+        // SAFETY: the writer id is valid (invariant of Self)
+        unsafe { self.ptr.strategy.estimated_reader_count(&self.id) }
+    }
+
+    /// Check if the latest swap is finished, without access to the swap
+    ///
+    /// Unlike [`Self::is_swap_finished`], this only needs a shared reference. Returns true
+    /// if no swap was started. Returns [`None`] if the strategy can't check this without the swap,
+    /// see [`Strategy::is_swap_finished_shared`] for details
+    ///
+    /// ```rust
+    /// use dbuf::delay::DelayWriter;
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple_async::SimpleAsyncStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleAsyncStrategy::new());
+    /// let mut writer = DelayWriter::from_writer(Writer::new(&mut data));
+    /// let mut reader = writer.reader();
+    ///
+    /// let guard = reader.read();
+    /// writer.start_swap();
+    /// assert_eq!(writer.peek_swap_finished(), Some(false));
+    ///
+    /// drop(guard);
+    /// assert_eq!(writer.peek_swap_finished(), Some(true));
+    /// ```
+    #[inline]
+    pub fn peek_swap_finished(&self) -> Option<bool> {
+        // SAFETY: the writer id is valid (invariant of Self)
+        unsafe { self.ptr.strategy.is_swap_finished_shared(&self.id) }
     }
 
     /// Get a shared reference to the writer half of the double buffer
@@ -56,12 +297,31 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
     /// Get an extra data stored along-side the buffers
     #[inline]
     pub fn extras(&self) -> &P::Extras {
-        &self.ptr.extras
+        // SAFETY: the extras are only mutated through `ExtrasMut`, which holds
+        // an exclusive reference to self
+        unsafe { &*self.ptr.extras.get() }
+    }
+
+    /// Try to get exclusive access to the extras
+    ///
+    /// This locks out all readers until the returned guard is dropped, so this
+    /// fails if there are any active reads. While the guard is alive, any new read will
+    /// wait until the lock is released.
+    pub fn try_extras_mut(&mut self) -> Option<ExtrasMut<'_, P>>
+    where
+        P::Strategy: ReaderLock,
+    {
+        // SAFETY: the writer id is valid (invariant of Self)
+        if unsafe { self.ptr.strategy.try_lock_readers(&mut self.id) } {
+            Some(ExtrasMut { writer: self })
+        } else {
+            None
+        }
     }
 
     /// Get shared references to both buffers
     #[inline]
-    pub fn split(&self) -> Split<P::Buffer, P::Extras> {
+    pub fn split(&self) -> Split<'_, P::Buffer, P::Extras> {
         let dbuf = &*self.ptr;
 
         // SAFETY: self.id is valid (invariant of Self)
@@ -75,15 +335,32 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
             Split {
                 read: &*read,
                 write: &*write,
-                extras: &dbuf.extras,
+                // SAFETY: the extras are only mutated through `ExtrasMut`, which holds
+                // an exclusive reference to self
+                extras: &*dbuf.extras.get(),
             }
         }
     }
 
+    /// Format both buffers independently, returns `(read, write)`
+    ///
+    /// This is useful for debugging, to compare the two buffers side by side
+    #[cfg(feature = "alloc")]
+    pub fn dump_both(&self) -> (alloc::string::String, alloc::string::String)
+    where
+        P::Buffer: core::fmt::Debug,
+    {
+        let split = self.split();
+        (
+            alloc::format!("{:?}", split.read),
+            alloc::format!("{:?}", split.write),
+        )
+    }
+
     /// Get a shared reference to the reader-half and an exclusive reference to the writer half of
     /// the buffers
     #[inline]
-    pub fn split_mut(&mut self) -> SplitMut<P::Buffer, P::Extras> {
+    pub fn split_mut(&mut self) -> SplitMut<'_, P::Buffer, P::Extras> {
         let dbuf = &*self.ptr;
 
         // SAFETY: self.id is valid (invariant of Self)
@@ -99,11 +376,36 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
             SplitMut {
                 read: &*read,
                 write: &mut *write,
-                extras: &dbuf.extras,
+                // SAFETY: the extras are only mutated through `ExtrasMut`, which holds
+                // an exclusive reference to self
+                extras: &*dbuf.extras.get(),
             }
         }
     }
 
+    /// Get exclusive references to both buffers, returns `(read, write)`
+    ///
+    /// This only succeeds if the strategy can prove that there are no readers, for example
+    /// before the first reader was created or after all readers were dropped.
+    /// See [`Strategy::no_active_readers`] for details
+    pub fn try_get_both_mut(&mut self) -> Option<(&mut P::Buffer, &mut P::Buffer)> {
+        let dbuf = &*self.ptr;
+
+        // SAFETY: self.id is valid (invariant of Self)
+        if !unsafe { dbuf.strategy.no_active_readers(&self.id) } {
+            return None;
+        }
+
+        // SAFETY: self.id is valid (invariant of Self)
+        let swapped = unsafe { dbuf.strategy.is_swapped_writer(&self.id) };
+
+        let (read, write) = dbuf.buffers.get(swapped);
+
+        // SAFETY: there are no readers, and no new readers can be created while
+        // self is borrowed, so no one else can access either buffer
+        unsafe { Some((&mut *read.cast_mut(), &mut *write)) }
+    }
+
     /// Try to swap the buffers, if the swap fails returns an error
     ///
     /// See the underlying strategy for details on when this may fail
@@ -121,6 +423,38 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
         Ok(())
     }
 
+    /// Try to swap the buffers, and report how much the swap had to wait on readers
+    ///
+    /// This behaves like [`Self::try_swap`]
+    ///
+    /// See the underlying strategy for details on when this may fail
+    pub fn try_swap_report(&mut self) -> Result<SwapReport, iface::SwapError<P::Strategy>>
+    where
+        P::Strategy: BlockingStrategy,
+    {
+        // SAFETY: there are no calls to split_mut or get_mut in this function
+        // and we call finish_swap before returning, and any panic before that
+        // is converted to an abort by NoUnwind, so there are no code paths,
+        // including panic code paths which can lead to a call to split_mut
+        // or get_mut without finish_swap completing
+        let mut swap = unsafe { self.try_start_swap()? };
+        let no_unwind = NoUnwind;
+
+        // SAFETY: the swap is the latest swap
+        let residual_readers = unsafe { self.ptr.strategy.residual_readers(&self.id, &swap) };
+        // SAFETY: the swap is the latest swap
+        let blocked = !unsafe { self.is_swap_finished(&mut swap) };
+        // SAFETY: the swap is the latest swap
+        unsafe { self.finish_swap(swap) }
+
+        core::mem::forget(no_unwind);
+
+        Ok(SwapReport {
+            blocked,
+            residual_readers,
+        })
+    }
+
     /// Try to swap the buffers
     ///
     /// # Panics
@@ -142,6 +476,186 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
         }
     }
 
+    /// Swap the buffers, and return the buffer that readers now see
+    ///
+    /// This is the same as calling [`Self::swap`] and then [`Self::split`]. The returned
+    /// buffer borrows the writer, so it stays the read buffer for as long as it's alive.
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    ///
+    /// *writer.get_mut() = 10;
+    /// assert_eq!(*writer.swap_and_read(), 10);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the buffer swap fails for some reason, then this function will panic
+    pub fn swap_and_read(&mut self) -> &P::Buffer
+    where
+        P::Strategy: BlockingStrategy,
+        iface::SwapError<P::Strategy>: core::fmt::Debug,
+    {
+        self.swap();
+        self.split().read
+    }
+
+    /// Swap the buffers exactly twice, so that both buffers were published
+    ///
+    /// Each swap only finishes once no reader is left in the buffer that becomes the write
+    /// buffer, so after the first swap no reader is in the original read buffer, and after the
+    /// second swap no reader is in the original write buffer. So every read guard which was
+    /// acquired before this call was released by the time this returns, and the writer is back
+    /// on the same physical buffer that it started on. [`Self::swap_generation`] goes up by two.
+    ///
+    /// Swapping doesn't copy anything between the buffers, so readers see the same buffer as
+    /// before this call. If every write is applied to both buffers (for example with
+    /// [`OpWriter`](crate::op::OpWriter), which replays operations on the other buffer after
+    /// each swap), then this brings both buffers up to date with the latest writes.
+    ///
+    /// If the first swap fails, then the buffers aren't swapped. If the second swap fails,
+    /// then the buffers stay swapped once, and the error is returned.
+    /// See the underlying strategy for details on when this may fail
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// *writer.get_mut() = 10;
+    /// writer.try_flush_both().unwrap();
+    /// assert_eq!(writer.swap_generation(), 2);
+    /// assert_eq!(*writer.get(), 10);
+    /// assert_eq!(*reader.read(), 0);
+    /// ```
+    pub fn try_flush_both(&mut self) -> Result<(), iface::SwapError<P::Strategy>>
+    where
+        P::Strategy: BlockingStrategy,
+    {
+        self.try_swap()?;
+        self.try_swap()
+    }
+
+    /// Swap the buffers exactly twice, so that both buffers were published
+    ///
+    /// see [`Self::try_flush_both`] for details
+    ///
+    /// # Panics
+    ///
+    /// If either buffer swap fails for some reason, then this function will panic
+    pub fn flush_both(&mut self)
+    where
+        P::Strategy: BlockingStrategy,
+        iface::SwapError<P::Strategy>: core::fmt::Debug,
+    {
+        self.swap();
+        self.swap();
+    }
+
+    /// Returns true if there are no active reads of the given buffer
+    ///
+    /// Readers may start reading the read buffer again right after this returns
+    pub fn is_buffer_empty(&self, side: BufferSide) -> bool
+    where
+        P::Strategy: BufferOccupancy,
+    {
+        // SAFETY: the writer id is valid (invariant of Self)
+        unsafe { self.ptr.strategy.is_buffer_empty(&self.id, side) }
+    }
+
+    /// Waits until there are no active reads of the given buffer, or the timeout elapses
+    ///
+    /// Returns true if the buffer was empty. This yields the thread until the buffer is empty,
+    /// so if there is a reader in that buffer on the current thread, this always waits
+    /// for the whole timeout and returns false.
+    #[cfg(feature = "std")]
+    pub fn wait_buffer_empty(&mut self, side: BufferSide, timeout: core::time::Duration) -> bool
+    where
+        P::Strategy: BufferOccupancy,
+    {
+        let deadline = std::time::Instant::now().checked_add(timeout);
+
+        while !self.is_buffer_empty(side) {
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                return false;
+            }
+
+            std::thread::yield_now();
+        }
+
+        true
+    }
+
+    /// Start a buffer swap, and convert this writer into a [`SwappingWriter`]
+    ///
+    /// The [`SwappingWriter`] only gives shared access to the buffers until the swap
+    /// is finished, so there is no way to call [`Self::get_mut`] or [`Self::split_mut`]
+    /// during the swap. This takes the writer by value, so that leaking the
+    /// [`SwappingWriter`] can't give access to the write buffer.
+    ///
+    /// If the swap couldn't be started, the writer is returned alongside the error
+    pub fn into_swapping(
+        mut self,
+    ) -> Result<SwappingWriter<P>, (Self, iface::SwapError<P::Strategy>)> {
+        // SAFETY: the writer is moved into the SwappingWriter, which doesn't allow
+        // calling split_mut or get_mut until the swap is finished
+        match unsafe { self.try_start_swap() } {
+            Ok(swap) => Ok(SwappingWriter { writer: self, swap }),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    /// Start a buffer swap, run `body` while the swap is in progress, and then finish the swap
+    ///
+    /// `body` gets a [`SwapInProgress`], which only gives shared access to the buffers, and can
+    /// check if the swap is finished. The swap is always finished before this returns, even if
+    /// `body` panics, so this is a safe alternative to [`Self::try_start_swap`].
+    ///
+    /// If the swap couldn't be started, then `body` isn't run and the error is returned
+    ///
+    /// ```rust
+    /// use dbuf::raw::{DoubleBufferData, Writer};
+    /// use dbuf::strategy::simple::SimpleStrategy;
+    ///
+    /// let mut data = DoubleBufferData::new(0, 1, SimpleStrategy::new());
+    /// let mut writer = Writer::new(&mut data);
+    /// let mut reader = writer.reader();
+    ///
+    /// let seen = writer
+    ///     .swapping(|swap| {
+    ///         assert!(swap.is_swap_finished());
+    ///         *swap.split().read
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(seen, 1);
+    /// assert_eq!(*reader.read(), 1);
+    /// *writer.get_mut() = 2;
+    /// ```
+    pub fn swapping<R>(
+        &mut self,
+        body: impl FnOnce(&mut SwapInProgress<'_, P>) -> R,
+    ) -> Result<R, iface::SwapError<P::Strategy>>
+    where
+        P::Strategy: BlockingStrategy,
+    {
+        // SAFETY: the writer is borrowed by the SwapInProgress, which doesn't allow
+        // calling split_mut or get_mut, and finishes the swap when it's dropped
+        let swap = unsafe { self.try_start_swap()? };
+        let mut swap = SwapInProgress {
+            writer: self,
+            swap: core::mem::ManuallyDrop::new(swap),
+        };
+        Ok(body(&mut swap))
+    }
+
     /// Try to start a buffer swap, returns an error if it's not possible
     ///
     /// See the underlying strategy for details on when this may fail
@@ -149,8 +663,8 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
     /// Note: you should not call `try_start_swap` twice, while it is not UB,
     /// it may lead to unpredictiable behaviors, such as panics, dead-locks, and more.
     ///
-    /// If you find yourself reaching for `try_start_swap`, instead try using `DelayWriter`,
-    /// which provides a safe interface for `try_start_swap`
+    /// If you find yourself reaching for `try_start_swap`, instead try using `DelayWriter`
+    /// or [`Self::swapping`], which provide a safe interface for `try_start_swap`
     ///
     /// # Safety
     ///
@@ -161,7 +675,9 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
         &mut self,
     ) -> Result<iface::Swap<P::Strategy>, iface::SwapError<P::Strategy>> {
         // SAFETY: the writer id is valid (invariant of Self)
-        unsafe { self.ptr.strategy.try_start_swap(&mut self.id) }
+        let swap = unsafe { self.ptr.strategy.try_start_swap(&mut self.id) }?;
+        self.swaps = self.swaps.wrapping_add(1);
+        Ok(swap)
     }
 
     /// Check if the given swap is completed
@@ -222,6 +738,285 @@ impl<P: DoubleBufferWriterPointer> Writer<P> {
     }
 }
 
+/// A writer with an in progress swap, returned by [`Writer::into_swapping`]
+///
+/// This only allows shared access to the buffers, until the swap is finished
+pub struct SwappingWriter<P: DoubleBufferWriterPointer> {
+    writer: Writer<P>,
+    swap: iface::Swap<P::Strategy>,
+}
+
+impl<P: DoubleBufferWriterPointer> SwappingWriter<P> {
+    /// Create a new reader that points to the same buffers as this writer
+    #[inline]
+    pub fn reader(&self) -> Reader<P::Reader> {
+        self.writer.reader()
+    }
+
+    /// Get a shared reference to the writer half of the double buffer
+    #[inline]
+    pub fn get(&self) -> &P::Buffer {
+        self.writer.get()
+    }
+
+    /// Get shared references to both buffers
+    #[inline]
+    pub fn split(&self) -> Split<'_, P::Buffer, P::Extras> {
+        self.writer.split()
+    }
+
+    /// Get an extra data stored along-side the buffers
+    #[inline]
+    pub fn extras(&self) -> &P::Extras {
+        self.writer.extras()
+    }
+
+    /// Check if the swap is finished
+    #[inline]
+    pub fn is_swap_finished(&mut self) -> bool {
+        // SAFETY: this swap is the latest swap, since Self holds the writer
+        unsafe { self.writer.is_swap_finished(&mut self.swap) }
+    }
+
+    /// Get the writer back if the swap is finished
+    pub fn try_finish(mut self) -> Result<Writer<P>, Self> {
+        if self.is_swap_finished() {
+            Ok(self.writer)
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Wait for the swap to finish, and get the writer back
+    pub fn finish(self) -> Writer<P>
+    where
+        P::Strategy: BlockingStrategy,
+    {
+        let Self { mut writer, swap } = self;
+        // SAFETY: this swap is the latest swap, since Self holds the writer
+        unsafe { writer.finish_swap(swap) };
+        writer
+    }
+
+    /// Wait for the swap to finish, and get the writer back
+    ///
+    /// If this future is dropped before it completes, then the writer is dropped as well
+    pub async fn afinish(self) -> Writer<P>
+    where
+        P::Strategy: AsyncStrategy,
+    {
+        let Self {
+            mut writer,
+            mut swap,
+        } = self;
+        // SAFETY: this swap is the latest swap, since Self holds the writer
+        // and the writer is only returned once this future completes
+        unsafe { writer.afinish_swap(&mut swap) }.await;
+        writer
+    }
+}
+
+/// An in progress swap, see [`Writer::swapping`]
+///
+/// This only allows shared access to the buffers, and finishes the swap when dropped
+pub struct SwapInProgress<'a, P: DoubleBufferWriterPointer>
+where
+    P::Strategy: BlockingStrategy,
+{
+    writer: &'a mut Writer<P>,
+    swap: core::mem::ManuallyDrop<iface::Swap<P::Strategy>>,
+}
+
+impl<P: DoubleBufferWriterPointer> SwapInProgress<'_, P>
+where
+    P::Strategy: BlockingStrategy,
+{
+    /// Create a new reader that points to the same buffers as this writer
+    #[inline]
+    pub fn reader(&self) -> Reader<P::Reader> {
+        self.writer.reader()
+    }
+
+    /// Get a shared reference to the writer half of the double buffer
+    #[inline]
+    pub fn get(&self) -> &P::Buffer {
+        self.writer.get()
+    }
+
+    /// Get shared references to both buffers
+    #[inline]
+    pub fn split(&self) -> Split<'_, P::Buffer, P::Extras> {
+        self.writer.split()
+    }
+
+    /// Get an extra data stored along-side the buffers
+    #[inline]
+    pub fn extras(&self) -> &P::Extras {
+        self.writer.extras()
+    }
+
+    /// Check if the swap is finished
+    #[inline]
+    pub fn is_swap_finished(&mut self) -> bool {
+        // SAFETY: this swap is the latest swap, since Self borrows the writer
+        unsafe { self.writer.is_swap_finished(&mut self.swap) }
+    }
+}
+
+impl<P: DoubleBufferWriterPointer> Drop for SwapInProgress<'_, P>
+where
+    P::Strategy: BlockingStrategy,
+{
+    fn drop(&mut self) {
+        // SAFETY: the swap is never used again after this
+        let swap = unsafe { core::mem::ManuallyDrop::take(&mut self.swap) };
+        // SAFETY: this swap is the latest swap, since Self borrows the writer
+        unsafe { self.writer.finish_swap(swap) }
+    }
+}
+
+/// An exclusive reference to the extras, returned by [`Writer::try_extras_mut`]
+///
+/// All readers are locked out while this guard is alive
+pub struct ExtrasMut<'a, P: DoubleBufferWriterPointer>
+where
+    P::Strategy: ReaderLock,
+{
+    writer: &'a mut Writer<P>,
+}
+
+impl<P: DoubleBufferWriterPointer> core::ops::Deref for ExtrasMut<'_, P>
+where
+    P::Strategy: ReaderLock,
+{
+    type Target = P::Extras;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.writer.extras()
+    }
+}
+
+impl<P: DoubleBufferWriterPointer> core::ops::DerefMut for ExtrasMut<'_, P>
+where
+    P::Strategy: ReaderLock,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: all readers are locked out, so there are no other references
+        // to the extras, and this guard holds an exclusive reference to the writer
+        unsafe { &mut *self.writer.ptr.extras.get() }
+    }
+}
+
+impl<P: DoubleBufferWriterPointer> Drop for ExtrasMut<'_, P>
+where
+    P::Strategy: ReaderLock,
+{
+    fn drop(&mut self) {
+        let writer = &mut *self.writer;
+        // SAFETY: the writer id is valid, and the readers were locked in try_extras_mut
+        unsafe { writer.ptr.strategy.unlock_readers(&mut writer.id) }
+    }
+}
+
+/// A writer which may be shared between threads
+///
+/// Every strategy assumes that there is a single writer id, and the buffers are only
+/// written through the [`Writer`] which owns that id. So instead of wrapping the strategy,
+/// this puts the writer itself behind a mutex. Each thread that wants to
+/// write locks the writer, and while the lock is held that thread is *the* writer. This
+/// upholds the single writer invariants of any strategy, since starting swaps, finishing
+/// swaps and accessing the buffers all go through the locked writer.
+///
+/// Readers don't go through the lock at all, they only talk to the strategy, so reads are
+/// as cheap as they are with a single writer.
+///
+/// # Deadlocks
+///
+/// The lock is a normal [`Mutex`](std::sync::Mutex), so locking the writer twice on the same
+/// thread deadlocks.
+///
+/// Swapping while holding the lock waits for readers to leave the write buffer, the same as
+/// with a single writer. So swapping while the same thread holds a read guard deadlocks, and
+/// all other writers are blocked until the swap is done. Readers are never blocked by the lock.
+#[cfg(feature = "std")]
+pub struct SharedWriter<P: DoubleBufferWriterPointer> {
+    writer: std::sync::Mutex<Writer<P>>,
+}
+
+#[cfg(feature = "std")]
+impl<P: DoubleBufferWriterPointer> From<Writer<P>> for SharedWriter<P> {
+    #[inline]
+    fn from(writer: Writer<P>) -> Self {
+        Self::new(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: DoubleBufferWriterPointer> SharedWriter<P> {
+    /// Share the given writer
+    pub const fn new(writer: Writer<P>) -> Self {
+        Self {
+            writer: std::sync::Mutex::new(writer),
+        }
+    }
+
+    /// Lock the writer, blocking until no other thread is writing
+    ///
+    /// If another writer panicked while holding the lock, then the writer is still returned.
+    /// Any pending writes are still in the write buffer, which readers can't see until the
+    /// next swap.
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, Writer<P>> {
+        self.writer
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Try to lock the writer, returns None if another thread is writing
+    pub fn try_lock(&self) -> Option<std::sync::MutexGuard<'_, Writer<P>>> {
+        match self.writer.try_lock() {
+            Ok(writer) => Some(writer),
+            Err(std::sync::TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Create a new reader that points to the same buffers as this writer
+    ///
+    /// This briefly locks the writer, use [`Reader::clone`] on an existing
+    /// reader if you don't want to wait on other writers
+    pub fn reader(&self) -> Reader<P::Reader> {
+        self.lock().reader()
+    }
+
+    /// Get exclusive access to the writer, without locking
+    pub fn get_mut(&mut self) -> &mut Writer<P> {
+        self.writer
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Get the underlying writer
+    pub fn into_inner(self) -> Writer<P> {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// A report on how a swap went, returned by [`Writer::try_swap_report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SwapReport {
+    /// true if the swap wasn't finished right after it was started
+    pub blocked: bool,
+    /// the number of readers that the swap had to wait on right after it was started
+    ///
+    /// This is [`None`] if the strategy doesn't track this
+    pub residual_readers: Option<usize>,
+}
+
 struct NoUnwind;
 
 impl Drop for NoUnwind {
@@ -237,6 +1032,18 @@ pub struct WaitForSwap<'a, 's, S: AsyncStrategy> {
     id: &'a mut S::WriterId,
 }
 
+impl<S: AsyncStrategy> WaitForSwap<'_, '_, S> {
+    /// Returns the number of readers that the swap is still waiting on
+    ///
+    /// This is useful for reporting the progress of a stalled swap.
+    /// Returns [`None`] if the strategy doesn't track this, see [`Strategy::residual_readers`]
+    #[inline]
+    pub fn remaining_residual(&self) -> Option<usize> {
+        // SAFETY: the id came from a Writer and the swap is the latest swap (see `poll`)
+        unsafe { self.strategy.residual_readers(self.id, self.swap) }
+    }
+}
+
 impl<S: AsyncStrategy> core::future::Future for WaitForSwap<'_, '_, S> {
     type Output = ();
 
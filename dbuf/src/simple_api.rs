@@ -0,0 +1,94 @@
+//! Convenience types for the common case, which hide the pointer and strategy generics
+//!
+//! * [`new`] creates a thread-safe [`DoubleBuffer`], which uses [`Arc`](alloc::sync::Arc) and [`FlashStrategy`]
+//! * [`new_local`] creates a single-threaded [`LocalDoubleBuffer`], which uses [`Rc`](alloc::rc::Rc) and [`SimpleStrategy`]
+//!
+//! If you need a different pointer or strategy, use [`raw::Writer`] directly.
+//!
+//! ```rust
+//! # #[cfg(all(feature = "std", feature = "triomphe"))]
+//! # {
+//! let (mut writer, mut reader) = dbuf::simple_api::new(10, 300);
+//!
+//! // the front buffer is shown first
+//! assert_eq!(*reader.read(), 10);
+//! assert_eq!(*writer.get(), 300);
+//!
+//! *writer.get_mut() = 20;
+//! writer.swap();
+//! assert_eq!(*reader.read(), 20);
+//! # }
+//! ```
+
+use alloc::rc;
+#[cfg(all(feature = "std", feature = "triomphe"))]
+use alloc::sync::{Arc, Weak};
+
+use rc_box::RcBox;
+
+#[cfg(all(feature = "std", feature = "triomphe"))]
+use crate::strategy::{flash_park_token::AdaptiveParkToken, flashmap::FlashStrategy};
+use crate::{
+    raw::{self, DoubleBufferData},
+    strategy::simple::SimpleStrategy,
+};
+
+/// A thread-safe double buffer writer, see [`new`]
+#[cfg(all(feature = "std", feature = "triomphe"))]
+pub type DoubleBuffer<T> = raw::Writer<Arc<DoubleBufferData<T, FlashStrategy<AdaptiveParkToken>>>>;
+
+/// A reader for a [`DoubleBuffer`]
+#[cfg(all(feature = "std", feature = "triomphe"))]
+pub type DoubleBufferReader<T> =
+    raw::Reader<Weak<DoubleBufferData<T, FlashStrategy<AdaptiveParkToken>>>>;
+
+/// A single-threaded double buffer writer, see [`new_local`]
+pub type LocalDoubleBuffer<T> = raw::Writer<rc::Rc<DoubleBufferData<T, SimpleStrategy>>>;
+
+/// A reader for a [`LocalDoubleBuffer`]
+pub type LocalDoubleBufferReader<T> = raw::Reader<rc::Weak<DoubleBufferData<T, SimpleStrategy>>>;
+
+/// Create a thread-safe double buffer, and a reader for it
+///
+/// Readers see `front` first, and the writer gets access to `back`.
+///
+/// Swaps wait for readers to leave the read buffer, either by blocking or asynchronously.
+/// The buffers are freed once the writer is dropped and all active reads are finished.
+#[cfg(all(feature = "std", feature = "triomphe"))]
+pub fn new<T>(front: T, back: T) -> (DoubleBuffer<T>, DoubleBufferReader<T>) {
+    let writer = raw::Writer::new(rc_box::ArcBox::new(DoubleBufferData::new(
+        front,
+        back,
+        FlashStrategy::new(),
+    )));
+    let reader = writer.reader();
+    (writer, reader)
+}
+
+/// Create a single-threaded double buffer, and a reader for it
+///
+/// Readers see `front` first, and the writer gets access to `back`.
+///
+/// Swaps fail if there are any readers in the read buffer, see [`SimpleStrategy`].
+///
+/// ```rust
+/// let (mut writer, mut reader) = dbuf::simple_api::new_local(10, 300);
+///
+/// let guard = reader.read();
+/// assert_eq!(*guard, 10);
+/// // the reader is still reading, so the swap fails
+/// assert!(writer.try_swap().is_err());
+///
+/// drop(guard);
+/// writer.swap();
+/// assert_eq!(*reader.read(), 300);
+/// ```
+pub fn new_local<T>(front: T, back: T) -> (LocalDoubleBuffer<T>, LocalDoubleBufferReader<T>) {
+    let writer = raw::Writer::new(RcBox::new(DoubleBufferData::new(
+        front,
+        back,
+        SimpleStrategy::new(),
+    )));
+    let reader = writer.reader();
+    (writer, reader)
+}
@@ -10,8 +10,11 @@ pub mod hazad_flash;
 
 pub mod atomic;
 
+pub mod null;
 pub mod simple;
 pub mod simple_async;
+pub mod spsc;
+pub mod static_slots;
 
 #[cfg(feature = "std")]
 #[cfg(feature = "triomphe")]
@@ -21,4 +24,15 @@ pub mod hazard_evmap;
 
 pub mod flash_park_token;
 
+pub mod instrument;
 pub mod outline_writer;
+pub mod strict;
+
+#[cfg(feature = "std")]
+pub mod notify_readers;
+
+#[cfg(feature = "std")]
+pub mod journal;
+
+#[cfg(test)]
+mod harness;
@@ -1,27 +1,64 @@
+//! A thread-safe strategy which keeps all of it's state in a single atomic word
+//!
+//! The state holds which buffer the readers are reading, and how many readers are in that
+//! buffer. So a reader can enter the buffer with a single `fetch_add`, which also tells it which
+//! buffer to read. Readers only ever enter the read buffer, so there is no need to count the
+//! readers in the write buffer.
+//!
+//! A swap is committed with a single `compare_exchange` which flips which buffer is read
+//! and expects no readers. Since entering the buffer and committing the swap both modify the
+//! same word, the writer can't commit a swap while a reader is entering the read buffer.
+//!
+//! # Disabling the reader lock
+//!
+//! Swaps never block readers, but the writer may lock out all readers to get exclusive access
+//! to the extras (see [`ReaderLock`]), and readers back off while the lock is held. This is the
+//! only way that readers can be made to wait. [`AtomicStrategy::without_reader_lock`] disables
+//! [`ReaderLock`], so `try_lock_readers` always fails and readers never back off in
+//! `acquire_read_guard`. Swaps work the same way in both cases.
+
 use const_fn::const_fn;
 #[cfg(not(loom))]
-use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::sync::atomic::{AtomicU64, Ordering};
 #[cfg(loom)]
-use loom::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use loom::sync::atomic::{AtomicU64, Ordering};
 
-use crate::interface::Strategy;
+use crate::interface::{BufferOccupancy, BufferSide, ReaderLock, Strategy};
 
+mod backoff;
 pub mod park_token;
 
+use backoff::Backoff;
+pub use backoff::BackoffConfig;
 use park_token::Parker;
 
 #[cfg(test)]
 mod tests;
 
 pub struct AtomicStrategy<P> {
-    num_readers: [AtomicU64; 2],
-    which: AtomicBool,
+    // see `SWAPPED`, `LOCKED` and `ONE_READER` for the layout
+    state: AtomicU64,
     #[allow(unused)]
     parker: P,
+    backoff: BackoffConfig,
+    // if false, then `try_lock_readers` always fails
+    reader_lock: bool,
+}
+
+/// set if the number of committed swaps is odd
+const SWAPPED: u64 = 1;
+/// set while the writer has locked out all readers, see [`ReaderLock`]
+const LOCKED: u64 = 2;
+/// the rest of the state is the number of readers in the read buffer
+const ONE_READER: u64 = 4;
+
+const fn num_readers(state: u64) -> u64 {
+    state / ONE_READER
 }
 
 #[cfg(feature = "std")]
 impl AtomicStrategy<park_token::ThreadParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new_blocking() -> Self {
         Self::with_park_token()
     }
@@ -29,6 +66,7 @@ impl AtomicStrategy<park_token::ThreadParkToken> {
 
 #[cfg(feature = "atomic-waker")]
 impl AtomicStrategy<park_token::AsyncParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new_async() -> Self {
         Self::with_park_token()
     }
@@ -37,6 +75,7 @@ impl AtomicStrategy<park_token::AsyncParkToken> {
 #[cfg(feature = "std")]
 #[cfg(feature = "atomic-waker")]
 impl AtomicStrategy<park_token::AdaptiveParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new() -> Self {
         Self::with_park_token()
     }
@@ -48,13 +87,37 @@ impl<P: Parker> AtomicStrategy<P> {
     #[allow(unused)]
     const fn with_park_token() -> Self {
         Self {
-            num_readers: [AtomicU64::new(0), AtomicU64::new(0)],
-            which: AtomicBool::new(false),
+            state: AtomicU64::new(0),
             parker: P::NEW,
+            backoff: BackoffConfig::DEFAULT,
+            reader_lock: true,
         }
     }
 }
 
+impl<P> AtomicStrategy<P> {
+    /// Set how readers back off while the readers are locked out, and how long the
+    /// writer retries a swap before parking
+    ///
+    /// see [`BackoffConfig`] for details
+    #[inline]
+    pub const fn with_backoff(mut self, backoff: BackoffConfig) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Disable [`ReaderLock`], so that the writer can never lock out the readers
+    ///
+    /// [`ReaderLock::try_lock_readers`] always fails, so
+    /// [`Writer::try_extras_mut`](crate::raw::Writer::try_extras_mut) always returns `None`.
+    /// This doesn't change how swaps work, see the module docs for details
+    #[inline]
+    pub const fn without_reader_lock(mut self) -> Self {
+        self.reader_lock = false;
+        self
+    }
+}
+
 // #[cfg(feature = "std")]
 #[cfg(feature = "std")]
 impl Default for AtomicStrategy<park_token::ThreadParkToken> {
@@ -80,9 +143,10 @@ impl Default for AtomicStrategy<park_token::AdaptiveParkToken> {
 
 // SAFETY:
 //
-// If there are no readers currently reading from the buffer
-// then we can swap to that buffer. If there are any readers reading
-// from the buffer an error is returned, and no swap happens
+// If there are no readers currently reading from the read buffer
+// then we can swap the buffers. Readers enter the read buffer and the
+// swap is committed by modifying the same atomic word, so a reader can
+// never enter a buffer after the writer decided to swap to it.
 unsafe impl<P: Parker> Strategy for AtomicStrategy<P> {
     type WriterId = ();
     type ReaderId = ();
@@ -101,30 +165,29 @@ unsafe impl<P: Parker> Strategy for AtomicStrategy<P> {
     #[inline]
     unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {}
 
+    #[inline]
+    unsafe fn create_detached_reader_id(_reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        Some(())
+    }
+
     #[inline]
     fn create_invalid_reader_id() -> Self::ReaderId {}
 
+    /// Go back to the initial state: not swapped, no readers and not locked
+    #[inline]
+    fn reset(&mut self) {
+        self.state = AtomicU64::new(0);
+    }
+
     #[inline]
     unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
-        // SAFETY: The caller ensures that the writer id is valid,
-        // and since the only write to `self.which` is in `try_start_swap`
-        // there is no race with reading the value here
-        #[cfg(not(loom))]
-        unsafe {
-            core::ptr::read(&self.which).into_inner()
-        }
-        // SAFETY: The caller ensures that the writer id is valid,
-        // and since the only write to `self.which` is in `try_start_swap`
-        // there is no race with reading the value here
-        #[cfg(loom)]
-        unsafe {
-            self.which.unsync_load()
-        }
+        // the swapped bit is only ever changed by the writer, so a relaxed load is enough
+        self.state.load(Ordering::Relaxed) & SWAPPED != 0
     }
 
     #[inline]
-    unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, _guard: &Self::ReadGuard) -> bool {
-        self.which.load(Ordering::Acquire)
+    unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, &guard: &Self::ReadGuard) -> bool {
+        guard
     }
 
     #[inline]
@@ -132,6 +195,9 @@ unsafe impl<P: Parker> Strategy for AtomicStrategy<P> {
         &self,
         writer: &mut Self::WriterId,
     ) -> Result<Self::Swap, Self::SwapError> {
+        // starting a swap only decides which buffer to swap to, the swap
+        // is committed once all readers have left the read buffer (see `try_commit_swap`)
+
         // SAFETY: The caller ensures that the writer id is valid
         let next_swap = unsafe { !self.is_swapped_writer(writer) };
 
@@ -141,76 +207,188 @@ unsafe impl<P: Parker> Strategy for AtomicStrategy<P> {
     #[inline]
     unsafe fn is_swap_finished(
         &self,
-        _writer: &mut Self::WriterId,
+        writer: &mut Self::WriterId,
         &mut next_swap: &mut Self::Swap,
     ) -> bool {
-        let num_readers = &self.num_readers[next_swap as usize];
+        // SAFETY: The caller ensures that the writer id is valid
+        let swapped = unsafe { self.is_swapped_writer(writer) };
 
-        // lock the number of readers
-        if num_readers
-            .compare_exchange(0, u64::MAX, Ordering::AcqRel, Ordering::Relaxed)
-            .is_ok()
-        {
-            self.which.store(next_swap, Ordering::Release);
-            num_readers.store(0, Ordering::Release);
-            true
-        } else {
-            false
-        }
+        // if the swap was already committed, then don't commit it again
+        swapped == next_swap || self.try_commit_swap(next_swap)
     }
 
     #[inline]
-    unsafe fn acquire_read_guard(&self, _reader: &mut Self::ReaderId) -> Self::ReadGuard {
-        let mut swapped = !self.which.load(Ordering::Acquire);
-        let mut reader_count = &self.num_readers[swapped as usize];
-
-        let mut num_readers = reader_count.load(Ordering::Acquire);
+    unsafe fn residual_readers(
+        &self,
+        _writer: &Self::WriterId,
+        _swap: &Self::Swap,
+    ) -> Option<usize> {
+        // the swap can't be committed while there are readers in the read buffer
+        let num_readers = num_readers(self.state.load(Ordering::Acquire));
+        Some(usize::try_from(num_readers).unwrap_or(usize::MAX))
+    }
 
+    #[inline]
+    unsafe fn acquire_read_guard(&self, _reader: &mut Self::ReaderId) -> Self::ReadGuard {
         loop {
-            #[cfg(loom)]
-            loom::thread::yield_now();
+            if let Some(swapped) = self.try_enter() {
+                return swapped;
+            }
 
-            let Some(next_num_readers) = num_readers.checked_add(1) else {
-                // the writer locked the readers and swapped the buffers
-                // so refresh everything
+            // the writer locked out all readers, so back off until it unlocks them
+            let mut backoff = Backoff::new(self.backoff);
+            while self.is_locked() {
+                backoff.snooze();
+            }
+        }
+    }
 
-                swapped = !self.which.load(Ordering::Acquire);
-                reader_count = &self.num_readers[swapped as usize];
-                num_readers = reader_count.load(Ordering::Acquire);
+    #[inline]
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        _reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        #[cfg(feature = "std")]
+        {
+            let deadline = std::time::Instant::now().checked_add(timeout);
 
-                core::hint::spin_loop();
-                continue;
-            };
+            loop {
+                if let Some(swapped) = self.try_enter() {
+                    return Some(swapped);
+                }
 
-            match reader_count.compare_exchange_weak(
-                num_readers,
-                next_num_readers,
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            ) {
-                Ok(_) => {
-                    let current_swapped = !self.which.load(Ordering::Acquire);
-                    if current_swapped == swapped {
-                        return swapped;
+                let mut backoff = Backoff::new(self.backoff);
+                while self.is_locked() {
+                    if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                        return None;
                     }
-                    reader_count.fetch_sub(1, Ordering::Release);
+                    backoff.snooze();
                 }
-                Err(current) => num_readers = current,
             }
+        }
 
-            core::hint::spin_loop();
+        // without a clock, give up as soon as the readers are locked out
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = timeout;
+            self.try_enter()
         }
     }
 
     #[inline]
-    unsafe fn release_read_guard(&self, _reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
-        let swapped = guard;
-        let num_readers = &self.num_readers[swapped as usize];
-        num_readers.fetch_sub(1, Ordering::Release);
+    unsafe fn release_read_guard(&self, _reader: &mut Self::ReaderId, _guard: Self::ReadGuard) {
+        self.state.fetch_sub(ONE_READER, Ordering::Release);
         self.parker.wake();
     }
 }
 
+// SAFETY: the state tracks how many readers are in the read buffer, and readers
+// never enter the write buffer
+unsafe impl<P: Parker> BufferOccupancy for AtomicStrategy<P> {
+    #[inline]
+    unsafe fn is_buffer_empty(&self, _writer: &Self::WriterId, side: BufferSide) -> bool {
+        match side {
+            // readers which are backing off from a lock are counted here too, but
+            // locks are only held inside of `ExtrasMut`, where this can't be called
+            BufferSide::Read => num_readers(self.state.load(Ordering::Acquire)) == 0,
+            BufferSide::Write => true,
+        }
+    }
+}
+
+// SAFETY: while locked, acquire_read_guard will spin until the readers are unlocked
+unsafe impl<P: Parker> ReaderLock for AtomicStrategy<P> {
+    unsafe fn try_lock_readers(&self, _writer: &mut Self::WriterId) -> bool {
+        if !self.reader_lock {
+            return false;
+        }
+
+        let swapped = self.state.load(Ordering::Relaxed) & SWAPPED;
+
+        self.state
+            .compare_exchange(
+                swapped,
+                swapped | LOCKED,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    unsafe fn unlock_readers(&self, _writer: &mut Self::WriterId) {
+        // readers which are backing off may still be counted, so only clear the lock bit
+        self.state.fetch_and(!LOCKED, Ordering::Release);
+    }
+}
+
+impl<P: Parker> AtomicStrategy<P> {
+    /// Try to finish the swap until the backoff is used up, returns true if the swap finished
+    ///
+    /// # Safety
+    ///
+    /// same as `Strategy::is_swap_finished`
+    #[cfg(feature = "std")]
+    unsafe fn retry_swap(&self, writer: &mut (), swap: &mut bool) -> bool {
+        let mut backoff = Backoff::new(self.backoff);
+
+        loop {
+            // SAFETY: guaranteed by caller
+            if unsafe { self.is_swap_finished(writer, swap) } {
+                return true;
+            }
+
+            if backoff.is_completed() {
+                return false;
+            }
+
+            backoff.snooze();
+        }
+    }
+}
+
+impl<P> AtomicStrategy<P> {
+    /// Try to enter the read buffer, returns which buffer was entered, or None if
+    /// the writer locked out all readers
+    #[inline]
+    fn try_enter(&self) -> Option<bool> {
+        // this is the only access on the uncontended path
+        let state = self.state.fetch_add(ONE_READER, Ordering::Acquire);
+
+        if state & LOCKED == 0 {
+            return Some(state & SWAPPED != 0);
+        }
+
+        debug_assert!(
+            self.reader_lock,
+            "readers are never locked out without the reader lock"
+        );
+
+        self.state.fetch_sub(ONE_READER, Ordering::Relaxed);
+        None
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & LOCKED != 0
+    }
+
+    /// Try to commit the swap to `next_swap`, this succeeds only if there are
+    /// no readers in the read buffer
+    ///
+    /// This must only be called by the writer, and only if the swap isn't committed yet
+    fn try_commit_swap(&self, next_swap: bool) -> bool {
+        let next = u64::from(next_swap);
+        let current = next ^ SWAPPED;
+
+        // this only succeeds if there are no readers (and the readers aren't locked)
+        // and any reader which enters after this will see the new swapped bit
+        self.state
+            .compare_exchange(current, next, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+    }
+}
+
 #[cfg(feature = "atomic-waker")]
 // SAFETY: is_swap_finished always returns true
 unsafe impl crate::interface::AsyncStrategy for AtomicStrategy<park_token::AsyncParkToken> {
@@ -236,6 +414,12 @@ unsafe impl crate::interface::AsyncStrategy for AtomicStrategy<park_token::Async
 // SAFETY: is_swap_finished always returns true
 unsafe impl crate::interface::BlockingStrategy for AtomicStrategy<park_token::ThreadParkToken> {
     unsafe fn finish_swap(&self, writer: &mut Self::WriterId, mut swap: Self::Swap) {
+        // fast path: retry the swap with backoff, without touching the park token
+        // SAFETY: the caller ensures that writer and swap are valid
+        if unsafe { self.retry_swap(writer, &mut swap) } {
+            return;
+        }
+
         self.parker
             // SAFETY: the caller ensures that writer and swap are valid
             .park_until(|| unsafe { self.is_swap_finished(writer, &mut swap) });
@@ -270,6 +454,13 @@ unsafe impl crate::interface::AsyncStrategy for AtomicStrategy<park_token::Adapt
 unsafe impl crate::interface::BlockingStrategy for AtomicStrategy<park_token::AdaptiveParkToken> {
     unsafe fn finish_swap(&self, writer: &mut Self::WriterId, mut swap: Self::Swap) {
         self.parker.async_token.clear();
+
+        // fast path: retry the swap with backoff, without touching the park token
+        // SAFETY: the caller ensures that writer and swap are valid
+        if unsafe { self.retry_swap(writer, &mut swap) } {
+            return;
+        }
+
         self.parker
             .thread_token
             // SAFETY: the caller ensures that writer and swap are valid
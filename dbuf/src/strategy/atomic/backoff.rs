@@ -0,0 +1,82 @@
+//! Exponential backoff for the spin loops in [`AtomicStrategy`](super::AtomicStrategy)
+
+/// How long to back off before giving up on spinning, see [`AtomicStrategy::with_backoff`](super::AtomicStrategy::with_backoff)
+///
+/// Each step of the backoff spins twice as long as the last step, until `spin_limit` steps
+/// are reached. After that each step yields the thread (with `std`, otherwise it keeps spinning)
+/// until `yield_limit` steps are reached.
+///
+/// Readers keep backing off at the last step until the writer unlocks them, if there are no
+/// yield steps (`yield_limit <= spin_limit`) then the last step is a single spin.
+/// The writer parks once it used up all the steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffConfig {
+    /// The number of steps which spin, step `n` spins `2^n` times
+    pub spin_limit: u32,
+    /// The total number of steps, including the steps which spin
+    pub yield_limit: u32,
+}
+
+impl BackoffConfig {
+    /// The default backoff, spins for a few steps and then yields for a few steps
+    pub const DEFAULT: Self = Self {
+        spin_limit: 6,
+        yield_limit: 10,
+    };
+
+    /// Don't back off at all, readers just spin and the writer parks right away
+    pub const NONE: Self = Self {
+        spin_limit: 0,
+        yield_limit: 0,
+    };
+}
+
+impl Default for BackoffConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+pub(super) struct Backoff {
+    step: u32,
+    config: BackoffConfig,
+}
+
+impl Backoff {
+    #[inline]
+    pub(super) const fn new(config: BackoffConfig) -> Self {
+        Self { step: 0, config }
+    }
+
+    /// Returns true once all the steps were used up
+    #[inline]
+    pub(super) const fn is_completed(&self) -> bool {
+        self.step >= self.config.yield_limit
+    }
+
+    /// Back off for a single step
+    #[inline]
+    pub(super) fn snooze(&mut self) {
+        #[cfg(loom)]
+        loom::thread::yield_now();
+
+        #[cfg(not(loom))]
+        if self.step < self.config.spin_limit {
+            for _ in 0..1_u32.checked_shl(self.step).unwrap_or(u32::MAX) {
+                core::hint::spin_loop();
+            }
+        } else if self.config.yield_limit > self.config.spin_limit {
+            #[cfg(feature = "std")]
+            std::thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            core::hint::spin_loop();
+        } else {
+            core::hint::spin_loop();
+        }
+
+        if !self.is_completed() {
+            self.step = self.step.saturating_add(1);
+        }
+    }
+}
@@ -1,13 +1,40 @@
+#![cfg(all(loom, feature = "std"))]
+
+use std::sync::Arc;
+
+use loom::cell::UnsafeCell;
+use rc_box::ArcBox;
+
+use super::{AtomicStrategy, BackoffConfig};
+
+use crate::{
+    delay::DelayWriter,
+    raw::{DoubleBufferData, Writer},
+};
+
+type Buffer = DoubleBufferData<
+    UnsafeCell<i32>,
+    AtomicStrategy<super::park_token::ThreadParkToken>,
+    UnsafeCell<i32>,
+>;
+
+fn new_writer() -> Writer<Arc<Buffer>> {
+    new_writer_with_backoff(BackoffConfig::DEFAULT)
+}
+
+fn new_writer_with_backoff(backoff: BackoffConfig) -> Writer<Arc<Buffer>> {
+    Writer::new(ArcBox::new(DoubleBufferData::with_extras(
+        UnsafeCell::new(0),
+        UnsafeCell::new(0),
+        AtomicStrategy::new_blocking().with_backoff(backoff),
+        UnsafeCell::new(0),
+    )))
+}
+
 #[test]
-#[cfg(loom)]
 fn loom() {
     loom::model(|| {
-        dbg!();
-        let x = crate::raw::Writer::new(rc_box::RcBox::new(crate::raw::DoubleBufferData::new(
-            loom::cell::UnsafeCell::new(0),
-            loom::cell::UnsafeCell::new(0),
-            AtomicStrategy::new(),
-        )));
+        let x = new_writer();
 
         let a = loom::thread::spawn({
             let mut x = x.reader();
@@ -21,7 +48,7 @@ fn loom() {
         });
 
         let b = loom::thread::spawn(move || {
-            let mut x = crate::delay::DelayWriter::from_writer(x);
+            let mut x = DelayWriter::from_writer(x);
 
             match x.get_writer_mut() {
                 None => (),
@@ -37,3 +64,135 @@ fn loom() {
         let _b = b.join().unwrap();
     });
 }
+
+#[test]
+fn loom_swap() {
+    loom::model(|| {
+        let writer = new_writer();
+        let mut reader = writer.reader();
+
+        let a = loom::thread::spawn(move || {
+            for _ in 0..2 {
+                let guard = reader.read();
+                guard.with(|x| {
+                    // SAFETY: the read guard ensures that the writer doesn't write to this buffer
+                    let _ = unsafe { *x };
+                });
+            }
+        });
+
+        let mut writer = DelayWriter::from_writer(writer);
+        writer.start_swap();
+
+        while !writer.is_swap_finished() {
+            loom::thread::yield_now();
+        }
+
+        writer.get_writer_mut().unwrap().get().with_mut(|x| {
+            // SAFETY: the swap is finished, so no readers can be in the write buffer
+            unsafe { *x = 1 }
+        });
+
+        a.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_reader_lock() {
+    loom::model(|| {
+        let mut writer = new_writer();
+        let mut reader = writer.reader();
+
+        let a = loom::thread::spawn(move || {
+            let guard = reader.read();
+            guard.extras().with(|x| {
+                // SAFETY: the read guard ensures that the extras aren't mutated
+                let _ = unsafe { *x };
+            });
+        });
+
+        loop {
+            if let Some(extras) = writer.try_extras_mut() {
+                extras.with_mut(|x| {
+                    // SAFETY: the readers are locked out, so no one else can access the extras
+                    unsafe { *x = 1 }
+                });
+                break;
+            }
+
+            loom::thread::yield_now();
+        }
+
+        a.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_backoff() {
+    loom::model(|| {
+        let mut writer = new_writer_with_backoff(BackoffConfig {
+            spin_limit: 1,
+            yield_limit: 2,
+        });
+        let mut reader = writer.reader();
+
+        let a = loom::thread::spawn(move || {
+            let guard = reader.read();
+            guard.with(|x| {
+                // SAFETY: the read guard ensures that the writer doesn't write to this buffer
+                let _ = unsafe { *x };
+            });
+        });
+
+        if let Some(extras) = writer.try_extras_mut() {
+            extras.with_mut(|x| {
+                // SAFETY: the readers are locked out, so no one else can access the extras
+                unsafe { *x = 1 }
+            });
+        }
+
+        // the swap retries with backoff, and then parks until the reader leaves
+        writer.swap();
+
+        writer.get().with_mut(|x| {
+            // SAFETY: the swap is finished, so no readers can be in the write buffer
+            unsafe { *x = 1 }
+        });
+
+        a.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_without_reader_lock() {
+    loom::model(|| {
+        let mut writer = Writer::new(ArcBox::new(DoubleBufferData::with_extras(
+            UnsafeCell::new(0),
+            UnsafeCell::new(0),
+            AtomicStrategy::new_blocking().without_reader_lock(),
+            UnsafeCell::new(0),
+        )));
+        let mut reader = writer.reader();
+
+        // acquire_read_guard debug asserts that readers are never locked out
+        let a = loom::thread::spawn(move || {
+            for _ in 0..2 {
+                let guard = reader.read();
+                guard.with(|x| {
+                    // SAFETY: the read guard ensures that the writer doesn't write to this buffer
+                    let _ = unsafe { *x };
+                });
+            }
+        });
+
+        assert!(writer.try_extras_mut().is_none());
+        writer.swap();
+
+        writer.get().with_mut(|x| {
+            // SAFETY: the swap is finished, so no readers can be in the write buffer
+            unsafe { *x = 1 }
+        });
+
+        a.join().unwrap();
+    });
+}
@@ -2,7 +2,7 @@
 //!
 //! see [`evmap`](https://docs.rs/evmap/latest/evmap/) for more details
 
-use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex, OnceLock, PoisonError};
 
 use crate::interface::{BlockingStrategy, Strategy};
@@ -14,7 +14,8 @@ use triomphe::Arc;
 mod test;
 
 pub struct EvMapStrategy {
-    is_swapped: AtomicBool,
+    // the number of swaps started so far, the low bit is the current parity
+    generation: AtomicUsize,
     epochs: Mutex<Vec<Arc<AtomicUsize>>>,
     condvar: Condvar,
 }
@@ -35,7 +36,7 @@ pub struct ReaderId {
 impl EvMapStrategy {
     pub const fn new() -> Self {
         Self {
-            is_swapped: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
             epochs: Mutex::new(Vec::new()),
             condvar: Condvar::new(),
         }
@@ -72,7 +73,7 @@ unsafe impl Strategy for EvMapStrategy {
     type Swap = Swap;
     type SwapError = core::convert::Infallible;
 
-    type ReadGuard = bool;
+    type ReadGuard = usize;
 
     #[inline]
     unsafe fn create_writer_id(&mut self) -> Self::WriterId {
@@ -103,8 +104,17 @@ unsafe impl Strategy for EvMapStrategy {
         }
     }
 
+    /// Go back to the initial state: generation 0, and no readers
+    fn reset(&mut self) {
+        self.generation = AtomicUsize::new(0);
+        self.epochs
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
     unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
-        // SAFETY: The only write to self.swap_state happens in try_start_swap
+        // SAFETY: The only write to self.generation happens in try_start_swap
         // which needs a &mut Self::WriterId, but we current hold a &Self::WriterId.
         //
         // There are at most 1 Self::WriterId's associated with a given strategy at a time.
@@ -113,18 +123,27 @@ unsafe impl Strategy for EvMapStrategy {
         // So there can be no race between that write and this read.
         //
         // And it is fine to race two (non-atomic) reads
-        unsafe { core::ptr::read(&self.is_swapped).into_inner() }
+        let generation = unsafe { core::ptr::read(&self.generation).into_inner() };
+        generation % 2 == 1
     }
 
     unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
-        *guard
+        *guard % 2 == 1
+    }
+
+    unsafe fn generation(
+        &self,
+        _reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        Some(*guard as u64)
     }
 
     unsafe fn try_start_swap(
         &self,
         writer: &mut Self::WriterId,
     ) -> Result<Self::Swap, Self::SwapError> {
-        self.is_swapped.fetch_xor(true, Ordering::AcqRel);
+        self.generation.fetch_add(1, Ordering::AcqRel);
 
         let mut epochs = self.epochs.lock().unwrap_or_else(PoisonError::into_inner);
 
@@ -147,12 +166,24 @@ unsafe impl Strategy for EvMapStrategy {
         is_swap_finished(&epochs, writer, swap)
     }
 
+    unsafe fn no_active_readers(&self, _writer: &Self::WriterId) -> bool {
+        let epochs = self.epochs.lock().unwrap_or_else(PoisonError::into_inner);
+        // each reader id holds onto it's epoch, so if all epochs are unique then there are no readers
+        epochs.iter().all(Arc::is_unique)
+    }
+
+    unsafe fn estimated_reader_count(&self, _writer: &Self::WriterId) -> Option<usize> {
+        let epochs = self.epochs.lock().unwrap_or_else(PoisonError::into_inner);
+        // dropped readers are only removed on the next swap, and have unique epochs
+        Some(epochs.iter().filter(|epoch| !epoch.is_unique()).count())
+    }
+
     unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
         // this needs to syncronize with `try_start_swap`/`is_swap_finished` (so needs at least `Release`) and
         // it needs to prevent reads from the `raw::ReaderGuard` from being reordered before this (so needs at least `Acquire`)
         // the cheapest ordering which satisfies this is `AcqRel`
         reader.id.fetch_add(1, Ordering::AcqRel);
-        self.is_swapped.load(Ordering::Acquire)
+        self.generation.load(Ordering::Acquire)
     }
 
     unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, _guard: Self::ReadGuard) {
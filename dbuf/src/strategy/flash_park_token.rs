@@ -1,16 +1,17 @@
-use core::{cell::Cell, task::Waker};
-
-#[cfg(feature = "alloc")]
-use core::task::Context;
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Waker},
+};
 
 #[cfg(feature = "std")]
 use std::thread::Thread;
 
 #[cfg(feature = "std")]
 #[derive(Default)]
-pub struct ThreadParkToken(Cell<Option<Thread>>);
+pub struct ThreadParkToken(Slot<Thread>);
 #[derive(Default)]
-pub struct AsyncParkToken(Cell<Option<Waker>>);
+pub struct AsyncParkToken(Slot<Waker>);
 #[cfg(feature = "std")]
 #[derive(Default)]
 pub struct AdaptiveParkToken {
@@ -18,22 +19,78 @@ pub struct AdaptiveParkToken {
     pub(crate) async_token: AsyncParkToken,
 }
 
-#[cfg(feature = "std")]
-// SAFETY: FlashStrategy ensures that all access to the park token
-// by the writer only happens when the residual is negative
-// and by readers when the residual is zero (and by only one reader)
-//
-// These two states are mutually disjoint, so they cannot race
-// All other parts of the FlashStrategy are trivially thread-safe
-unsafe impl Sync for ThreadParkToken {}
-
-// SAFETY: FlashStrategy ensures that all access to the park token
-// by the writer only happens when the residual is negative
-// and by readers when the residual is zero (and by only one reader)
-//
-// These two states are mutually disjoint, so they cannot race
-// All other parts of the FlashStrategy are trivially thread-safe
-unsafe impl Sync for AsyncParkToken {}
+/// A park token for writers which run on a [`tokio`] runtime
+///
+/// This behaves like [`ThreadParkToken`], but if `finish_swap` has to wait on residual readers,
+/// then it parks the thread inside [`tokio::task::block_in_place`]. So the runtime moves
+/// its other tasks off of this worker thread while the writer is blocked, instead of stalling them.
+///
+/// # Constraints
+///
+/// `block_in_place` panics on a [current thread runtime], and `finish_swap` may not unwind
+/// (a panic there aborts the process). So `block_in_place` is only used on a multi-threaded runtime.
+///
+/// * on a current thread runtime (including inside of a [`LocalSet`](tokio::task::LocalSet)
+///   running on one), `finish_swap` parks the thread just like [`ThreadParkToken`], which stalls
+///   every other task on that runtime until the residual readers are done
+/// * outside of a tokio runtime, `finish_swap` parks the thread just like [`ThreadParkToken`]
+///
+/// [current thread runtime]: tokio::runtime::Builder::new_current_thread
+#[cfg(feature = "tokio")]
+#[derive(Default)]
+pub struct TokioParkToken {
+    pub(crate) thread_token: ThreadParkToken,
+}
+
+/// A slot for the writer's thread or waker, which is shared with the last residual reader
+///
+/// The writer registers itself while it is polling the residual, so the last residual reader
+/// may take the value out of the slot at the same time as the writer puts it in. So the slot
+/// is guarded by a tiny spin lock, which is only held while moving values in and out of the slot.
+/// Cloning, dropping and waking all happen outside of the lock, so the lock is never held
+/// while running user code. This doesn't need `std` or the `atomic-waker` crate.
+struct Slot<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<Option<T>>,
+}
+
+// SAFETY: the value is only accessed while the lock is held, and
+// values are moved in and out of the slot, so `T` only needs to be `Send`
+unsafe impl<T: Send> Sync for Slot<T> {}
+
+impl<T> Default for Slot<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Slot<T> {
+    const fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    fn replace(&self, value: Option<T>) -> Option<T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        // SAFETY: the lock is held, so there are no other accesses to the value
+        // and mem::replace can't panic, so the lock is always released
+        let old = unsafe { core::mem::replace(&mut *self.value.get(), value) };
+
+        self.locked.store(false, Ordering::Release);
+
+        old
+    }
+}
 
 mod seal {
     pub trait Seal {}
@@ -42,7 +99,7 @@ mod seal {
 /// This is an internal trait, do not use it directly.
 ///
 /// It only exists as an implementation detail to abstract over [`ThreadParkToken`],
-/// [`AsyncParkToken`], and [`AdaptiveParkToken`]
+/// [`AsyncParkToken`], [`AdaptiveParkToken`], and `TokioParkToken`
 ///
 /// This trait is sealed, so you cannot implement this trait.
 ///
@@ -63,11 +120,11 @@ impl seal::Seal for ThreadParkToken {}
 // SAFETY: thread::park doesn't unwind
 unsafe impl Parker for ThreadParkToken {
     #[doc(hidden)]
-    const NEW: Self = ThreadParkToken(Cell::new(None));
+    const NEW: Self = ThreadParkToken(Slot::new());
 
     #[doc(hidden)]
     unsafe fn wake(&self) {
-        if let Some(thread) = self.0.take() {
+        if let Some(thread) = self.0.replace(None) {
             thread.unpark()
         }
     }
@@ -76,31 +133,29 @@ unsafe impl Parker for ThreadParkToken {
 #[cfg(feature = "std")]
 impl ThreadParkToken {
     pub const fn new() -> Self {
-        Self(Cell::new(None))
+        Self(Slot::new())
     }
 
     pub(in crate::strategy) fn set(&self) {
-        self.0.set(Some(std::thread::current()))
+        drop(self.0.replace(Some(std::thread::current())))
     }
 
     pub(in crate::strategy) fn clear(&self) {
-        self.0.set(None)
+        drop(self.0.replace(None))
     }
 }
 
 impl AsyncParkToken {
     pub const fn new() -> Self {
-        Self(Cell::new(None))
+        Self(Slot::new())
     }
 
-    #[cfg(feature = "alloc")]
     pub(in crate::strategy) fn set(&self, ctx: &mut Context) {
-        self.0.set(Some(ctx.waker().clone()))
+        drop(self.0.replace(Some(ctx.waker().clone())))
     }
 
-    #[cfg(feature = "alloc")]
     pub(in crate::strategy) fn clear(&self) {
-        self.0.set(None);
+        drop(self.0.replace(None))
     }
 }
 
@@ -121,7 +176,7 @@ unsafe impl Parker for AsyncParkToken {
         }
 
         let guard = Bomb;
-        if let Some(waker) = self.0.take() {
+        if let Some(waker) = self.0.replace(None) {
             waker.wake()
         }
         core::mem::forget(guard);
@@ -148,3 +203,27 @@ unsafe impl Parker for AdaptiveParkToken {
         }
     }
 }
+
+#[cfg(feature = "tokio")]
+impl TokioParkToken {
+    pub const fn new() -> Self {
+        Self {
+            thread_token: ThreadParkToken::new(),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl seal::Seal for TokioParkToken {}
+#[cfg(feature = "tokio")]
+// SAFETY: Parker::wake can't unwind for thread_token
+unsafe impl Parker for TokioParkToken {
+    #[doc(hidden)]
+    const NEW: Self = Self::new();
+
+    #[doc(hidden)]
+    unsafe fn wake(&self) {
+        // SAFETY: ensured by caller
+        unsafe { self.thread_token.wake() }
+    }
+}
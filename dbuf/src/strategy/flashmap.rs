@@ -1,18 +1,44 @@
 //! this strategy was inspired by the flashmap crate
 //!
 //! see [`flashmap`](https://docs.rs/flashmap/latest/flashmap/) for more details
-
-use core::{
-    sync::atomic::{AtomicIsize, AtomicUsize, Ordering},
-    task::Poll,
+//!
+//! Dropped readers aren't removed from the list of readers right away, they are only pruned
+//! every few swaps (see [`FlashStrategy::with_prune_interval`]). Until then the writer skips
+//! over them, which is as cheap as checking if they were dropped in the first place.
+//! Pruned slots are kept on a free list, and reused by new readers. So readers which are
+//! frequently created and dropped don't allocate.
+//!
+//! # Leaked read guards
+//!
+//! If a read guard is leaked, then `acquire_read_guard` panics the next time the reader
+//! tries to read. This check costs a load and a branch on every read, so it can be compiled
+//! out in release builds by disabling the `debug-checks` feature (which is on by default). It's
+//! always enabled with `debug_assertions`.
+//!
+//! Leaking a read guard is a logic error, not UB, so this is a tradeoff between catching bugs
+//! and speed. Without the check, the next read takes over the leaked guard's slot. So the
+//! writer may wait on the leaked guard forever, or stop waiting for it after the next read.
+
+use const_fn::const_fn;
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+use core::task::Poll;
+#[cfg(loom)]
+use loom::sync::{
+    atomic::{AtomicIsize, AtomicUsize, Ordering},
+    Mutex,
 };
-use std::sync::{Mutex, OnceLock, PoisonError};
+#[cfg(not(loom))]
+use std::sync::Mutex;
+use std::sync::{OnceLock, PoisonError};
 
 use crate::interface::{AsyncStrategy, BlockingStrategy, Strategy};
 
 use alloc::vec::Vec;
 use triomphe::Arc;
 
+#[cfg(feature = "tokio")]
+use super::flash_park_token::TokioParkToken;
 use super::flash_park_token::{AdaptiveParkToken, AsyncParkToken, Parker, ThreadParkToken};
 
 #[cfg(test)]
@@ -20,22 +46,39 @@ mod test;
 
 pub struct FlashStrategy<ParkToken> {
     swap_state: AtomicUsize,
-    readers: Mutex<Vec<Arc<AtomicUsize>>>,
+    readers: Mutex<Readers>,
     residual: AtomicIsize,
     parker: ParkToken,
+    prune_interval: usize,
 }
 
+struct Readers {
+    slots: Vec<Arc<AtomicUsize>>,
+    // pruned slots, these aren't shared with any reader
+    free: Vec<Arc<AtomicUsize>>,
+    // the number of swaps since dropped readers were last pruned
+    swaps_since_prune: usize,
+}
+
+/// The default number of swaps between each time dropped readers are pruned,
+/// see [`FlashStrategy::with_prune_interval`]
+pub const DEFAULT_PRUNE_INTERVAL: usize = 4;
+
 const _: () = {
     const fn send_sync<T: Send + Sync>() {}
 
     let _ = send_sync::<FlashStrategy<ThreadParkToken>>;
     let _ = send_sync::<FlashStrategy<AsyncParkToken>>;
     let _ = send_sync::<FlashStrategy<AdaptiveParkToken>>;
+    #[cfg(feature = "tokio")]
+    let _ = send_sync::<FlashStrategy<TokioParkToken>>;
 };
 
+// the swap state is laid out as `generation << 1 | READER_ACTIVE`, so each swap
+// adds `SWAPPED` to the state, and the parity of the generation is the `SWAPPED` bit
 const NOT_SWAPPED: usize = 0;
-const SWAPPED: usize = 1;
-const READER_ACTIVE: usize = 2;
+const READER_ACTIVE: usize = 1;
+const SWAPPED: usize = 2;
 
 pub struct WriterId(());
 pub struct ReaderId {
@@ -47,23 +90,36 @@ pub struct ReadGuard {
 }
 
 impl FlashStrategy<ThreadParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new_blocking() -> Self {
         Self::with_park_token()
     }
 }
 
 impl FlashStrategy<AsyncParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new_async() -> Self {
         Self::with_park_token()
     }
 }
 
 impl FlashStrategy<AdaptiveParkToken> {
+    #[const_fn(cfg(not(loom)))]
     pub const fn new() -> Self {
         Self::with_park_token()
     }
 }
 
+#[cfg(feature = "tokio")]
+impl FlashStrategy<TokioParkToken> {
+    /// Create a blocking strategy which yields the tokio worker thread while waiting for readers,
+    /// see [`TokioParkToken`] for the constraints on the runtime
+    #[const_fn(cfg(not(loom)))]
+    pub const fn new_tokio() -> Self {
+        Self::with_park_token()
+    }
+}
+
 impl Default for FlashStrategy<ThreadParkToken> {
     #[inline]
     fn default() -> Self {
@@ -86,21 +142,53 @@ impl Default for FlashStrategy<AdaptiveParkToken> {
 }
 
 impl<ParkToken: Parker> FlashStrategy<ParkToken> {
+    #[const_fn(cfg(not(loom)))]
     const fn with_park_token() -> Self {
         Self {
             swap_state: AtomicUsize::new(NOT_SWAPPED),
-            readers: Mutex::new(Vec::new()),
+            readers: Mutex::new(Readers {
+                slots: Vec::new(),
+                free: Vec::new(),
+                swaps_since_prune: 0,
+            }),
             residual: AtomicIsize::new(0),
             parker: ParkToken::NEW,
+            prune_interval: DEFAULT_PRUNE_INTERVAL,
         }
     }
 }
 
+impl<ParkToken> FlashStrategy<ParkToken> {
+    /// Set how many swaps happen between each time dropped readers are pruned
+    ///
+    /// Pruning less often makes swaps cheaper when readers are frequently created and dropped,
+    /// but dropped readers hold onto their slot (a small allocation) until they are pruned.
+    /// An interval of `0` or `1` prunes on every swap.
+    ///
+    /// By default this is [`DEFAULT_PRUNE_INTERVAL`]
+    #[inline]
+    pub const fn with_prune_interval(mut self, prune_interval: usize) -> Self {
+        self.prune_interval = prune_interval;
+        self
+    }
+}
+
 impl<ParkToken> FlashStrategy<ParkToken> {
     fn create_reader_id(&self) -> ReaderId {
         let mut readers = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
-        let reader = Arc::new(AtomicUsize::new(0));
-        readers.push(reader.clone());
+        // new readers must start out in the current swap state, otherwise they would read from the
+        // writer's buffer. The swap state is only updated while holding the lock, so it can't change
+        // before this reader is added to the list
+        let swap_state = self.swap_state.load(Ordering::Relaxed) & !READER_ACTIVE;
+        let reader = match readers.free.pop() {
+            // free slots aren't shared, so no one else can access this slot
+            Some(reader) => {
+                reader.store(swap_state, Ordering::Relaxed);
+                reader
+            }
+            None => Arc::new(AtomicUsize::new(swap_state)),
+        };
+        readers.slots.push(reader.clone());
         ReaderId { id: reader }
     }
 }
@@ -148,6 +236,21 @@ unsafe impl<ParkToken: Parker> Strategy for FlashStrategy<ParkToken> {
         }
     }
 
+    /// Go back to the initial state: not swapped, and no readers or residual readers
+    ///
+    /// The slots of the old readers are kept on the free list, so new readers can reuse them
+    fn reset(&mut self) {
+        self.swap_state = AtomicUsize::new(NOT_SWAPPED);
+        self.residual = AtomicIsize::new(0);
+
+        let mut readers = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
+        let readers = &mut *readers;
+        readers.swaps_since_prune = 0;
+        // all readers were dropped, so all of these slots should be unique
+        let slots = readers.slots.drain(..).filter(|slot| slot.is_unique());
+        readers.free.extend(slots);
+    }
+
     unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
         // SAFETY: The only write to self.swap_state happens in try_start_swap
         // which needs a &mut Self::WriterId, but we current hold a &Self::WriterId.
@@ -158,45 +261,81 @@ unsafe impl<ParkToken: Parker> Strategy for FlashStrategy<ParkToken> {
         // So there can be no race between that write and this read.
         //
         // And it is fine to race two (non-atomic) reads
+        #[cfg(not(loom))]
         let swap_state = unsafe { core::ptr::read(&self.swap_state).into_inner() };
-        swap_state != NOT_SWAPPED
+        #[cfg(loom)]
+        let swap_state = unsafe { self.swap_state.unsync_load() };
+        swap_state & SWAPPED != 0
     }
 
     unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
-        guard.swap_state != NOT_SWAPPED
+        guard.swap_state & SWAPPED != 0
+    }
+
+    unsafe fn generation(
+        &self,
+        _reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        Some((guard.swap_state >> 1) as u64)
     }
 
     unsafe fn try_start_swap(
         &self,
         _writer: &mut Self::WriterId,
     ) -> Result<Self::Swap, Self::SwapError> {
-        let old_swap_state = self.swap_state.fetch_xor(SWAPPED, Ordering::Release);
-
         let mut readers = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
 
-        let residual_swap_state = old_swap_state | READER_ACTIVE;
+        // this must happen while holding the lock, see `create_reader_id`
+        let old_swap_state = self.swap_state.fetch_add(SWAPPED, Ordering::Release);
+
+        let residual_swap_state = (old_swap_state & SWAPPED) | READER_ACTIVE;
         let mut residual = 0;
 
-        readers.retain(|reader| {
-            // if the reader was dropped, then remove it from the list
+        let Readers {
+            slots,
+            free,
+            swaps_since_prune,
+        } = &mut *readers;
+
+        // this is bounded by the prune interval
+        #[allow(clippy::arithmetic_side_effects)]
+        {
+            *swaps_since_prune += 1;
+        }
+        let should_prune = *swaps_since_prune >= self.prune_interval;
+        if should_prune {
+            *swaps_since_prune = 0;
+        }
+
+        slots.retain(|reader| {
+            // if the reader was dropped, then it can't be reading. So it
+            // doesn't need to be swapped, and may be moved to the free list
             if Arc::is_unique(reader) {
-                return false;
+                if should_prune {
+                    free.push(reader.clone());
+                }
+
+                return !should_prune;
             }
 
             // swap the buffers in each reader
-            let reader_swap_state = reader.fetch_xor(1, Ordering::AcqRel);
+            let reader_swap_state = reader.fetch_add(SWAPPED, Ordering::AcqRel);
 
             // This increment is bounded by the number of readers there are
             // which can never exceed isize::MAX (because of the max allocation
             // size of readers) so this increment can never overflow
             #[allow(clippy::arithmetic_side_effects)]
-            if reader_swap_state == residual_swap_state {
+            if reader_swap_state & (SWAPPED | READER_ACTIVE) == residual_swap_state {
                 residual += 1;
             }
 
             true
         });
 
+        // don't hold onto more free slots than there are readers
+        free.truncate(slots.len());
+
         self.residual.fetch_add(residual, Ordering::Release);
 
         Ok(Swap)
@@ -206,24 +345,65 @@ unsafe impl<ParkToken: Parker> Strategy for FlashStrategy<ParkToken> {
         self.residual.load(Ordering::Acquire) == 0
     }
 
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        // the swap doesn't hold any state, so this is the same as `is_swap_finished`
+        Some(self.residual.load(Ordering::Acquire) == 0)
+    }
+
+    unsafe fn residual_readers(
+        &self,
+        _writer: &Self::WriterId,
+        Swap: &Self::Swap,
+    ) -> Option<usize> {
+        // the residual may be temporarily negative if a reader finished before
+        // the writer registered it as a residual reader
+        Some(usize::try_from(self.residual.load(Ordering::Acquire)).unwrap_or(0))
+    }
+
+    unsafe fn no_active_readers(&self, _writer: &Self::WriterId) -> bool {
+        let readers = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
+        // each reader id holds onto it's slot, so if all slots are unique then there are no readers
+        readers.slots.iter().all(Arc::is_unique)
+    }
+
+    unsafe fn estimated_reader_count(&self, _writer: &Self::WriterId) -> Option<usize> {
+        let readers = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
+        // dropped readers which weren't pruned yet have unique slots
+        Some(
+            readers
+                .slots
+                .iter()
+                .filter(|slot| !slot.is_unique())
+                .count(),
+        )
+    }
+
     unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
-        let reader_id = &*reader.id;
+        if cfg!(any(debug_assertions, feature = "debug-checks")) {
+            // SAFETY: guaranteed by caller
+            unsafe { self.try_acquire_read_guard(reader) }.expect("Detected a leaked read guard")
+        } else {
+            // leaking a guard is a logic error, see the module docs
+            start_read(&reader.id)
+        }
+    }
 
-        assert_eq!(
-            reader_id.load(Ordering::Relaxed) & READER_ACTIVE,
-            0,
-            "Detected a leaked read guard"
-        );
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        if reader.id.load(Ordering::Relaxed) & READER_ACTIVE != 0 {
+            return None;
+        }
 
-        let id = reader_id.fetch_or(READER_ACTIVE, Ordering::Release);
-        ReadGuard { swap_state: id }
+        Some(start_read(&reader.id))
     }
 
     unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
         let reader_swap_state = reader.id.fetch_and(!READER_ACTIVE, Ordering::Release);
 
         // if there wasn't any intervening swap then just return
-        if guard.swap_state & 1 == reader_swap_state & 1 {
+        if guard.swap_state & SWAPPED == reader_swap_state & SWAPPED {
             return;
         }
 
@@ -244,6 +424,13 @@ unsafe impl<ParkToken: Parker> Strategy for FlashStrategy<ParkToken> {
     }
 }
 
+fn start_read(reader_id: &AtomicUsize) -> ReadGuard {
+    // this needs to syncronize with the writer's update in `try_start_swap`, so that the writes
+    // to the buffer before the swap happen before the reads from it (so needs `Acquire`)
+    let id = reader_id.fetch_or(READER_ACTIVE, Ordering::AcqRel);
+    ReadGuard { swap_state: id }
+}
+
 // SAFETY: we check if is_swap_finished would return true before returning Poll::Ready
 unsafe impl AsyncStrategy for FlashStrategy<AsyncParkToken> {
     unsafe fn register_context(
@@ -322,6 +509,41 @@ unsafe impl BlockingStrategy for FlashStrategy<AdaptiveParkToken> {
     }
 }
 
+#[cfg(feature = "tokio")]
+// SAFETY: we check if is_swap_finished would return true before returning
+unsafe impl BlockingStrategy for FlashStrategy<TokioParkToken> {
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, Swap: Self::Swap) {
+        if self
+            .poll(|should_set| {
+                if should_set {
+                    self.parker.thread_token.set()
+                } else {
+                    self.parker.thread_token.clear();
+                }
+            })
+            .is_pending()
+        {
+            let wait = || {
+                while self.residual.load(Ordering::Relaxed) != 0 {
+                    std::thread::park();
+                }
+            };
+
+            // `block_in_place` panics on a current thread runtime, and this runs inside of
+            // `Writer::finish_swap` where a panic aborts. So only use it on a multi-threaded runtime
+            let is_multi_thread = tokio::runtime::Handle::try_current().is_ok_and(|handle| {
+                handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread
+            });
+
+            if is_multi_thread {
+                tokio::task::block_in_place(wait)
+            } else {
+                wait()
+            }
+        }
+    }
+}
+
 impl<T> FlashStrategy<T> {
     fn poll(&self, mut setup: impl FnMut(bool)) -> Poll<()> {
         if self.residual.load(Ordering::Acquire) == 0 {
@@ -4,12 +4,13 @@ use super::FlashStrategy;
 
 use crate::{
     delay::DelayWriter,
-    raw::{DoubleBufferData, Writer},
+    raw::{DoubleBufferData, ReadError, Writer},
     strategy::flash_park_token::AsyncParkToken,
 };
 
 use pollster::test as async_test;
 
+#[cfg(not(loom))]
 #[test]
 fn smoke() {
     let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new());
@@ -36,3 +37,321 @@ fn smoke() {
     // SAFETY: the swap is the latest swap
     unsafe { writer.finish_swap(swap) }
 }
+
+#[cfg(not(loom))]
+#[test]
+fn peek_swap_finished() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new());
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+    assert_eq!(writer.peek_swap_finished(), Some(true));
+
+    let mut reader = writer.reader();
+    let x = reader.read();
+
+    writer.start_swap();
+    assert_eq!(writer.peek_swap_finished(), Some(false));
+    // peeking doesn't finish the swap
+    assert!(writer.has_swap());
+
+    drop(x);
+    assert_eq!(writer.peek_swap_finished(), Some(true));
+    assert!(writer.is_swap_finished());
+}
+
+#[cfg(not(loom))]
+#[test]
+fn get_both_mut() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new());
+    let mut writer = Writer::new(&mut state);
+
+    let (read, write) = writer.try_get_both_mut().unwrap();
+    *read = 10;
+    *write = 10;
+
+    let mut reader = writer.reader();
+    assert!(writer.try_get_both_mut().is_none());
+    assert_eq!(*reader.read(), 10);
+
+    drop(reader);
+    assert!(writer.try_get_both_mut().is_some());
+}
+
+#[cfg(not(loom))]
+#[test]
+fn generation() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new());
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+    assert_eq!(reader.read().generation(), Some(0));
+    assert_eq!(reader.read().generation(), Some(0));
+
+    writer.swap();
+    assert_eq!(reader.read().generation(), Some(1));
+    assert!(reader.read().is_swapped());
+
+    writer.swap();
+    let guard = reader.read();
+    assert_eq!(guard.generation(), Some(2));
+    assert!(!guard.is_swapped());
+    assert_eq!(*guard, 0);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn cancel_swap() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new_blocking());
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    assert!(!writer.cancel_swap());
+
+    assert!(writer.start_swap());
+    assert_eq!(*reader.read(), 1);
+    assert!(writer.cancel_swap());
+    assert!(!writer.has_swap());
+
+    assert_eq!(*reader.read(), 0);
+    let writer = writer.get_writer_mut().unwrap();
+    assert_eq!(*writer.split().read, 0);
+    assert_eq!(*writer.get_mut(), 1);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn swap_scope_drop_finishes_swap() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new_blocking());
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    {
+        let guard = writer.swap_scope();
+        assert_eq!(*guard.split().read, 1);
+        assert_eq!(*reader.read(), 1);
+    }
+
+    assert!(!writer.has_swap());
+    assert_eq!(*writer.get_writer_mut().unwrap().get_mut(), 0);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn swap_scope_finish() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new_blocking());
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    let inner = writer.swap_scope().finish();
+    assert_eq!(*inner.split().read, 1);
+    *inner.get_mut() = 2;
+
+    assert!(!writer.has_swap());
+    assert_eq!(*reader.read(), 1);
+    assert_eq!(*writer.split().write, 2);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn swap_scope_leaked() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new_blocking());
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    let guard = writer.swap_scope();
+    core::mem::forget(guard);
+
+    // the swap was started, but never finished
+    assert!(writer.has_swap());
+    assert!(writer.get_writer_mut().is_none());
+    assert_eq!(*reader.read(), 1);
+
+    assert_eq!(*writer.finish_swap().get_mut(), 0);
+    assert!(!writer.has_swap());
+}
+
+#[cfg(not(loom))]
+#[async_test]
+async fn remaining_residual() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new_async());
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    let guard = reader.read();
+
+    // SAFETY: afinish_swap is polled to completion before split_mut/get_mut is called
+    let mut swap = unsafe { writer.try_start_swap().unwrap() };
+    // SAFETY: the swap is the latest swap
+    let wait = unsafe { writer.afinish_swap(&mut swap) };
+    assert_eq!(wait.remaining_residual(), Some(1));
+
+    drop(guard);
+    assert_eq!(wait.remaining_residual(), Some(0));
+    wait.await;
+}
+
+#[cfg(not(loom))]
+#[test]
+fn try_read_checked() {
+    let mut state = DoubleBufferData::new(0, 1, FlashStrategy::new());
+    let writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    assert_eq!(*reader.try_read_checked().unwrap(), 0);
+
+    core::mem::forget(reader.read());
+    assert!(matches!(
+        reader.try_read_checked(),
+        Err(ReadError::LeakedGuard)
+    ));
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_deferred_prune() {
+    use std::sync::Arc;
+
+    use loom::cell::UnsafeCell;
+    use rc_box::ArcBox;
+
+    type Buffer = DoubleBufferData<UnsafeCell<i32>, FlashStrategy<AsyncParkToken>>;
+
+    loom::model(|| {
+        let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+            UnsafeCell::new(0),
+            UnsafeCell::new(0),
+            FlashStrategy::new_async().with_prune_interval(2),
+        )));
+        let mut writer = DelayWriter::from_writer(writer);
+        let mut reader = writer.reader();
+        // this reader's slot is skipped by the first swap, and pruned by the second one
+        drop(writer.reader());
+
+        let t = loom::thread::spawn(move || {
+            reader.read().with(|_| loom::thread::yield_now());
+            // this reader may be created in the middle of a swap
+            let mut cloned = reader.clone();
+            cloned.read().with(|_| loom::thread::yield_now());
+            (reader, cloned)
+        });
+
+        for _ in 0..2 {
+            writer.start_swap();
+            while !writer.is_swap_finished() {
+                loom::thread::yield_now();
+            }
+
+            let writer = writer.get_writer_mut().unwrap();
+            writer.get().with_mut(|_| loom::thread::yield_now());
+        }
+
+        let (mut reader, mut cloned) = t.join().unwrap();
+        reader.read().with(|_| ());
+        cloned.read().with(|_| ());
+    });
+}
+
+#[cfg(feature = "tokio")]
+#[cfg(not(loom))]
+#[test]
+fn tokio_finish_swap() {
+    use alloc::sync::Arc;
+    use std::sync::mpsc;
+
+    use rc_box::ArcBox;
+
+    use crate::strategy::flash_park_token::TokioParkToken;
+
+    type Buffer = DoubleBufferData<i32, FlashStrategy<TokioParkToken>>;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .build()
+        .unwrap();
+
+    let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        1,
+        FlashStrategy::new_tokio(),
+    )));
+    let mut writer = DelayWriter::from_writer(writer);
+    let mut reader = writer.reader();
+
+    let (started, start) = mpsc::channel();
+    let (released, release) = mpsc::channel::<()>();
+    let t = std::thread::spawn(move || {
+        let guard = reader.read();
+        started.send(()).unwrap();
+        release.recv().unwrap();
+        drop(guard);
+    });
+
+    start.recv().unwrap();
+    writer.start_swap();
+
+    let mut writer = runtime
+        .block_on(runtime.spawn(async move {
+            released.send(()).unwrap();
+            // the reader may still be reading, so this may wait inside of `block_in_place`
+            writer.finish_swap();
+            writer
+        }))
+        .unwrap();
+
+    t.join().unwrap();
+    assert_eq!(*writer.get(), 0);
+
+    // outside of a runtime, this parks the thread as usual
+    writer.start_swap();
+    assert_eq!(*writer.finish_swap().get(), 1);
+}
+
+#[cfg(feature = "tokio")]
+#[cfg(not(loom))]
+#[test]
+fn tokio_finish_swap_current_thread() {
+    use alloc::sync::Arc;
+    use std::sync::mpsc;
+
+    use rc_box::ArcBox;
+
+    use crate::strategy::flash_park_token::TokioParkToken;
+
+    type Buffer = DoubleBufferData<i32, FlashStrategy<TokioParkToken>>;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        1,
+        FlashStrategy::new_tokio(),
+    )));
+    let mut writer = DelayWriter::from_writer(writer);
+    let mut reader = writer.reader();
+
+    let (started, start) = mpsc::channel();
+    let (released, release) = mpsc::channel::<()>();
+    let t = std::thread::spawn(move || {
+        let guard = reader.read();
+        started.send(()).unwrap();
+        release.recv().unwrap();
+        // make sure that the writer has to wait
+        std::thread::sleep(core::time::Duration::from_millis(10));
+        drop(guard);
+    });
+
+    start.recv().unwrap();
+    writer.start_swap();
+
+    // `block_in_place` would panic here, so this must park the thread instead
+    let local = tokio::task::LocalSet::new();
+    let writer = local.block_on(&runtime, async move {
+        released.send(()).unwrap();
+        writer.finish_swap();
+        writer
+    });
+
+    t.join().unwrap();
+    assert_eq!(*writer.get(), 0);
+}
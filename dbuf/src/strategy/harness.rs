@@ -0,0 +1,348 @@
+//! A correctness harness that is run against every strategy
+//!
+//! Readers may be created from the writer or cloned from another reader at any point,
+//! including right after a swap. All readers created this way must observe the buffer
+//! that the writer just published, and must be tracked correctly by later swaps.
+
+use core::fmt::Debug;
+
+use crate::{
+    delay::DelayWriter,
+    interface::BlockingStrategy,
+    raw::{DoubleBufferData, Writer},
+};
+
+fn clone_after_swap<S: BlockingStrategy>(strategy: S)
+where
+    S::SwapError: Debug,
+{
+    let mut state = DoubleBufferData::new(0, 1, strategy);
+    let mut writer = Writer::new(&mut state);
+    let mut before = writer.reader();
+
+    for _ in 0..4 {
+        writer.swap();
+
+        let expected = *writer.split().read;
+        let expected_ptr: *const i32 = writer.split().read;
+        let mut cloned = before.clone();
+        let mut from_writer = writer.reader();
+        let mut from_new = from_writer.clone();
+
+        let swapped = before.read().is_swapped();
+        for reader in [&mut before, &mut cloned, &mut from_writer, &mut from_new] {
+            let guard = reader.read();
+            assert_eq!(*guard, expected);
+            assert_eq!(guard.as_ptr(), expected_ptr);
+            assert_eq!(guard.is_swapped(), swapped);
+        }
+
+        // a reader which was created after the swap must block the next swap
+        // while it's reading, and not after it's done reading
+        let guard = from_new.read();
+        assert_eq!(*guard, expected);
+        drop(guard);
+
+        before = from_new;
+    }
+}
+
+fn generation_after_swap<S: BlockingStrategy>(strategy: S)
+where
+    S::SwapError: Debug,
+{
+    let mut state = DoubleBufferData::new(0, 1, strategy);
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+    let start = reader.read().generation();
+
+    writer.swap();
+    writer.swap();
+    writer.swap();
+
+    let mut cloned = reader.clone();
+    let mut from_writer = writer.reader();
+
+    let expected = reader.read().generation();
+    assert_eq!(cloned.read().generation(), expected);
+    assert_eq!(from_writer.read().generation(), expected);
+
+    if let (Some(start), Some(expected)) = (start, expected) {
+        assert_eq!(expected.wrapping_sub(start), 3);
+    }
+}
+
+fn reader_during_swap<S: BlockingStrategy>(strategy: S)
+where
+    S::SwapError: Debug,
+{
+    let mut state = DoubleBufferData::new(0, 1, strategy);
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut before = writer.reader();
+
+    for _ in 0..4 {
+        writer.start_swap();
+
+        let mut during = writer.reader();
+        let mut cloned = during.clone();
+        // reading while the swap is in progress may see either buffer, but it must
+        // not keep the swap from finishing once the guard is released
+        drop(during.read());
+        drop(cloned.read());
+
+        let expected_ptr: *const i32 = writer.finish_swap().split().read;
+
+        for reader in [&mut before, &mut during, &mut cloned] {
+            assert_eq!(reader.read().as_ptr(), expected_ptr);
+        }
+
+        before = during;
+    }
+}
+
+/// for strategies which reset their swap state, see [`Strategy::reset`](crate::interface::Strategy::reset)
+#[cfg(feature = "std")]
+#[cfg(any(not(loom), feature = "triomphe"))]
+fn reset_after_swap<S: BlockingStrategy>(strategy: S)
+where
+    S::SwapError: Debug,
+{
+    let mut state = DoubleBufferData::new(0, 1, strategy);
+
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+    let _cloned = reader.clone();
+    writer.swap();
+    assert_eq!(*reader.read(), 1);
+    drop((writer, reader, _cloned));
+
+    state.strategy.reset();
+
+    for _ in 0..2 {
+        let mut writer = Writer::new(&mut state);
+        let mut reader = writer.reader();
+        assert_eq!(*reader.read(), 0);
+        assert_eq!(*writer.get(), 1);
+
+        writer.swap();
+        assert_eq!(*reader.read(), 1);
+        writer.swap();
+        drop((writer, reader));
+
+        state.strategy.reset();
+    }
+}
+
+/// [`DelayWriter::afinish_swap`] must be cancellation safe: if the future is dropped after it
+/// registered it's waker, the next call must register the new waker and finish the same swap
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+fn cancel_afinish_swap<S: crate::interface::AsyncStrategy>(strategy: S)
+where
+    S::SwapError: Debug,
+{
+    use alloc::{sync::Arc, task::Wake};
+    use core::{
+        future::Future,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll},
+    };
+
+    #[derive(Default)]
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let mut state = DoubleBufferData::new(0, 1, strategy);
+    let mut writer = DelayWriter::from(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    let guard = reader.read();
+    assert_eq!(*guard, 0);
+    writer.start_swap();
+
+    let stale = Arc::new(CountWakes::default());
+    let fresh = Arc::new(CountWakes::default());
+    let stale_waker = Arc::clone(&stale).into();
+    let fresh_waker = Arc::clone(&fresh).into();
+
+    {
+        let future = pin!(writer.afinish_swap());
+        assert!(future
+            .poll(&mut Context::from_waker(&stale_waker))
+            .is_pending());
+    }
+
+    // the swap wasn't finished, so it must be resumed by the next call
+    assert!(writer.has_swap());
+
+    let mut future = pin!(writer.afinish_swap());
+    assert!(future
+        .as_mut()
+        .poll(&mut Context::from_waker(&fresh_waker))
+        .is_pending());
+
+    drop(guard);
+
+    // the last residual reader must wake the latest waker, not the one from the canceled future
+    assert_eq!(fresh.0.load(Ordering::Relaxed), 1);
+    assert_eq!(stale.0.load(Ordering::Relaxed), 0);
+
+    let Poll::Ready(writer) = future.poll(&mut Context::from_waker(&fresh_waker)) else {
+        panic!("the swap must be finished once the residual reader is gone")
+    };
+
+    assert_eq!(*writer.get(), 0);
+    assert_eq!(*reader.read(), 1);
+}
+
+fn check<S: BlockingStrategy>(new: fn() -> S)
+where
+    S::SwapError: Debug,
+{
+    clone_after_swap(new());
+    generation_after_swap(new());
+    reader_during_swap(new());
+}
+
+#[test]
+fn simple() {
+    check(super::simple::SimpleStrategy::new);
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+fn atomic() {
+    check(super::atomic::AtomicStrategy::new_blocking);
+    reset_after_swap(super::atomic::AtomicStrategy::new_blocking());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn static_slots() {
+    check(super::static_slots::StaticStrategy::<8, _>::new_blocking);
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+fn hazard_flash() {
+    check(super::hazad_flash::HazardFlashStrategy::new_blocking);
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+fn hazard_evmap() {
+    check(super::hazard_evmap::HazardEvMapStrategy::new_blocking);
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(feature = "triomphe")]
+#[cfg(not(loom))]
+fn flashmap() {
+    check(super::flashmap::FlashStrategy::new_blocking);
+    reset_after_swap(super::flashmap::FlashStrategy::new_blocking());
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(feature = "triomphe")]
+fn evmap() {
+    check(super::evmap::EvMapStrategy::new);
+    reset_after_swap(super::evmap::EvMapStrategy::new());
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(feature = "triomphe")]
+#[cfg(not(loom))]
+fn wrappers() {
+    use super::{
+        flashmap::FlashStrategy, instrument::InstrumentedStrategy,
+        notify_readers::NotifyReadersStrategy, strict::StrictStrategy,
+    };
+
+    check(|| InstrumentedStrategy::new(FlashStrategy::new_blocking()));
+    check(|| NotifyReadersStrategy::new(FlashStrategy::new_blocking()));
+    check(|| StrictStrategy::new(FlashStrategy::new_blocking()));
+    reset_after_swap(InstrumentedStrategy::new(FlashStrategy::new_blocking()));
+    reset_after_swap(NotifyReadersStrategy::new(FlashStrategy::new_blocking()));
+    reset_after_swap(StrictStrategy::new(FlashStrategy::new_blocking()));
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+fn cancel_async_swap() {
+    use super::{
+        hazad_flash::HazardFlashStrategy, simple_async::SimpleAsyncStrategy, spsc::SpscStrategy,
+        static_slots::StaticStrategy,
+    };
+
+    cancel_afinish_swap(SimpleAsyncStrategy::new());
+    cancel_afinish_swap(StaticStrategy::<8, _>::new_async());
+    cancel_afinish_swap(HazardFlashStrategy::new_async());
+    cancel_afinish_swap(SpscStrategy::new_async());
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(feature = "triomphe")]
+#[cfg(not(loom))]
+fn cancel_async_swap_flashmap() {
+    use super::flashmap::FlashStrategy;
+
+    cancel_afinish_swap(FlashStrategy::new_async());
+    cancel_afinish_swap(FlashStrategy::new());
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[cfg(feature = "atomic-waker")]
+#[cfg(not(loom))]
+fn cancel_async_swap_atomic() {
+    use super::{atomic::AtomicStrategy, hazard_evmap::HazardEvMapStrategy};
+
+    cancel_afinish_swap(AtomicStrategy::new_async());
+    cancel_afinish_swap(HazardEvMapStrategy::new_async());
+}
+
+#[cfg(loom)]
+#[cfg(feature = "std")]
+#[test]
+fn loom_clone_during_swap() {
+    use std::sync::Arc;
+
+    use rc_box::ArcBox;
+
+    use super::atomic::AtomicStrategy;
+
+    loom::model(|| {
+        let mut writer: Writer<Arc<DoubleBufferData<i32, AtomicStrategy<_>>>> = Writer::new(
+            ArcBox::new(DoubleBufferData::new(0, 1, AtomicStrategy::new_blocking())),
+        );
+        let reader = writer.reader();
+
+        let t = loom::thread::spawn(move || {
+            let mut cloned = reader.clone();
+            drop(cloned.read());
+            (reader, cloned)
+        });
+
+        writer.swap();
+
+        let (mut reader, mut cloned) = t.join().unwrap();
+        let expected = *writer.split().read;
+        assert_eq!(*cloned.read(), expected);
+        assert_eq!(*reader.read(), expected);
+        writer.swap();
+    });
+}
@@ -1,6 +1,14 @@
 //! this strategy was inspired by the flashmap crate
 //!
 //! see [`flashmap`](https://docs.rs/flashmap/latest/flashmap/) for more details
+//!
+//! Only [`HazardFlashStrategy::new_async`] is available without `std`, it only needs `alloc`.
+//! This doesn't need the `atomic-waker` crate, the writer's waker is shared with the readers
+//! through a tiny spin lock (see `flash_park_token`).
+//!
+//! Reader slots are allocated in chunks, which are kept around after the readers are gone.
+//! After a burst of readers, call [`HazardFlashStrategy::compact`] to retire idle chunks,
+//! so swaps don't have to visit them. Retired chunks are reused before allocating new ones.
 
 use crate::{
     interface::{AsyncStrategy, Strategy},
@@ -18,7 +26,7 @@ use sync_wrapper::SyncWrapper;
 use super::flash_park_token::{AdaptiveParkToken, ThreadParkToken};
 use super::{
     flash_park_token::{AsyncParkToken, Parker},
-    hazard::{Hazard, RawHazardGuard},
+    hazard::{Hazard, RawHazardGuard, CHUNK_TAG},
 };
 
 #[cfg(test)]
@@ -34,6 +42,8 @@ pub struct HazardFlashStrategy<P> {
 const _: () = {
     const fn send_sync<T: Send + Sync>() {}
 
+    // the async strategy is thread safe without `std`
+    let _ = send_sync::<HazardFlashStrategy<AsyncParkToken>>;
     #[cfg(feature = "std")]
     let _ = send_sync::<HazardFlashStrategy<AdaptiveParkToken>>;
 };
@@ -42,6 +52,10 @@ const NOT_SWAPPED: usize = 0;
 const SWAPPED: usize = 1;
 const READER_ACTIVE: usize = 2;
 
+// the chunk tag tracks the swap state of all readers in the chunk, so that retired
+// chunks don't need to be visited on each swap
+const CHUNK_SWAPPED: usize = CHUNK_TAG;
+
 pub struct WriterId(());
 pub struct ReaderId {
     id: SyncWrapper<Option<RawHazardGuard<AtomicUsize, 4>>>,
@@ -112,17 +126,44 @@ impl Default for HazardFlashStrategy<AdaptiveParkToken> {
     }
 }
 
+impl<P> HazardFlashStrategy<P> {
+    /// Retire the reader slots which aren't used by any readers
+    ///
+    /// Each swap has to visit every reader slot, so after many readers
+    /// have come and gone this makes swaps cheaper again. Retired slots
+    /// keep their memory, and are reused by new readers before allocating
+    /// new slots.
+    ///
+    /// Returns the number of chunks of reader slots which were retired
+    pub fn compact(&self) -> usize {
+        self.readers.retire_idle()
+    }
+
+    fn new_reader_slot(&self) -> RawHazardGuard<AtomicUsize, 4> {
+        self.readers.get_or_revive_with(
+            || AtomicUsize::new(NOT_SWAPPED),
+            |tag, reader| {
+                let swap_state = if tag & CHUNK_SWAPPED == 0 {
+                    NOT_SWAPPED
+                } else {
+                    SWAPPED
+                };
+                reader.store(swap_state, Ordering::Relaxed);
+            },
+        )
+    }
+}
+
 impl<P: Parker> HazardFlashStrategy<P> {
     fn create_reader_id(&self) -> ReaderId {
-        let id = self.readers.get_or_insert_with(|| AtomicUsize::new(0));
         ReaderId {
-            id: SyncWrapper::new(Some(id)),
+            id: SyncWrapper::new(Some(self.new_reader_slot())),
         }
     }
 
     fn reader_id<'a>(&'a self, reader: &'a mut ReaderId) -> &'a AtomicUsize {
-        let reader_id = (reader.id.get_mut())
-            .get_or_insert_with(|| self.readers.get_or_insert_with(|| AtomicUsize::new(0)));
+        // the old slot is locked by another reader (or retired), so get a new one
+        let reader_id = reader.id.get_mut().insert(self.new_reader_slot());
         // SAFETY: the hazard is still alive, since the HazardFlashStrategy contains it
         unsafe { reader_id.as_ref() }
     }
@@ -202,7 +243,8 @@ unsafe impl<P: Parker> Strategy for HazardFlashStrategy<P> {
         let residual_swap_state = old_swap_state | READER_ACTIVE;
         let mut residual = 0;
 
-        for reader in self.readers.iter() {
+        // retired chunks have no active readers, so they can be skipped
+        self.readers.for_each_live(CHUNK_SWAPPED, |reader| {
             let reader_swap_state = reader.fetch_xor(1, Ordering::AcqRel);
 
             // This increment is bounded by the number of readers there are
@@ -212,7 +254,7 @@ unsafe impl<P: Parker> Strategy for HazardFlashStrategy<P> {
             if reader_swap_state == residual_swap_state {
                 residual += 1;
             }
-        }
+        });
 
         self.residual.fetch_add(residual, Ordering::Release);
 
@@ -223,7 +265,30 @@ unsafe impl<P: Parker> Strategy for HazardFlashStrategy<P> {
         self.residual.load(Ordering::Acquire) == 0
     }
 
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        // the swap doesn't hold any state, so this is the same as `is_swap_finished`
+        Some(self.residual.load(Ordering::Acquire) == 0)
+    }
+
+    unsafe fn residual_readers(
+        &self,
+        _writer: &Self::WriterId,
+        Swap: &Self::Swap,
+    ) -> Option<usize> {
+        // the residual may be temporarily negative if a reader finished before
+        // the writer registered it as a residual reader
+        Some(usize::try_from(self.residual.load(Ordering::Acquire)).unwrap_or(0))
+    }
+
     unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // SAFETY: guaranteed by caller
+        unsafe { self.try_acquire_read_guard(reader) }.expect("Detected a leaked read guard")
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
         let reader_id = if let Some(reader_id) = reader.id.get_mut() {
             // SAFETY: reader is associated from the this HazardFlashStrategy
             // so the RawHazardGuard is still valid
@@ -238,14 +303,12 @@ unsafe impl<P: Parker> Strategy for HazardFlashStrategy<P> {
             self.reader_id(reader)
         };
 
-        assert_eq!(
-            reader_id.load(Ordering::Relaxed) & READER_ACTIVE,
-            0,
-            "Detected a leaked read guard"
-        );
+        if reader_id.load(Ordering::Relaxed) & READER_ACTIVE != 0 {
+            return None;
+        }
 
         let id = reader_id.fetch_or(READER_ACTIVE, Ordering::Release);
-        ReadGuard { swap_state: id }
+        Some(ReadGuard { swap_state: id })
     }
 
     unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
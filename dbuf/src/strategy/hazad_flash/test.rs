@@ -1,4 +1,4 @@
-#![cfg(feature = "std")]
+#![cfg(all(feature = "std", not(loom)))]
 #![allow(unused)]
 
 use super::HazardFlashStrategy as FlashStrategy;
@@ -37,3 +37,44 @@ fn smoke() {
     // SAFETY: the swap is the latest swap
     unsafe { writer.finish_swap(swap) }
 }
+
+#[test]
+fn compact() {
+    use alloc::{sync::Arc, vec::Vec};
+    use rc_box::ArcBox;
+
+    let data = DoubleBufferData::new(0, 1, FlashStrategy::new());
+    let mut writer: Writer<Arc<DoubleBufferData<i32, FlashStrategy<_>>>> =
+        Writer::new(ArcBox::new(data));
+    let mut reader = writer.reader();
+    let compact = |reader: &crate::raw::Reader<_>| {
+        let data: Arc<DoubleBufferData<i32, FlashStrategy<_>>> =
+            crate::interface::DoubleBufferReaderPointer::try_writer(reader.pointer()).unwrap();
+        data.strategy.compact()
+    };
+
+    // 3 chunks of reader slots
+    let mut readers = (0..8).map(|_| writer.reader()).collect::<Vec<_>>();
+    readers.push(reader.clone());
+    drop(reader.read());
+
+    let guards = readers.iter_mut().map(|r| *r.read()).collect::<Vec<_>>();
+    assert_eq!(guards, [0; 9]);
+
+    assert_eq!(compact(&reader), 3);
+    assert_eq!(compact(&reader), 0);
+
+    writer.swap();
+
+    // the retired slots are revived, and caught up with the swap
+    let guards = readers.iter_mut().map(|r| r.read()).collect::<Vec<_>>();
+    assert!(guards.iter().all(|guard| **guard == 1));
+    drop(guards);
+
+    // no new chunks were allocated
+    assert_eq!(compact(&reader), 3);
+
+    writer.swap();
+    assert_eq!(*reader.read(), 0);
+    assert!(readers.iter_mut().all(|r| *r.read() == 0));
+}
@@ -12,12 +12,22 @@ use const_fn::const_fn;
 use crossbeam_utils::CachePadded;
 
 #[cfg(not(loom))]
-use core::sync::atomic::{AtomicBool, AtomicPtr};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
 #[cfg(loom)]
-use loom::sync::atomic::{AtomicBool, AtomicPtr};
+use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
 
 type AtomicHazardPtr<T, const N: usize> = AtomicPtr<HazardNodeChunk<T, N>>;
 
+// the chunk was retired by `retire_idle`, and all of it's nodes are locked
+const RETIRED: usize = 1;
+// a thread is reviving this chunk, which also implies `RETIRED`
+const REVIVING: usize = 2;
+
+/// The lowest bit of a chunk's state which is free for the owner of the `Hazard` to use
+///
+/// see [`Hazard::for_each_live`] and [`Hazard::get_or_revive_with`]
+pub const CHUNK_TAG: usize = 4;
+
 pub struct Hazard<T, const N: usize> {
     head: AtomicHazardPtr<T, N>,
 }
@@ -34,6 +44,8 @@ struct HazardNodeChunk<T, const N: usize> {
     // This ptr is only written to while HazardNodeChunk isn't shared
     // so it doesn't need to be atomic
     next: *mut HazardNodeChunk<T, N>,
+    // a combination of `RETIRED`, `REVIVING` and the owner's tag
+    state: AtomicUsize,
     items: [CachePadded<HazardNode<T, N>>; N],
 }
 
@@ -157,11 +169,17 @@ impl<T, const N: usize> RawHazardGuard<T, N> {
 
     /// Tries to the lock on the node, returning true iff node was locked
     ///
+    /// If this guard already holds the lock, then this returns true
+    ///
     /// # Safety
     ///
     /// The Hazard this guard was derived from must still be alive
     #[must_use]
     pub unsafe fn try_acquire(&mut self) -> bool {
+        if addr(self.node).get() & 1 == 0 {
+            return true;
+        }
+
         let node = map_addr(self.node, |addr| {
             let addr = addr.get() & !1;
             // SAFETY: self.node is aligned to 8 bytes
@@ -253,7 +271,43 @@ impl<T, const N: usize> Hazard<T, N> {
     }
 
     pub fn get_or_insert_with(&self, f: impl FnMut() -> T) -> RawHazardGuard<T, N> {
-        let node = self.nodes(Ordering::Acquire).find_map(|node| {
+        if let Some(node) = self.find_unlocked() {
+            return node;
+        }
+
+        self.insert_with(&mut { f })
+    }
+
+    /// Like [`Self::get_or_insert_with`], but revives a chunk retired by [`Self::retire_idle`]
+    /// before allocating a new chunk
+    ///
+    /// `revive` is called on each node of the revived chunk with the chunk's tag, to bring the
+    /// node up to date with any changes to the tag which happened while the chunk was retired.
+    /// It may be called multiple times per node if the tag changes during the revival.
+    pub fn get_or_revive_with(
+        &self,
+        f: impl FnMut() -> T,
+        revive: impl FnMut(usize, &T),
+    ) -> RawHazardGuard<T, N> {
+        if let Some(node) = self.find_unlocked() {
+            return node;
+        }
+
+        if let Some(node) = self.revive(&mut { revive }) {
+            return node;
+        }
+
+        self.insert_with(&mut { f })
+    }
+
+    fn find_unlocked(&self) -> Option<RawHazardGuard<T, N>> {
+        let mut nodes = self
+            .chunks(Ordering::Acquire)
+            // retired chunks only have locked nodes, so there is no need to look at them
+            .filter(|chunk| chunk.state.load(Ordering::Relaxed) & RETIRED == 0)
+            .flatten();
+
+        nodes.find_map(|node| {
             // on single-threaded tests, the spurious failures are basically impossible
             // to hit. So just pretend that it's compare_exchange so MIRI won't
             // make tests unpredictable. We use loom to ensure that everything is working
@@ -277,13 +331,108 @@ impl<T, const N: usize> Hazard<T, N> {
             } else {
                 None
             }
-        });
+        })
+    }
 
-        if let Some(node) = node {
-            return node;
+    #[cold]
+    #[inline(never)]
+    fn revive(&self, revive: &mut dyn FnMut(usize, &T)) -> Option<RawHazardGuard<T, N>> {
+        let chunk = self.chunks(Ordering::Acquire).find(|chunk| {
+            chunk
+                .state
+                .fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                    (state & (RETIRED | REVIVING) == RETIRED).then_some(state | REVIVING)
+                })
+                .is_ok()
+        })?;
+
+        // all nodes in a retired chunk stay locked until the chunk is revived, so no one else
+        // can access the nodes right now. The tag may still change, so retry until
+        // the nodes are up to date with the tag
+        let mut state = chunk.state.load(Ordering::Acquire);
+        loop {
+            let tag = state & !(RETIRED | REVIVING);
+            chunk.items.iter().for_each(|node| revive(tag, &node.value));
+
+            match chunk
+                .state
+                .compare_exchange_weak(state, tag, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(current) => state = current,
+            }
         }
 
-        self.insert_with(&mut { f })
+        // keep the first node locked for the caller
+        let (first, rest) = chunk.items.split_first()?;
+        for node in rest {
+            node.is_locked.store(false, Ordering::Release);
+        }
+
+        Some(RawHazardGuard {
+            node: NonNull::from(&**first),
+        })
+    }
+
+    /// Retire all chunks which don't have any locked nodes, and return how many chunks were retired
+    ///
+    /// The nodes of retired chunks stay locked, so they are skipped by [`Self::for_each_live`] and
+    /// are never handed out by [`Self::get_or_insert_with`]. They are only reused by
+    /// [`Self::get_or_revive_with`] once all other nodes are locked. The memory of retired chunks
+    /// is only released when the `Hazard` is dropped, since there may still be guards pointing
+    /// into them.
+    pub fn retire_idle(&self) -> usize {
+        let mut retired = 0;
+
+        for chunk in self.chunks(Ordering::Acquire) {
+            if chunk.state.load(Ordering::Relaxed) & RETIRED != 0 {
+                continue;
+            }
+
+            let locked = chunk
+                .items
+                .iter()
+                .take_while(|node| {
+                    node.is_locked
+                        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                        .is_ok()
+                })
+                .count();
+
+            if locked == N {
+                chunk.state.fetch_or(RETIRED, Ordering::Release);
+                // the number of chunks can never exceed usize::MAX,
+                // since each chunk is a separate allocation
+                #[allow(clippy::arithmetic_side_effects)]
+                {
+                    retired += 1;
+                }
+            } else {
+                for node in &chunk.items[..locked] {
+                    node.is_locked.store(false, Ordering::Release);
+                }
+            }
+        }
+
+        retired
+    }
+
+    /// Call `f` on each node in a chunk which isn't retired
+    ///
+    /// Before visiting a chunk, the chunk's tag is updated by xor-ing it with `tag`, which must
+    /// be a multiple of [`CHUNK_TAG`]. This is done atomically with the check for whether
+    /// the chunk is retired, so retired chunks can catch up on changes to the tag
+    /// when they are revived.
+    pub fn for_each_live(&self, tag: usize, mut f: impl FnMut(&T)) {
+        debug_assert_eq!(tag % CHUNK_TAG, 0);
+
+        for chunk in self.chunks(Ordering::Acquire) {
+            let state = chunk.state.fetch_xor(tag, Ordering::AcqRel);
+
+            if state & RETIRED == 0 {
+                chunk.items.iter().for_each(|node| f(&node.value));
+            }
+        }
     }
 
     #[cold]
@@ -303,6 +452,8 @@ impl<T, const N: usize> Hazard<T, N> {
 
         // SAFETY: chunk was just allocated, and we checked the allocation is non-null
         unsafe { core::ptr::addr_of_mut!((*chunk.as_ptr()).next).write(ptr::null_mut()) }
+        // SAFETY: chunk was just allocated, and we checked the allocation is non-null
+        unsafe { core::ptr::addr_of_mut!((*chunk.as_ptr()).state).write(AtomicUsize::new(0)) }
 
         // SAFETY: chunk was just allocated, and we checked the allocation is non-null
         let items = unsafe { core::ptr::addr_of_mut!((*chunk.as_ptr()).items) };
@@ -433,7 +584,7 @@ impl<T, const N: usize> Iterator for RawHazardIter<T, N> {
             .next()
             // SAFETY: since the caller of `raw_iter` ensures that this iterator doesn't outlive the Hazard
             // this pointer is still valid, since it is a part of the Hazard
-            .map(|x| NonNull::from(unsafe { &(*x.as_ptr()).value }))
+            .map(|x| NonNull::from(unsafe { &(**x.as_ptr()).value }))
     }
 }
 
@@ -545,6 +696,7 @@ fn test_basic() {
     }
 }
 
+#[cfg(not(loom))]
 #[test]
 fn test_reuse_and_chunk_count() {
     let hazard = Hazard::<u8, 1>::new();
@@ -578,6 +730,91 @@ fn test_reuse_and_chunk_count() {
     assert_eq!(count_chunks(), 2);
 }
 
+#[cfg(not(loom))]
+#[test]
+fn test_retire_and_revive() {
+    let hazard = Hazard::<u8, 2>::new();
+
+    let count_chunks = || hazard.chunks(Ordering::Relaxed).count();
+
+    let mut a = hazard.get_or_insert_with(|| 0);
+    let mut b = hazard.get_or_insert_with(|| panic!());
+    let c = hazard.get_or_insert_with(|| 1);
+    assert_eq!(count_chunks(), 2);
+
+    // the second chunk is still locked
+    // SAFETY: the hazard is still alive
+    unsafe { a.release() }
+    assert_eq!(hazard.retire_idle(), 0);
+
+    // SAFETY: the hazard is still alive
+    unsafe { b.release() }
+    assert_eq!(hazard.retire_idle(), 1);
+
+    // SAFETY: the hazard is still alive
+    assert!(unsafe { !a.try_acquire() });
+
+    let mut visited = 0;
+    hazard.for_each_live(CHUNK_TAG, |_| visited += 1);
+    assert_eq!(visited, 2);
+
+    // this takes the last free node in the live chunk, instead of the retired ones
+    let e = hazard.get_or_insert_with(|| panic!());
+
+    let mut revived = 0;
+    let d = hazard.get_or_revive_with(
+        || panic!(),
+        |tag, _| {
+            assert_eq!(tag, CHUNK_TAG);
+            revived += 1;
+        },
+    );
+    assert_eq!(revived, 2);
+    assert_eq!(count_chunks(), 2);
+
+    // SAFETY: the hazard is still alive
+    unsafe {
+        assert_eq!(*c.as_ref(), 1);
+        assert_eq!(*d.as_ref(), 0);
+        assert_eq!(*e.as_ref(), 1);
+    }
+
+    // the rest of the revived chunk is available again
+    // SAFETY: the hazard is still alive
+    assert!(unsafe { a.try_acquire() } || unsafe { b.try_acquire() });
+}
+
+#[cfg(loom)]
+#[test]
+fn test_loom_revive() {
+    loom::model(|| {
+        let hazard = loom::sync::Arc::new(Hazard::<core::sync::atomic::AtomicUsize, 1>::new());
+
+        let mut node = hazard.get_or_insert_with(|| core::sync::atomic::AtomicUsize::new(0));
+        unsafe { node.release() }
+        assert_eq!(hazard.retire_idle(), 1);
+
+        let t = loom::thread::spawn({
+            let hazard = hazard.clone();
+            move || {
+                hazard.for_each_live(CHUNK_TAG, |x| {
+                    x.fetch_xor(1, Ordering::Relaxed);
+                })
+            }
+        });
+
+        let node = hazard.get_or_revive_with(
+            || panic!(),
+            |tag, x| x.store(tag / CHUNK_TAG, Ordering::Relaxed),
+        );
+
+        t.join().unwrap();
+
+        // the node was either revived after the update, or updated after the revival
+        assert_eq!(unsafe { node.as_ref() }.load(Ordering::Relaxed), 1);
+    })
+}
+
 #[cfg(loom)]
 #[test]
 fn test_loom_simple() {
@@ -141,8 +141,11 @@ impl<P: Parker> HazardEvMapStrategy<P> {
     }
 
     fn reader_id<'a>(&'a self, reader: &'a mut ReaderId) -> &'a AtomicUsize {
-        let reader_id =
-            (reader.id.get_mut()).get_or_insert_with(|| self.epochs.get_or_insert_with(Epoch::new));
+        // the old slot is locked by another reader, so get a new one
+        let reader_id = reader
+            .id
+            .get_mut()
+            .insert(self.epochs.get_or_insert_with(Epoch::new));
         // SAFETY: the hazard is still alive, since the HazardEvMapStrategy contains it
         &unsafe { reader_id.as_ref() }.current
     }
@@ -1,4 +1,4 @@
-#![cfg(feature = "std")]
+#![cfg(all(feature = "std", not(loom)))]
 #![allow(unused)]
 
 use super::HazardEvMapStrategy;
@@ -37,3 +37,35 @@ fn smoke() {
     // SAFETY: the swap is the latest swap
     unsafe { writer.finish_swap(swap) }
 }
+
+#[test]
+fn reader_slot_taken_by_another_reader() {
+    let mut state = DoubleBufferData::new(0, 1, HazardEvMapStrategy::new_blocking());
+    let mut writer = Writer::new(&mut state);
+
+    let mut a = writer.reader();
+    // reading releases a's slot
+    drop(a.read());
+
+    // so the next reader takes over a's slot
+    let mut b = writer.reader();
+
+    // a must not share the slot with b, otherwise their reads cancel each other out
+    let x = a.read();
+    let y = b.read();
+
+    // SAFETY: finish_swap is called before split_mut/get_mut is called
+    let mut swap = unsafe { writer.try_start_swap().unwrap() };
+
+    // SAFETY: the swap is the latest swap
+    assert!(!unsafe { writer.is_swap_finished(&mut swap) });
+    drop(x);
+    // SAFETY: the swap is the latest swap
+    assert!(!unsafe { writer.is_swap_finished(&mut swap) });
+    drop(y);
+    // SAFETY: the swap is the latest swap
+    assert!(unsafe { writer.is_swap_finished(&mut swap) });
+
+    // SAFETY: the swap is the latest swap
+    unsafe { writer.finish_swap(swap) }
+}
@@ -0,0 +1,303 @@
+//! This is a thin wrapper around another strategy which counts how the strategy is used
+//!
+//! see [`InstrumentedStrategy::metrics`] for which counters are kept
+
+use core::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::interface::{
+    AsyncStrategy, BlockingStrategy, BufferOccupancy, BufferSide, NotifyReaders, ReaderLock,
+    Strategy,
+};
+
+#[cfg(test)]
+mod test;
+
+pub struct InstrumentedStrategy<S> {
+    strategy: S,
+    swaps_started: AtomicU64,
+    swaps_blocked: AtomicU64,
+    read_guards: AtomicU64,
+    active_readers: AtomicUsize,
+    max_concurrent_readers: AtomicUsize,
+}
+
+/// A snapshot of the counters of an [`InstrumentedStrategy`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StrategyMetrics {
+    /// The number of successful calls to [`Strategy::try_start_swap`]
+    pub swaps_started: u64,
+    /// The number of swaps which had to wait on readers before they finished
+    pub swaps_blocked: u64,
+    /// The number of read guards which were acquired
+    pub read_guards: u64,
+    /// The most read guards which were active at the same time
+    pub max_concurrent_readers: usize,
+}
+
+pub struct Swap<S> {
+    swap: S,
+    blocked: bool,
+}
+
+impl<S> InstrumentedStrategy<S> {
+    pub const fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            swaps_started: AtomicU64::new(0),
+            swaps_blocked: AtomicU64::new(0),
+            read_guards: AtomicU64::new(0),
+            active_readers: AtomicUsize::new(0),
+            max_concurrent_readers: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the inner strategy
+    pub const fn get_ref(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Get a snapshot of the counters
+    ///
+    /// The counters are read independently, so they may be slightly out of sync
+    /// with each other while the double buffer is in use
+    pub fn metrics(&self) -> StrategyMetrics {
+        StrategyMetrics {
+            swaps_started: self.swaps_started.load(Ordering::Relaxed),
+            swaps_blocked: self.swaps_blocked.load(Ordering::Relaxed),
+            read_guards: self.read_guards.load(Ordering::Relaxed),
+            max_concurrent_readers: self.max_concurrent_readers.load(Ordering::Relaxed),
+        }
+    }
+
+    /// count the swap as blocked, if it wasn't already counted
+    fn mark_blocked<T>(&self, swap: &mut Swap<T>) {
+        if !swap.blocked {
+            swap.blocked = true;
+            self.swaps_blocked.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// count a new read guard, and update the max concurrent readers
+    fn record_read_guard(&self) {
+        self.read_guards.fetch_add(1, Ordering::Relaxed);
+        let active = self
+            .active_readers
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1);
+        self.max_concurrent_readers
+            .fetch_max(active, Ordering::Relaxed);
+    }
+}
+
+impl<S: Default> Default for InstrumentedStrategy<S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+/// SAFETY: defer to the safety of S, since all methods defer to `S`
+unsafe impl<S: Strategy> Strategy for InstrumentedStrategy<S> {
+    type WriterId = S::WriterId;
+    type ReaderId = S::ReaderId;
+    type Swap = Swap<S::Swap>;
+    type SwapError = S::SwapError;
+    type ReadGuard = S::ReadGuard;
+
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        // SAFETY: defer to S::create_writer_id
+        unsafe { self.strategy.create_writer_id() }
+    }
+
+    unsafe fn create_reader_id_from_writer(&self, writer: &Self::WriterId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_writer
+        unsafe { self.strategy.create_reader_id_from_writer(writer) }
+    }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &Self::ReaderId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_reader
+        unsafe { self.strategy.create_reader_id_from_reader(reader) }
+    }
+
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        // SAFETY: defer to S::create_detached_reader_id
+        unsafe { S::create_detached_reader_id(reader) }
+    }
+
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        S::create_invalid_reader_id()
+    }
+
+    /// Reset the inner strategy, the counters aren't reset
+    fn reset(&mut self) {
+        self.strategy.reset()
+    }
+
+    unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::is_swapped_writer
+        unsafe { self.strategy.is_swapped_writer(writer) }
+    }
+
+    unsafe fn is_swapped(&self, reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        // SAFETY: defer to S::is_swapped
+        unsafe { self.strategy.is_swapped(reader, guard) }
+    }
+
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        // SAFETY: defer to S::generation
+        unsafe { self.strategy.generation(reader, guard) }
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        // SAFETY: defer to S::try_start_swap
+        let swap = unsafe { self.strategy.try_start_swap(writer)? };
+        self.swaps_started.fetch_add(1, Ordering::Relaxed);
+        Ok(Swap {
+            swap,
+            blocked: false,
+        })
+    }
+
+    unsafe fn is_swap_finished(&self, writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool {
+        // SAFETY: defer to S::is_swap_finished
+        let finished = unsafe { self.strategy.is_swap_finished(writer, &mut swap.swap) };
+        if !finished {
+            self.mark_blocked(swap);
+        }
+        finished
+    }
+
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        // SAFETY: defer to S::residual_readers
+        unsafe { self.strategy.residual_readers(writer, &swap.swap) }
+    }
+
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        // SAFETY: defer to S::is_swap_finished_shared
+        unsafe { self.strategy.is_swap_finished_shared(writer) }
+    }
+
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::no_active_readers
+        unsafe { self.strategy.no_active_readers(writer) }
+    }
+
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        // SAFETY: defer to S::estimated_reader_count
+        unsafe { self.strategy.estimated_reader_count(writer) }
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // SAFETY: defer to S::acquire_read_guard
+        let guard = unsafe { self.strategy.acquire_read_guard(reader) };
+        self.record_read_guard();
+        guard
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::try_acquire_read_guard
+        let guard = unsafe { self.strategy.try_acquire_read_guard(reader) }?;
+        self.record_read_guard();
+        Some(guard)
+    }
+
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::acquire_read_guard_timeout
+        let guard = unsafe { self.strategy.acquire_read_guard_timeout(reader, timeout) }?;
+        self.record_read_guard();
+        Some(guard)
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        self.active_readers.fetch_sub(1, Ordering::Relaxed);
+        // SAFETY: defer to S::release_read_guard
+        unsafe { self.strategy.release_read_guard(reader, guard) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BlockingStrategy> BlockingStrategy for InstrumentedStrategy<S> {
+    unsafe fn finish_swap(&self, writer: &mut Self::WriterId, mut swap: Self::Swap) {
+        // SAFETY: defer to S::is_swap_finished
+        if !swap.blocked && !unsafe { self.strategy.is_swap_finished(writer, &mut swap.swap) } {
+            self.mark_blocked(&mut swap);
+        }
+
+        // SAFETY: defer to S::finish_swap
+        unsafe { self.strategy.finish_swap(writer, swap.swap) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: AsyncStrategy> AsyncStrategy for InstrumentedStrategy<S> {
+    unsafe fn register_context(
+        &self,
+        writer: &mut Self::WriterId,
+        swap: &mut Self::Swap,
+        ctx: &mut Context<'_>,
+    ) -> Poll<()> {
+        // SAFETY: defer to S::register_context
+        let poll = unsafe { self.strategy.register_context(writer, &mut swap.swap, ctx) };
+        if poll.is_pending() {
+            self.mark_blocked(swap);
+        }
+        poll
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BufferOccupancy> BufferOccupancy for InstrumentedStrategy<S> {
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool {
+        // SAFETY: defer to S::is_buffer_empty
+        unsafe { self.strategy.is_buffer_empty(writer, side) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: ReaderLock> ReaderLock for InstrumentedStrategy<S> {
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool {
+        // SAFETY: defer to S::try_lock_readers
+        unsafe { self.strategy.try_lock_readers(writer) }
+    }
+
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId) {
+        // SAFETY: defer to S::unlock_readers
+        unsafe { self.strategy.unlock_readers(writer) }
+    }
+}
+
+impl<S: NotifyReaders> NotifyReaders for InstrumentedStrategy<S> {
+    unsafe fn wait_for_swap(&self, reader: &mut Self::ReaderId) {
+        // SAFETY: defer to S::wait_for_swap
+        unsafe { self.strategy.wait_for_swap(reader) }
+    }
+
+    unsafe fn wait_for_swap_timeout(&self, reader: &mut Self::ReaderId, timeout: Duration) -> bool {
+        // SAFETY: defer to S::wait_for_swap_timeout
+        unsafe { self.strategy.wait_for_swap_timeout(reader, timeout) }
+    }
+
+    unsafe fn poll_for_swap(&self, reader: &mut Self::ReaderId, ctx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: defer to S::poll_for_swap
+        unsafe { self.strategy.poll_for_swap(reader, ctx) }
+    }
+}
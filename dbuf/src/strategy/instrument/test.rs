@@ -0,0 +1,41 @@
+use super::{InstrumentedStrategy, StrategyMetrics};
+
+use crate::{
+    delay::DelayWriter,
+    raw::{DoubleBufferData, Writer},
+    strategy::simple_async::SimpleAsyncStrategy,
+};
+
+#[test]
+fn metrics() {
+    let mut state =
+        DoubleBufferData::new(0, 1, InstrumentedStrategy::new(SimpleAsyncStrategy::new()));
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+
+    let mut reader = writer.reader();
+    let mut other = reader;
+
+    let a = reader.read();
+    let b = other.read();
+    drop((a, b));
+    let a = reader.read();
+
+    writer.start_swap();
+    assert!(!writer.is_swap_finished());
+    assert!(!writer.is_swap_finished());
+    drop(a);
+    assert!(writer.is_swap_finished());
+
+    writer.start_swap();
+    assert!(writer.is_swap_finished());
+
+    assert_eq!(
+        state.strategy.metrics(),
+        StrategyMetrics {
+            swaps_started: 2,
+            swaps_blocked: 1,
+            read_guards: 3,
+            max_concurrent_readers: 2,
+        }
+    );
+}
@@ -0,0 +1,367 @@
+//! This is a thin wrapper around another strategy which writes a record to a journal before each swap
+//!
+//! Each record is the generation of the swap as 8 little-endian bytes. The generation
+//! starts at [`JournaledStrategy::with_generation`] (0 by default), and is incremented by each swap
+//! which started successfully. After a crash, [`last_generation`] reads back the generation
+//! of the last record, which is the last swap that *may* have been published.
+//!
+//! The record is written and flushed before the swap is started, so the process may have crashed
+//! after writing the record but before publishing the swap. If writing the record fails,
+//! then the swap isn't started, and the error is returned from `try_start_swap`. So a swap is never
+//! published without being journaled. But if the inner strategy fails to start the swap after
+//! the record was written, then the same generation is written again by the next swap.
+//!
+//! A failed write may leave part of a record in the journal, and any record written after it would
+//! be misaligned. So a failed write poisons the strategy, and every later swap fails with
+//! [`JournalError::Poisoned`] until [`JournaledStrategy::clear_poison`] is called.
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::io;
+
+use crate::interface::{
+    AsyncStrategy, BlockingStrategy, BufferOccupancy, BufferSide, NotifyReaders, ReaderLock,
+    Strategy,
+};
+
+#[cfg(test)]
+mod test;
+
+pub struct JournaledStrategy<S, W> {
+    // this may only be accessed with a `&mut JournaledWriterId`
+    journal: UnsafeCell<Journal<W>>,
+    strategy: S,
+}
+
+struct Journal<W> {
+    writer: W,
+    generation: u64,
+    poisoned: bool,
+}
+
+// SAFETY: From a shared reference to `JournaledStrategy<S, W>` you can get
+// * a shared reference to S
+// * an exclusive reference to W (only with the unique writer id)
+unsafe impl<S: Sync, W: Send> Sync for JournaledStrategy<S, W> {}
+
+pub struct JournaledWriterId<I> {
+    id: I,
+}
+
+/// The error returned when starting a swap of a [`JournaledStrategy`]
+#[derive(Debug)]
+pub enum JournalError<E> {
+    /// Writing the record to the journal failed, so the swap wasn't started
+    ///
+    /// This poisons the strategy
+    Journal(io::Error),
+    /// An earlier write to the journal failed, so the swap wasn't started
+    Poisoned,
+    /// The inner strategy failed to start the swap
+    Swap(E),
+}
+
+impl<E: fmt::Display> fmt::Display for JournalError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Journal(err) => write!(f, "could not write to the journal: {err}"),
+            Self::Poisoned => f.write_str("the journal was poisoned by an earlier failed write"),
+            Self::Swap(err) => write!(f, "could not start the swap: {err}"),
+        }
+    }
+}
+
+impl<S, W> JournaledStrategy<S, W> {
+    pub const fn new(strategy: S, journal: W) -> Self {
+        Self {
+            journal: UnsafeCell::new(Journal {
+                writer: journal,
+                generation: 0,
+                poisoned: false,
+            }),
+            strategy,
+        }
+    }
+
+    /// Set the generation of the last swap, the next swap will write `generation + 1`
+    ///
+    /// This is useful to continue a journal after recovering from it, see [`last_generation`]
+    #[inline]
+    pub const fn with_generation(mut self, generation: u64) -> Self {
+        self.journal.get_mut().generation = generation;
+        self
+    }
+
+    /// The generation of the last swap which was started
+    #[inline]
+    pub const fn generation(&mut self) -> u64 {
+        self.journal.get_mut().generation
+    }
+
+    /// Returns true if writing a record failed, see [`JournalError::Journal`]
+    #[inline]
+    pub const fn is_poisoned(&mut self) -> bool {
+        self.journal.get_mut().poisoned
+    }
+
+    /// Allow swaps again after writing a record failed
+    ///
+    /// The journal may end with part of a record, which must be removed (for example by
+    /// truncating the journal to a multiple of 8 bytes) before clearing the poison.
+    /// Otherwise every record written after it would be misaligned.
+    #[inline]
+    pub const fn clear_poison(&mut self) {
+        self.journal.get_mut().poisoned = false;
+    }
+
+    /// Get exclusive access to the journal
+    #[inline]
+    pub const fn journal_mut(&mut self) -> &mut W {
+        &mut self.journal.get_mut().writer
+    }
+
+    /// Get the inner strategy and the journal
+    #[inline]
+    pub fn into_inner(self) -> (S, W) {
+        (self.strategy, self.journal.into_inner().writer)
+    }
+}
+
+impl<S, W: io::Write> JournaledStrategy<S, W> {
+    fn journal<'a, I>(&'a self, _writer: &'a mut JournaledWriterId<I>) -> &'a mut Journal<W> {
+        // SAFETY: all users of the journal (in this module) are safe
+        // because they all require a valid `&'a mut JournaledWriterId`, which was
+        // created by this `JournaledStrategy`. There is only one valid writer id at a time,
+        // so this is the only reference to the journal.
+        unsafe { &mut *self.journal.get() }
+    }
+}
+
+/// Read back the generation of the last record in a journal written by [`JournaledStrategy`]
+///
+/// Returns `None` if there are no complete records. A partially written record at the end
+/// of the journal (for example, if the process crashed while writing it) is ignored.
+pub fn last_generation(mut journal: impl io::Read) -> io::Result<Option<u64>> {
+    let mut last = None;
+    let mut record = [0; 8];
+
+    loop {
+        let mut len = 0;
+
+        while len < record.len() {
+            match journal.read(&mut record[len..]) {
+                Ok(0) => return Ok(last),
+                // read never returns more bytes than the buffer can hold
+                #[allow(clippy::arithmetic_side_effects)]
+                Ok(n) => len += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        }
+
+        last = Some(u64::from_le_bytes(record));
+    }
+}
+
+/// SAFETY: defer to the safety of S, since all methods defer to `S`
+unsafe impl<S: Strategy, W: io::Write> Strategy for JournaledStrategy<S, W> {
+    type WriterId = JournaledWriterId<S::WriterId>;
+    type ReaderId = S::ReaderId;
+    type Swap = S::Swap;
+    type SwapError = JournalError<S::SwapError>;
+    type ReadGuard = S::ReadGuard;
+
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        JournaledWriterId {
+            // SAFETY: defer to S::create_writer_id
+            id: unsafe { self.strategy.create_writer_id() },
+        }
+    }
+
+    unsafe fn create_reader_id_from_writer(&self, writer: &Self::WriterId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_writer
+        unsafe { self.strategy.create_reader_id_from_writer(&writer.id) }
+    }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &Self::ReaderId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_reader
+        unsafe { self.strategy.create_reader_id_from_reader(reader) }
+    }
+
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        // SAFETY: defer to S::create_detached_reader_id
+        unsafe { S::create_detached_reader_id(reader) }
+    }
+
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        S::create_invalid_reader_id()
+    }
+
+    /// Reset the inner strategy, the generation isn't reset so the journal stays ordered
+    fn reset(&mut self) {
+        self.strategy.reset()
+    }
+
+    unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::is_swapped_writer
+        unsafe { self.strategy.is_swapped_writer(&writer.id) }
+    }
+
+    unsafe fn is_swapped(&self, reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        // SAFETY: defer to S::is_swapped
+        unsafe { self.strategy.is_swapped(reader, guard) }
+    }
+
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        // SAFETY: defer to S::generation
+        unsafe { self.strategy.generation(reader, guard) }
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        let journal = self.journal(writer);
+        let generation = journal.generation.wrapping_add(1);
+
+        if journal.poisoned {
+            return Err(JournalError::Poisoned);
+        }
+
+        if let Err(err) = journal
+            .writer
+            .write_all(&generation.to_le_bytes())
+            .and_then(|()| journal.writer.flush())
+        {
+            journal.poisoned = true;
+            return Err(JournalError::Journal(err));
+        }
+
+        // SAFETY: defer to S::try_start_swap
+        let swap =
+            unsafe { self.strategy.try_start_swap(&mut writer.id) }.map_err(JournalError::Swap)?;
+
+        self.journal(writer).generation = generation;
+
+        Ok(swap)
+    }
+
+    unsafe fn is_swap_finished(&self, writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool {
+        // SAFETY: defer to S::is_swap_finished
+        unsafe { self.strategy.is_swap_finished(&mut writer.id, swap) }
+    }
+
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        // SAFETY: defer to S::residual_readers
+        unsafe { self.strategy.residual_readers(&writer.id, swap) }
+    }
+
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        // SAFETY: defer to S::is_swap_finished_shared
+        unsafe { self.strategy.is_swap_finished_shared(&writer.id) }
+    }
+
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::no_active_readers
+        unsafe { self.strategy.no_active_readers(&writer.id) }
+    }
+
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        // SAFETY: defer to S::estimated_reader_count
+        unsafe { self.strategy.estimated_reader_count(&writer.id) }
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // SAFETY: defer to S::acquire_read_guard
+        unsafe { self.strategy.acquire_read_guard(reader) }
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::try_acquire_read_guard
+        unsafe { self.strategy.try_acquire_read_guard(reader) }
+    }
+
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::acquire_read_guard_timeout
+        unsafe { self.strategy.acquire_read_guard_timeout(reader, timeout) }
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        // SAFETY: defer to S::release_read_guard
+        unsafe { self.strategy.release_read_guard(reader, guard) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BlockingStrategy, W: io::Write> BlockingStrategy for JournaledStrategy<S, W> {
+    unsafe fn finish_swap(&self, writer: &mut Self::WriterId, swap: Self::Swap) {
+        // SAFETY: defer to S::finish_swap
+        unsafe { self.strategy.finish_swap(&mut writer.id, swap) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: AsyncStrategy, W: io::Write> AsyncStrategy for JournaledStrategy<S, W> {
+    unsafe fn register_context(
+        &self,
+        writer: &mut Self::WriterId,
+        swap: &mut Self::Swap,
+        ctx: &mut Context<'_>,
+    ) -> Poll<()> {
+        // SAFETY: defer to S::register_context
+        unsafe { self.strategy.register_context(&mut writer.id, swap, ctx) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BufferOccupancy, W: io::Write> BufferOccupancy for JournaledStrategy<S, W> {
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool {
+        // SAFETY: defer to S::is_buffer_empty
+        unsafe { self.strategy.is_buffer_empty(&writer.id, side) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: ReaderLock, W: io::Write> ReaderLock for JournaledStrategy<S, W> {
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool {
+        // SAFETY: defer to S::try_lock_readers
+        unsafe { self.strategy.try_lock_readers(&mut writer.id) }
+    }
+
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId) {
+        // SAFETY: defer to S::unlock_readers
+        unsafe { self.strategy.unlock_readers(&mut writer.id) }
+    }
+}
+
+impl<S: NotifyReaders, W: io::Write> NotifyReaders for JournaledStrategy<S, W> {
+    unsafe fn wait_for_swap(&self, reader: &mut Self::ReaderId) {
+        // SAFETY: defer to S::wait_for_swap
+        unsafe { self.strategy.wait_for_swap(reader) }
+    }
+
+    unsafe fn wait_for_swap_timeout(&self, reader: &mut Self::ReaderId, timeout: Duration) -> bool {
+        // SAFETY: defer to S::wait_for_swap_timeout
+        unsafe { self.strategy.wait_for_swap_timeout(reader, timeout) }
+    }
+
+    unsafe fn poll_for_swap(&self, reader: &mut Self::ReaderId, ctx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: defer to S::poll_for_swap
+        unsafe { self.strategy.poll_for_swap(reader, ctx) }
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+use std::io;
+
+use super::{last_generation, JournalError, JournaledStrategy};
+
+use crate::{
+    raw::{DoubleBufferData, Writer},
+    strategy::simple::SimpleStrategy,
+};
+
+#[test]
+fn journal() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        JournaledStrategy::new(SimpleStrategy::new(), Vec::new()),
+    );
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    writer.swap();
+    let guard = reader.read();
+    assert!(matches!(writer.try_swap(), Err(JournalError::Swap(_))));
+    drop(guard);
+    writer.swap();
+
+    // the failed swap wrote the same generation as the one after it
+    assert_eq!(state.strategy.generation(), 2);
+    let journal = state.strategy.journal_mut();
+    assert_eq!(journal.len(), 24);
+    assert_eq!(last_generation(&journal[..]).unwrap(), Some(2));
+
+    // torn records are ignored
+    journal.extend([0xff; 3]);
+    assert_eq!(last_generation(&journal[..]).unwrap(), Some(2));
+    assert_eq!(last_generation(&[][..]).unwrap(), None);
+}
+
+struct FailingJournal;
+
+impl io::Write for FailingJournal {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_journal_aborts_swap() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        JournaledStrategy::new(SimpleStrategy::new(), FailingJournal).with_generation(10),
+    );
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    assert!(matches!(writer.try_swap(), Err(JournalError::Journal(_))));
+    assert_eq!(*reader.read(), 0);
+    assert_eq!(state.strategy.generation(), 10);
+}
+
+/// A journal which fails after `capacity` bytes were written
+struct ShortJournal {
+    data: Vec<u8>,
+    capacity: usize,
+}
+
+impl io::Write for ShortJournal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.capacity.saturating_sub(self.data.len()));
+
+        if len == 0 {
+            return Err(io::Error::other("disk full"));
+        }
+
+        self.data.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn partial_write_poisons() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        JournaledStrategy::new(
+            SimpleStrategy::new(),
+            ShortJournal {
+                data: Vec::new(),
+                capacity: 12,
+            },
+        ),
+    );
+    let mut writer = Writer::new(&mut state);
+
+    writer.swap();
+    assert!(matches!(writer.try_swap(), Err(JournalError::Journal(_))));
+    assert!(matches!(writer.try_swap(), Err(JournalError::Poisoned)));
+
+    assert!(state.strategy.is_poisoned());
+    assert_eq!(state.strategy.generation(), 1);
+    let journal = state.strategy.journal_mut();
+    assert_eq!(journal.data.len(), 12);
+    assert_eq!(last_generation(&journal.data[..]).unwrap(), Some(1));
+
+    // remove the torn record before clearing the poison
+    journal.data.truncate(8);
+    journal.capacity = usize::MAX;
+    state.strategy.clear_poison();
+
+    let mut writer = Writer::new(&mut state);
+    writer.swap();
+
+    let journal = state.strategy.journal_mut();
+    assert_eq!(last_generation(&journal.data[..]).unwrap(), Some(2));
+}
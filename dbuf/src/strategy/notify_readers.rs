@@ -0,0 +1,377 @@
+//! This is a wrapper around another strategy which allows readers to wait for the next swap
+//!
+//! The wrapper keeps a generation counter, which is bumped after each successful swap of
+//! the inner strategy. Readers remember the generation they last read, and
+//! [`Reader::wait_for_swap`](crate::raw::Reader::wait_for_swap) parks the reader until
+//! the generation changes.
+//!
+//! Reads don't take any locks, so this composes with lock-free strategies such as
+//! [`FlashStrategy`](crate::strategy::flashmap::FlashStrategy), only waiting readers and
+//! swaps while there are waiting readers touch the lock.
+//!
+//! Async readers can register a waker with
+//! [`Reader::poll_swap`](crate::raw::Reader::poll_swap), these are woken after the next swap.
+
+use alloc::vec::Vec;
+use core::{
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use std::{
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
+
+use crate::interface::{
+    AsyncStrategy, BlockingStrategy, BufferOccupancy, BufferSide, NotifyReaders, ReaderLock,
+    Strategy,
+};
+
+#[cfg(test)]
+mod test;
+
+pub struct NotifyReadersStrategy<S> {
+    strategy: S,
+    generation: AtomicUsize,
+    // the number of blocked readers plus the number of registered wakers
+    waiters: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+}
+
+pub struct ReaderId<Id> {
+    id: Id,
+    // the generation of the last buffer this reader saw
+    seen: usize,
+}
+
+impl<S> NotifyReadersStrategy<S> {
+    pub const fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            generation: AtomicUsize::new(0),
+            waiters: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Get the inner strategy
+    pub const fn get_ref(&self) -> &S {
+        &self.strategy
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify_waiters(&self) {
+        if self.waiters.load(Ordering::SeqCst) != 0 {
+            // take the lock, so that a reader can't miss this notification between
+            // checking the generation and waiting on the condvar
+            let mut wakers = core::mem::take(&mut *self.lock());
+            self.waiters.fetch_sub(wakers.len(), Ordering::SeqCst);
+            self.condvar.notify_all();
+            wakers.drain(..).for_each(Waker::wake);
+        }
+    }
+
+    fn reader_id<Id>(&self, id: Id) -> ReaderId<Id> {
+        ReaderId {
+            id,
+            seen: self.generation.load(Ordering::Acquire),
+        }
+    }
+}
+
+impl<S> Drop for NotifyReadersStrategy<S> {
+    fn drop(&mut self) {
+        // wake any async readers, so they can observe that the writer is gone
+        let wakers = self
+            .wakers
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl<S: Default> Default for NotifyReadersStrategy<S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+/// SAFETY: defer to the safety of S, since all methods defer to `S`
+unsafe impl<S: Strategy> Strategy for NotifyReadersStrategy<S> {
+    type WriterId = S::WriterId;
+    type ReaderId = ReaderId<S::ReaderId>;
+    type Swap = S::Swap;
+    type SwapError = S::SwapError;
+    type ReadGuard = S::ReadGuard;
+
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        // SAFETY: defer to S::create_writer_id
+        unsafe { self.strategy.create_writer_id() }
+    }
+
+    unsafe fn create_reader_id_from_writer(&self, writer: &Self::WriterId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_writer
+        self.reader_id(unsafe { self.strategy.create_reader_id_from_writer(writer) })
+    }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &Self::ReaderId) -> Self::ReaderId {
+        ReaderId {
+            // SAFETY: defer to S::create_reader_id_from_reader
+            id: unsafe { self.strategy.create_reader_id_from_reader(&reader.id) },
+            seen: reader.seen,
+        }
+    }
+
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        Some(ReaderId {
+            // SAFETY: defer to S::create_detached_reader_id
+            id: unsafe { S::create_detached_reader_id(&reader.id)? },
+            seen: reader.seen,
+        })
+    }
+
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        ReaderId {
+            id: S::create_invalid_reader_id(),
+            seen: 0,
+        }
+    }
+
+    /// Reset the inner strategy, and forget all waiting readers
+    fn reset(&mut self) {
+        self.strategy.reset();
+        *self.waiters.get_mut() = 0;
+        self.wakers
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::is_swapped_writer
+        unsafe { self.strategy.is_swapped_writer(writer) }
+    }
+
+    unsafe fn is_swapped(&self, reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        // SAFETY: defer to S::is_swapped
+        unsafe { self.strategy.is_swapped(&mut reader.id, guard) }
+    }
+
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        // SAFETY: defer to S::generation
+        unsafe { self.strategy.generation(&mut reader.id, guard) }
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        // SAFETY: defer to S::try_start_swap
+        let swap = unsafe { self.strategy.try_start_swap(writer)? };
+
+        // the generation must be bumped after the swap, so that readers
+        // never see a generation that is newer than the buffer they read
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        self.notify_waiters();
+
+        Ok(swap)
+    }
+
+    unsafe fn is_swap_finished(&self, writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool {
+        // SAFETY: defer to S::is_swap_finished
+        unsafe { self.strategy.is_swap_finished(writer, swap) }
+    }
+
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        // SAFETY: defer to S::residual_readers
+        unsafe { self.strategy.residual_readers(writer, swap) }
+    }
+
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        // SAFETY: defer to S::is_swap_finished_shared
+        unsafe { self.strategy.is_swap_finished_shared(writer) }
+    }
+
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::no_active_readers
+        unsafe { self.strategy.no_active_readers(writer) }
+    }
+
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        // SAFETY: defer to S::estimated_reader_count
+        unsafe { self.strategy.estimated_reader_count(writer) }
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // this is loaded before acquiring the guard, so that it is never newer than the buffer
+        // since the generation is only bumped after the inner swap was started
+        reader.seen = self.generation.load(Ordering::Acquire);
+        // SAFETY: defer to S::acquire_read_guard
+        unsafe { self.strategy.acquire_read_guard(&mut reader.id) }
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        // see `acquire_read_guard`
+        reader.seen = self.generation.load(Ordering::Acquire);
+        // SAFETY: defer to S::try_acquire_read_guard
+        unsafe { self.strategy.try_acquire_read_guard(&mut reader.id) }
+    }
+
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: Duration,
+    ) -> Option<Self::ReadGuard> {
+        // see `acquire_read_guard`
+        reader.seen = self.generation.load(Ordering::Acquire);
+        // SAFETY: defer to S::acquire_read_guard_timeout
+        unsafe {
+            self.strategy
+                .acquire_read_guard_timeout(&mut reader.id, timeout)
+        }
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        // SAFETY: defer to S::release_read_guard
+        unsafe { self.strategy.release_read_guard(&mut reader.id, guard) }
+    }
+}
+
+impl<S: Strategy> NotifyReaders for NotifyReadersStrategy<S> {
+    unsafe fn wait_for_swap(&self, reader: &mut Self::ReaderId) {
+        if self.generation.load(Ordering::Acquire) != reader.seen {
+            return;
+        }
+
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+
+        let mut guard = self.lock();
+        while self.generation.load(Ordering::SeqCst) == reader.seen {
+            guard = self
+                .condvar
+                .wait(guard)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        drop(guard);
+
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    unsafe fn wait_for_swap_timeout(&self, reader: &mut Self::ReaderId, timeout: Duration) -> bool {
+        if self.generation.load(Ordering::Acquire) != reader.seen {
+            return true;
+        }
+
+        let deadline = Instant::now().checked_add(timeout);
+
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+
+        let mut guard = self.lock();
+        let swapped = loop {
+            if self.generation.load(Ordering::SeqCst) != reader.seen {
+                break true;
+            }
+
+            guard = match deadline {
+                // the deadline isn't representable, so wait without a timeout
+                None => self
+                    .condvar
+                    .wait(guard)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let Some(timeout) = deadline.checked_duration_since(Instant::now()) else {
+                        break false;
+                    };
+
+                    self.condvar
+                        .wait_timeout(guard, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        };
+        drop(guard);
+
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+
+        swapped
+    }
+
+    unsafe fn poll_for_swap(&self, reader: &mut Self::ReaderId, ctx: &mut Context<'_>) -> Poll<()> {
+        if self.generation.load(Ordering::Acquire) != reader.seen {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.lock();
+
+        if !wakers.iter().any(|waker| waker.will_wake(ctx.waker())) {
+            wakers.push(ctx.waker().clone());
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // the waker is registered, and the writer will see the new waiter
+        // so either we see the new generation, or the writer wakes us up
+        if self.generation.load(Ordering::SeqCst) != reader.seen {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BlockingStrategy> BlockingStrategy for NotifyReadersStrategy<S> {
+    unsafe fn finish_swap(&self, writer: &mut Self::WriterId, swap: Self::Swap) {
+        // SAFETY: defer to S::finish_swap
+        unsafe { self.strategy.finish_swap(writer, swap) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: AsyncStrategy> AsyncStrategy for NotifyReadersStrategy<S> {
+    unsafe fn register_context(
+        &self,
+        writer: &mut Self::WriterId,
+        swap: &mut Self::Swap,
+        ctx: &mut Context<'_>,
+    ) -> core::task::Poll<()> {
+        // SAFETY: defer to S::register_context
+        unsafe { self.strategy.register_context(writer, swap, ctx) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BufferOccupancy> BufferOccupancy for NotifyReadersStrategy<S> {
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool {
+        // SAFETY: defer to S::is_buffer_empty
+        unsafe { self.strategy.is_buffer_empty(writer, side) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: ReaderLock> ReaderLock for NotifyReadersStrategy<S> {
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool {
+        // SAFETY: defer to S::try_lock_readers
+        unsafe { self.strategy.try_lock_readers(writer) }
+    }
+
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId) {
+        // SAFETY: defer to S::unlock_readers
+        unsafe { self.strategy.unlock_readers(writer) }
+    }
+}
@@ -0,0 +1,141 @@
+#![cfg(not(loom))]
+
+use core::time::Duration;
+
+use super::NotifyReadersStrategy;
+
+use crate::{
+    raw::{DoubleBufferData, Writer},
+    strategy::atomic::AtomicStrategy,
+};
+
+#[test]
+fn wait_for_swap() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        NotifyReadersStrategy::new(AtomicStrategy::new_blocking()),
+    );
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+    assert_eq!(*reader.read(), 0);
+
+    std::thread::scope(|s| {
+        let waiter = s.spawn(move || {
+            reader.wait_for_swap().unwrap();
+            *reader.read()
+        });
+
+        writer.swap();
+
+        assert_eq!(waiter.join().unwrap(), 1);
+    });
+}
+
+#[test]
+fn wait_for_swap_timeout() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        NotifyReadersStrategy::new(AtomicStrategy::new_blocking()),
+    );
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+    assert_eq!(*reader.read(), 0);
+
+    assert!(!reader
+        .wait_for_swap_timeout(Duration::from_millis(1))
+        .unwrap());
+
+    writer.swap();
+
+    // the swap happened after the last read, so this doesn't block
+    assert!(reader.wait_for_swap_timeout(Duration::ZERO).unwrap());
+    assert_eq!(*reader.read(), 1);
+    assert!(!reader.wait_for_swap_timeout(Duration::ZERO).unwrap());
+}
+
+#[test]
+fn read_timeout() {
+    use alloc::sync::Arc;
+
+    use rc_box::ArcBox;
+
+    use crate::{raw::ReadTimeout, strategy::atomic::park_token::ThreadParkToken};
+
+    type Buffer = DoubleBufferData<i32, NotifyReadersStrategy<AtomicStrategy<ThreadParkToken>>>;
+
+    let mut writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        1,
+        NotifyReadersStrategy::new(AtomicStrategy::new_blocking()),
+    )));
+    let mut reader = writer.reader();
+
+    {
+        // the readers are locked out while the extras are borrowed
+        let _extras = writer.try_extras_mut().unwrap();
+        assert_eq!(
+            reader.read_timeout(Duration::from_millis(10)).err(),
+            Some(ReadTimeout::Elapsed)
+        );
+    }
+
+    assert_eq!(*reader.read_timeout(Duration::ZERO).unwrap(), 0);
+
+    drop(writer);
+    assert!(matches!(
+        reader.read_timeout(Duration::ZERO),
+        Err(ReadTimeout::Upgrade(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn snapshot_stream() {
+    use alloc::{sync::Arc, task::Wake};
+    use core::{
+        pin::Pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll},
+    };
+    use futures_core::Stream;
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        NotifyReadersStrategy::new(AtomicStrategy::new_blocking()),
+    );
+    let mut writer = Writer::new(&mut state);
+
+    let mut stream = writer.reader().into_snapshot_stream();
+
+    let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+    let waker = wakes.clone().into();
+    let mut ctx = Context::from_waker(&waker);
+    let mut poll = || Pin::new(&mut stream).poll_next(&mut ctx);
+
+    // the current buffer is yielded right away
+    assert_eq!(poll(), Poll::Ready(Some(0)));
+    assert_eq!(poll(), Poll::Pending);
+    assert_eq!(poll(), Poll::Pending);
+
+    writer.swap();
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    *writer.get_mut() = 2;
+    writer.swap();
+
+    // both swaps are coalesced into a single snapshot
+    assert_eq!(poll(), Poll::Ready(Some(2)));
+    assert_eq!(poll(), Poll::Pending);
+}
@@ -0,0 +1,141 @@
+//! A strategy for buffers which are never swapped
+//!
+//! Readers always read the first buffer passed to
+//! [`DoubleBufferData::new`](crate::raw::DoubleBufferData::new), and the writer always writes
+//! the second buffer. Since the reader and the writer never share a buffer, reads don't need
+//! any synchronization at all, acquiring and releasing a read guard does nothing.
+//!
+//! This is useful as a baseline in benchmarks, as a mock in tests, and for buffers that hold a
+//! single immutable snapshot, which the readers see for as long as they exist.
+//!
+//! # Swapping
+//!
+//! Swaps always fail with [`NeverSwaps`], so [`Writer::try_swap`](crate::raw::Writer::try_swap)
+//! returns [`Err`] and [`Writer::swap`](crate::raw::Writer::swap) panics. Swaps don't silently
+//! succeed, since the writes would never become visible to the readers.
+
+use crate::interface::{AsyncStrategy, BlockingStrategy, Strategy};
+
+#[cfg(test)]
+mod test;
+
+pub struct NullStrategy(());
+
+/// The error returned when trying to swap the buffers of a [`NullStrategy`]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct NeverSwaps;
+
+impl core::fmt::Debug for NeverSwaps {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "NullStrategy never swaps the buffers")
+    }
+}
+
+impl NullStrategy {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(())
+    }
+}
+
+impl Default for NullStrategy {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY:
+//
+// The swap never succeeds, so readers always read one buffer and the writer always
+// writes the other buffer, so there are never any active reads of the write buffer
+unsafe impl Strategy for NullStrategy {
+    type WriterId = ();
+    type ReaderId = ();
+
+    type Swap = core::convert::Infallible;
+    type SwapError = NeverSwaps;
+
+    type ReadGuard = ();
+
+    #[inline]
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {}
+
+    #[inline]
+    unsafe fn create_reader_id_from_writer(&self, _writer: &Self::WriterId) -> Self::ReaderId {}
+
+    #[inline]
+    unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {}
+
+    #[inline]
+    unsafe fn create_detached_reader_id(_reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        Some(())
+    }
+
+    #[inline]
+    fn create_invalid_reader_id() -> Self::ReaderId {}
+
+    #[inline]
+    unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
+        false
+    }
+
+    #[inline]
+    unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, _guard: &Self::ReadGuard) -> bool {
+        false
+    }
+
+    #[inline]
+    unsafe fn try_start_swap(
+        &self,
+        _writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        Err(NeverSwaps)
+    }
+
+    #[inline]
+    unsafe fn is_swap_finished(&self, _writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool {
+        match *swap {}
+    }
+
+    #[inline]
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        Some(true)
+    }
+
+    #[inline]
+    unsafe fn generation(
+        &self,
+        _reader: &mut Self::ReaderId,
+        _guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        Some(0)
+    }
+
+    #[inline]
+    unsafe fn acquire_read_guard(&self, _reader: &mut Self::ReaderId) -> Self::ReadGuard {}
+
+    #[inline]
+    unsafe fn release_read_guard(&self, _reader: &mut Self::ReaderId, _guard: Self::ReadGuard) {}
+}
+
+// SAFETY: there are no swaps, so this is never called
+unsafe impl AsyncStrategy for NullStrategy {
+    #[inline]
+    unsafe fn register_context(
+        &self,
+        _writer: &mut Self::WriterId,
+        swap: &mut Self::Swap,
+        _ctx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<()> {
+        match *swap {}
+    }
+}
+
+// SAFETY: there are no swaps, so this is never called
+unsafe impl BlockingStrategy for NullStrategy {
+    #[inline]
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, swap: Self::Swap) {
+        match swap {}
+    }
+}
@@ -0,0 +1,72 @@
+#![allow(clippy::let_unit_value)]
+
+use super::{NeverSwaps, NullStrategy};
+
+use crate::{
+    delay::DelayWriter,
+    raw::{DoubleBufferData, Writer},
+};
+
+#[test]
+fn smoke() {
+    let mut state = DoubleBufferData::new(0, 1, NullStrategy::new());
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+    let mut copied = reader;
+
+    let guard = reader.read();
+    assert_eq!(*guard, 0);
+    assert_eq!(guard.generation(), Some(0));
+
+    // the writer never writes the buffer that readers see, even while they are reading
+    *writer.get_mut() = 10;
+    assert_eq!(*guard, 0);
+    assert_eq!(*copied.read(), 0);
+    drop(guard);
+
+    assert_eq!(writer.try_swap(), Err(NeverSwaps));
+    assert_eq!(writer.swap_generation(), 0);
+    assert_eq!(*writer.get(), 10);
+    assert_eq!(*reader.read(), 0);
+}
+
+#[test]
+#[should_panic = "swap failed: NullStrategy never swaps the buffers"]
+fn swap() {
+    let mut state = DoubleBufferData::new(0, 1, NullStrategy::new());
+    let mut writer = Writer::new(&mut state);
+    writer.swap();
+}
+
+#[test]
+fn delay_writer() {
+    let mut state = DoubleBufferData::new(0, 1, NullStrategy::new());
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+
+    assert_eq!(writer.try_start_swap(), Err(NeverSwaps));
+    assert!(!writer.has_swap());
+    assert!(writer.is_swap_finished());
+    assert_eq!(writer.peek_swap_finished(), Some(true));
+    *writer.finish_swap().get_mut() = 10;
+}
+
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+#[test]
+fn threaded() {
+    let mut state = DoubleBufferData::new(0, 1, NullStrategy::new());
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..1000 {
+                assert_eq!(*reader.read(), 0);
+            }
+        });
+
+        for i in 0..1000 {
+            *writer.get_mut() = i;
+        }
+    });
+}
@@ -3,7 +3,7 @@
 
 use core::cell::UnsafeCell;
 
-use crate::interface::Strategy;
+use crate::interface::{BufferOccupancy, BufferSide, ReaderLock, Strategy};
 
 pub struct OutlineWriterStrategy<S: Strategy> {
     writer_id: UnsafeCell<S::WriterId>,
@@ -70,10 +70,23 @@ unsafe impl<S: Strategy> Strategy for OutlineWriterStrategy<S> {
         unsafe { self.strategy.create_reader_id_from_reader(reader) }
     }
 
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        // SAFETY: defer to S::create_detached_reader_id
+        unsafe { S::create_detached_reader_id(reader) }
+    }
+
     fn create_invalid_reader_id() -> Self::ReaderId {
         S::create_invalid_reader_id()
     }
 
+    /// Reset the inner strategy, and create a new writer id for it
+    fn reset(&mut self) {
+        self.strategy.reset();
+        // SAFETY: Struct drop order ensures that the writer id is dropped before the strategy
+        // the old writer id is invalid after the reset, so it's fine to replace it
+        *self.writer_id.get_mut() = unsafe { self.strategy.create_writer_id() };
+    }
+
     unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
         // SAFETY: defer to S::is_swapped_writer
         unsafe { self.strategy.is_swapped_writer(self.writer_id(writer)) }
@@ -84,6 +97,15 @@ unsafe impl<S: Strategy> Strategy for OutlineWriterStrategy<S> {
         unsafe { self.strategy.is_swapped(reader, guard) }
     }
 
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        // SAFETY: defer to S::generation
+        unsafe { self.strategy.generation(reader, guard) }
+    }
+
     unsafe fn try_start_swap(
         &self,
         writer: &mut Self::WriterId,
@@ -100,13 +122,74 @@ unsafe impl<S: Strategy> Strategy for OutlineWriterStrategy<S> {
         }
     }
 
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        // SAFETY: defer to S::residual_readers
+        unsafe { self.strategy.residual_readers(self.writer_id(writer), swap) }
+    }
+
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        // SAFETY: defer to S::is_swap_finished_shared
+        unsafe {
+            self.strategy
+                .is_swap_finished_shared(self.writer_id(writer))
+        }
+    }
+
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::no_active_readers
+        unsafe { self.strategy.no_active_readers(self.writer_id(writer)) }
+    }
+
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        // SAFETY: defer to S::estimated_reader_count
+        unsafe { self.strategy.estimated_reader_count(self.writer_id(writer)) }
+    }
+
     unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
         // SAFETY: defer to S::acquire_read_guard
         unsafe { self.strategy.acquire_read_guard(reader) }
     }
 
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::try_acquire_read_guard
+        unsafe { self.strategy.try_acquire_read_guard(reader) }
+    }
+
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        // SAFETY: defer to S::acquire_read_guard_timeout
+        unsafe { self.strategy.acquire_read_guard_timeout(reader, timeout) }
+    }
+
     unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
         // SAFETY: defer to S::release_read_guard
         unsafe { self.strategy.release_read_guard(reader, guard) }
     }
 }
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BufferOccupancy> BufferOccupancy for OutlineWriterStrategy<S> {
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool {
+        // SAFETY: defer to S::is_buffer_empty
+        unsafe { self.strategy.is_buffer_empty(self.writer_id(writer), side) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: ReaderLock> ReaderLock for OutlineWriterStrategy<S> {
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool {
+        // SAFETY: defer to S::try_lock_readers
+        unsafe { self.strategy.try_lock_readers(self.writer_id_mut(writer)) }
+    }
+
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId) {
+        // SAFETY: defer to S::unlock_readers
+        unsafe { self.strategy.unlock_readers(self.writer_id_mut(writer)) }
+    }
+}
@@ -1,13 +1,13 @@
 use core::cell::Cell;
 
-use crate::interface::{AsyncStrategy, BlockingStrategy, Strategy};
+use crate::interface::{AsyncStrategy, BlockingStrategy, BufferOccupancy, BufferSide, Strategy};
 
 #[cfg(test)]
 mod tests;
 
 pub struct SimpleStrategy {
     // how many readers in each buffer
-    num_readers: [Cell<u32>; 2],
+    num_readers: [Cell<usize>; 2],
     swapped: Cell<bool>,
 }
 
@@ -51,6 +51,11 @@ unsafe impl Strategy for SimpleStrategy {
     #[inline]
     unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {}
 
+    #[inline]
+    unsafe fn create_detached_reader_id(_reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        Some(())
+    }
+
     #[inline]
     fn create_invalid_reader_id() -> Self::ReaderId {}
 
@@ -88,6 +93,11 @@ unsafe impl Strategy for SimpleStrategy {
         true
     }
 
+    #[inline]
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        Some(true)
+    }
+
     #[inline]
     unsafe fn acquire_read_guard(&self, _reader: &mut Self::ReaderId) -> Self::ReadGuard {
         let swapped = !self.swapped.get();
@@ -127,3 +137,16 @@ unsafe impl BlockingStrategy for SimpleStrategy {
     #[inline]
     unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, _swap: Self::Swap) {}
 }
+
+// SAFETY: num_readers tracks how many readers are in each buffer
+unsafe impl BufferOccupancy for SimpleStrategy {
+    #[inline]
+    unsafe fn is_buffer_empty(&self, _writer: &Self::WriterId, side: BufferSide) -> bool {
+        let swapped = self.swapped.get();
+        let index = match side {
+            BufferSide::Read => !swapped,
+            BufferSide::Write => swapped,
+        };
+        self.num_readers[index as usize].get() == 0
+    }
+}
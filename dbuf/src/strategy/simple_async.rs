@@ -1,13 +1,13 @@
 use core::{cell::Cell, task::Waker};
 
-use crate::interface::{AsyncStrategy, Strategy};
+use crate::interface::{AsyncStrategy, BufferOccupancy, BufferSide, Strategy};
 
 #[cfg(test)]
 mod test;
 
 pub struct SimpleAsyncStrategy {
     // how many readers in each buffer
-    num_readers: [Cell<u32>; 2],
+    num_readers: [Cell<usize>; 2],
     swapped: Cell<bool>,
     waker: Cell<Option<Waker>>,
 }
@@ -52,6 +52,11 @@ unsafe impl Strategy for SimpleAsyncStrategy {
     #[inline]
     unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {}
 
+    #[inline]
+    unsafe fn create_detached_reader_id(_reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        Some(())
+    }
+
     #[inline]
     fn create_invalid_reader_id() -> Self::ReaderId {}
 
@@ -84,6 +89,11 @@ unsafe impl Strategy for SimpleAsyncStrategy {
         self.num_readers[self.swapped.get() as usize].get() == 0
     }
 
+    #[inline]
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        Some(self.num_readers[self.swapped.get() as usize].get() == 0)
+    }
+
     #[inline]
     unsafe fn acquire_read_guard(&self, _reader: &mut Self::ReaderId) -> Self::ReadGuard {
         let swapped = !self.swapped.get();
@@ -123,3 +133,16 @@ unsafe impl AsyncStrategy for SimpleAsyncStrategy {
         core::task::Poll::Pending
     }
 }
+
+// SAFETY: num_readers tracks how many readers are in each buffer
+unsafe impl BufferOccupancy for SimpleAsyncStrategy {
+    #[inline]
+    unsafe fn is_buffer_empty(&self, _writer: &Self::WriterId, side: BufferSide) -> bool {
+        let swapped = self.swapped.get();
+        let index = match side {
+            BufferSide::Read => !swapped,
+            BufferSide::Write => swapped,
+        };
+        self.num_readers[index as usize].get() == 0
+    }
+}
@@ -0,0 +1,429 @@
+//! A thread-safe strategy for exactly one writer and one reader
+//!
+//! This uses the same algorithm as [`FlashStrategy`](crate::strategy::flashmap::FlashStrategy),
+//! but since there is only a single reader, the reader's swap state is stored inline in the strategy.
+//! So there is no mutex, no list of readers, and no allocation. The writer flips the reader's swap
+//! state with a single `fetch_add`, and the reader enters and leaves the buffer with a single
+//! `fetch_or` and `fetch_and`.
+//!
+//! # Single reader
+//!
+//! Only one reader id may exist at a time. Creating a second reader id while the first one is
+//! alive panics, this includes cloning the reader (see [`Reader::clone`](crate::raw::Reader)) and
+//! [`Reader::read_owned`](crate::raw::Reader::read_owned), which creates a reader id for the guard.
+//! Dropping the reader frees up the slot, so a new reader may be created from the writer afterwards.
+//!
+//! # Leaked read guards
+//!
+//! Like [`FlashStrategy`](crate::strategy::flashmap::FlashStrategy), if a read guard is leaked,
+//! then `acquire_read_guard` panics the next time the reader tries to read, unless both
+//! `debug_assertions` and the `debug-checks` feature are disabled.
+
+use const_fn::const_fn;
+use core::ptr::NonNull;
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+use core::task::Poll;
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+
+#[cfg(feature = "std")]
+use crate::interface::BlockingStrategy;
+use crate::interface::{AsyncStrategy, Strategy};
+
+#[cfg(feature = "std")]
+use super::flash_park_token::{AdaptiveParkToken, ThreadParkToken};
+use super::flash_park_token::{AsyncParkToken, Parker};
+
+#[cfg(test)]
+mod test;
+
+pub struct SpscStrategy<ParkToken> {
+    // the swap state of the reader, laid out as `generation << 1 | READER_ACTIVE`
+    // the writer only ever adds `SWAPPED`, and the reader only ever sets and clears `READER_ACTIVE`
+    swap_state: AtomicUsize,
+    // set once a reader id was created for the current writer id
+    has_reader: AtomicBool,
+    // may be temporarily negative if the reader finished before
+    // the writer registered it as a residual reader
+    residual: AtomicIsize,
+    parker: ParkToken,
+}
+
+const NOT_SWAPPED: usize = 0;
+const READER_ACTIVE: usize = 1;
+const SWAPPED: usize = 2;
+
+const _: () = {
+    const fn send_sync<T: Send + Sync>() {}
+
+    #[cfg(feature = "std")]
+    let _ = send_sync::<SpscStrategy<ThreadParkToken>>;
+    let _ = send_sync::<SpscStrategy<AsyncParkToken>>;
+    #[cfg(feature = "std")]
+    let _ = send_sync::<SpscStrategy<AdaptiveParkToken>>;
+    let _ = send_sync::<ReaderId>;
+};
+
+pub struct WriterId(());
+pub struct ReaderId {
+    // this is `None` for invalid reader ids, which never held the reader slot
+    has_reader: Option<NonNull<AtomicBool>>,
+}
+
+// SAFETY: has_reader is only accessed through atomic operations
+unsafe impl Send for ReaderId {}
+// SAFETY: has_reader is only accessed through atomic operations
+unsafe impl Sync for ReaderId {}
+
+pub struct ReadGuard {
+    swap_state: usize,
+}
+
+#[non_exhaustive]
+pub struct Swap;
+
+#[cfg(feature = "std")]
+impl SpscStrategy<ThreadParkToken> {
+    #[const_fn(cfg(not(loom)))]
+    pub const fn new_blocking() -> Self {
+        Self::with_park_token()
+    }
+}
+
+impl SpscStrategy<AsyncParkToken> {
+    #[const_fn(cfg(not(loom)))]
+    pub const fn new_async() -> Self {
+        Self::with_park_token()
+    }
+}
+
+#[cfg(feature = "std")]
+impl SpscStrategy<AdaptiveParkToken> {
+    #[const_fn(cfg(not(loom)))]
+    pub const fn new() -> Self {
+        Self::with_park_token()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SpscStrategy<ThreadParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::new_blocking()
+    }
+}
+
+impl Default for SpscStrategy<AsyncParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::new_async()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SpscStrategy<AdaptiveParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ParkToken: Parker> SpscStrategy<ParkToken> {
+    #[const_fn(cfg(not(loom)))]
+    const fn with_park_token() -> Self {
+        Self {
+            swap_state: AtomicUsize::new(NOT_SWAPPED),
+            has_reader: AtomicBool::new(false),
+            residual: AtomicIsize::new(0),
+            parker: ParkToken::NEW,
+        }
+    }
+}
+
+impl<ParkToken> SpscStrategy<ParkToken> {
+    fn create_reader_id(&self) -> ReaderId {
+        if self.has_reader.swap(true, Ordering::Acquire) {
+            second_reader()
+        }
+
+        ReaderId {
+            has_reader: Some(NonNull::from(&self.has_reader)),
+        }
+    }
+
+    fn start_read(&self) -> ReadGuard {
+        // this needs to syncronize with the writer's update in `try_start_swap`, so that the writes
+        // to the buffer before the swap happen before the reads from it (so needs `Acquire`)
+        let swap_state = self.swap_state.fetch_or(READER_ACTIVE, Ordering::AcqRel);
+        ReadGuard { swap_state }
+    }
+
+    fn poll(&self, mut setup: impl FnMut(bool)) -> Poll<()> {
+        if self.residual.load(Ordering::Acquire) == 0 {
+            return Poll::Ready(());
+        }
+
+        setup(true);
+        let residual = self.residual.load(Ordering::Acquire);
+        // if the residual reader finished already
+        if residual == 0 {
+            setup(false);
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for ReaderId {
+    fn drop(&mut self) {
+        if let Some(has_reader) = self.has_reader {
+            // SAFETY: The reader id is only created in create_reader_id_from_* which require the
+            // id is dropped before the strategy, so if we have reached this point then
+            // the strategy is still alive
+            let has_reader = unsafe { has_reader.as_ref() };
+            // the reader's last read happens before the next reader is created
+            has_reader.store(false, Ordering::Release);
+        }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn second_reader() -> ! {
+    panic!("SpscStrategy only supports a single reader")
+}
+
+// SAFETY: SpscStrategy is the same algorithm as FlashStrategy, with a single reader
+// finish_swap doesn't return while the reader is still in the buffer that the writer
+// is writing to. And since there is only a single reader (checked by `create_reader_id`)
+// the reader's swap state is the only one which needs to be swapped
+unsafe impl<ParkToken: Parker> Strategy for SpscStrategy<ParkToken> {
+    type WriterId = WriterId;
+    type ReaderId = ReaderId;
+
+    type Swap = Swap;
+    type SwapError = core::convert::Infallible;
+
+    type ReadGuard = ReadGuard;
+
+    /// All reader ids are invalidated, so this frees up the reader slot even if a reader was leaked
+    #[inline]
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        self.has_reader = AtomicBool::new(false);
+        WriterId(())
+    }
+
+    #[inline]
+    unsafe fn create_reader_id_from_writer(&self, _writer: &Self::WriterId) -> Self::ReaderId {
+        self.create_reader_id()
+    }
+
+    /// # Panics
+    ///
+    /// This always panics, since there may only be a single reader at a time
+    #[inline]
+    unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {
+        self.create_reader_id()
+    }
+
+    #[inline]
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        ReaderId { has_reader: None }
+    }
+
+    /// Go back to the initial state: not swapped, and no reader or residual reader
+    fn reset(&mut self) {
+        self.swap_state = AtomicUsize::new(NOT_SWAPPED);
+        self.has_reader = AtomicBool::new(false);
+        self.residual = AtomicIsize::new(0);
+    }
+
+    #[inline]
+    unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
+        // the swapped bit is only ever changed by the writer, so a relaxed load is enough
+        self.swap_state.load(Ordering::Relaxed) & SWAPPED != 0
+    }
+
+    #[inline]
+    unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        guard.swap_state & SWAPPED != 0
+    }
+
+    #[inline]
+    unsafe fn generation(
+        &self,
+        _reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        Some((guard.swap_state >> 1) as u64)
+    }
+
+    #[inline]
+    unsafe fn try_start_swap(
+        &self,
+        _writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        let old_swap_state = self.swap_state.fetch_add(SWAPPED, Ordering::AcqRel);
+
+        // if the reader is active, then it's reading the old read buffer
+        if old_swap_state & READER_ACTIVE != 0 {
+            self.residual.fetch_add(1, Ordering::Release);
+        }
+
+        Ok(Swap)
+    }
+
+    #[inline]
+    unsafe fn is_swap_finished(&self, _writer: &mut Self::WriterId, Swap: &mut Self::Swap) -> bool {
+        self.residual.load(Ordering::Acquire) == 0
+    }
+
+    #[inline]
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        // the swap doesn't hold any state, so this is the same as `is_swap_finished`
+        Some(self.residual.load(Ordering::Acquire) == 0)
+    }
+
+    #[inline]
+    unsafe fn residual_readers(
+        &self,
+        _writer: &Self::WriterId,
+        Swap: &Self::Swap,
+    ) -> Option<usize> {
+        Some(usize::try_from(self.residual.load(Ordering::Acquire)).unwrap_or(0))
+    }
+
+    #[inline]
+    unsafe fn no_active_readers(&self, _writer: &Self::WriterId) -> bool {
+        // if there is no reader id, then there is no one who could read
+        // this syncronizes with the drop of the last reader id, so its reads happen before
+        // the writer accesses the buffers (so needs `Acquire`)
+        !self.has_reader.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        if cfg!(any(debug_assertions, feature = "debug-checks")) {
+            // SAFETY: guaranteed by caller
+            unsafe { self.try_acquire_read_guard(reader) }.expect("Detected a leaked read guard")
+        } else {
+            // leaking a guard is a logic error, see the module docs
+            self.start_read()
+        }
+    }
+
+    #[inline]
+    unsafe fn try_acquire_read_guard(
+        &self,
+        _reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        if self.swap_state.load(Ordering::Relaxed) & READER_ACTIVE != 0 {
+            return None;
+        }
+
+        Some(self.start_read())
+    }
+
+    #[inline]
+    unsafe fn release_read_guard(&self, _reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        let swap_state = self.swap_state.fetch_and(!READER_ACTIVE, Ordering::Release);
+
+        // if there wasn't any intervening swap then just return
+        if guard.swap_state & SWAPPED == swap_state & SWAPPED {
+            return;
+        }
+
+        // if there was an intervening swap, then this is the residual reader of that swap
+        let residual = self.residual.fetch_sub(1, Ordering::AcqRel);
+
+        // if the writer didn't register the residual reader yet, it will see that
+        // the swap is already finished
+        if residual != 1 {
+            return;
+        }
+
+        // SAFETY: residual is non-zero
+        unsafe { self.parker.wake() }
+    }
+}
+
+// SAFETY: we check if is_swap_finished would return true before returning Poll::Ready
+unsafe impl AsyncStrategy for SpscStrategy<AsyncParkToken> {
+    unsafe fn register_context(
+        &self,
+        _writer: &mut Self::WriterId,
+        Swap: &mut Self::Swap,
+        ctx: &mut core::task::Context<'_>,
+    ) -> Poll<()> {
+        self.poll(|should_set| {
+            if should_set {
+                self.parker.set(ctx)
+            } else {
+                self.parker.clear();
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning
+unsafe impl BlockingStrategy for SpscStrategy<ThreadParkToken> {
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, Swap: Self::Swap) {
+        if self
+            .poll(|should_set| {
+                if should_set {
+                    self.parker.set()
+                } else {
+                    self.parker.clear();
+                }
+            })
+            .is_pending()
+        {
+            while self.residual.load(Ordering::Acquire) != 0 {
+                std::thread::park();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning Poll::Ready
+unsafe impl AsyncStrategy for SpscStrategy<AdaptiveParkToken> {
+    unsafe fn register_context(
+        &self,
+        _writer: &mut Self::WriterId,
+        Swap: &mut Self::Swap,
+        ctx: &mut core::task::Context<'_>,
+    ) -> Poll<()> {
+        self.poll(|should_set| {
+            if should_set {
+                self.parker.async_token.set(ctx)
+            } else {
+                self.parker.async_token.clear();
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning
+unsafe impl BlockingStrategy for SpscStrategy<AdaptiveParkToken> {
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, Swap: Self::Swap) {
+        if self
+            .poll(|should_set| {
+                if should_set {
+                    self.parker.thread_token.set()
+                } else {
+                    self.parker.thread_token.clear();
+                }
+            })
+            .is_pending()
+        {
+            while self.residual.load(Ordering::Acquire) != 0 {
+                std::thread::park();
+            }
+        }
+    }
+}
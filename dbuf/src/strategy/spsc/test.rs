@@ -0,0 +1,201 @@
+#![allow(unused)]
+
+use super::SpscStrategy;
+
+use crate::{
+    delay::DelayWriter,
+    raw::{DoubleBufferData, Writer},
+};
+
+use pollster::test as async_test;
+
+#[cfg(not(loom))]
+#[test]
+fn smoke() {
+    let mut state = DoubleBufferData::new(0, 1, SpscStrategy::new_async());
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+
+    let x = reader.read();
+    assert_eq!(*x, *writer.split().read);
+
+    // SAFETY: the swap is finished before split_mut/get_mut is called
+    let mut swap = unsafe { writer.try_start_swap().unwrap() };
+
+    // SAFETY: the swap is the latest swap
+    assert!(!unsafe { writer.is_swap_finished(&mut swap) });
+    assert_eq!(writer.peek_swap_finished(), Some(false));
+
+    assert_eq!(*x, *writer.split().write);
+
+    drop(x);
+
+    // SAFETY: the swap is the latest swap
+    assert!(unsafe { writer.is_swap_finished(&mut swap) });
+    assert_eq!(*reader.read(), 1);
+    assert_eq!(reader.read().generation(), Some(1));
+}
+
+#[cfg(not(loom))]
+#[test]
+#[should_panic = "SpscStrategy only supports a single reader"]
+fn second_reader() {
+    let mut state = DoubleBufferData::new(0, 1, SpscStrategy::new_async());
+    let writer = Writer::new(&mut state);
+
+    let reader = writer.reader();
+    let _ = reader.clone();
+}
+
+#[cfg(not(loom))]
+#[test]
+fn reader_after_new_writer() {
+    let mut state = DoubleBufferData::new(0, 1, SpscStrategy::new_async());
+
+    {
+        let mut writer = Writer::new(&mut state);
+        assert!(writer.try_get_both_mut().is_some());
+        let _reader = writer.reader();
+    }
+
+    // a new writer invalidates the old reader, so there may be a new reader
+    let mut writer = Writer::new(&mut state);
+    let mut reader = writer.reader();
+    assert!(writer.try_get_both_mut().is_none());
+    assert_eq!(*reader.read(), 0);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn reader_after_drop() {
+    let mut state = DoubleBufferData::new(0, 1, SpscStrategy::new_async());
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+
+    let reader = writer.reader();
+    drop(reader);
+
+    // dropping the reader frees up the slot
+    let mut reader = writer.reader();
+    writer.start_swap();
+    assert_eq!(*reader.read(), 1);
+    drop(reader);
+
+    assert_eq!(*writer.reader().read(), 1);
+}
+
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+#[test]
+fn clone_after_writer_dropped() {
+    use alloc::sync::Arc;
+
+    use rc_box::ArcBox;
+
+    type Buffer =
+        DoubleBufferData<i32, SpscStrategy<crate::strategy::flash_park_token::AsyncParkToken>>;
+
+    let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        1,
+        SpscStrategy::new_async(),
+    )));
+    let mut reader = writer.reader();
+    drop(writer);
+
+    // the clone gets an invalid reader id, which doesn't need the reader slot
+    let mut clone = reader.clone();
+    assert!(clone.try_read().is_err());
+    assert!(reader.try_read().is_err());
+}
+
+#[cfg(not(loom))]
+#[async_test]
+async fn async_swap() {
+    let mut state = DoubleBufferData::new(0, 1, SpscStrategy::new_async());
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+    let mut reader = writer.reader();
+
+    writer.start_swap();
+    assert_eq!(*writer.afinish_swap().await.get(), 0);
+    assert_eq!(*reader.read(), 1);
+}
+
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+#[test]
+fn threaded() {
+    use alloc::sync::Arc;
+
+    use rc_box::ArcBox;
+
+    type Buffer =
+        DoubleBufferData<u64, SpscStrategy<crate::strategy::flash_park_token::ThreadParkToken>>;
+
+    let mut writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        0,
+        SpscStrategy::new_blocking(),
+    )));
+    let mut reader = writer.reader();
+
+    let t = std::thread::spawn(move || {
+        let mut last = 0;
+        while let Ok(guard) = reader.try_read() {
+            // the reader never goes back to an older buffer
+            assert!(*guard >= last);
+            last = *guard;
+        }
+    });
+
+    for i in 1..=10_000 {
+        *writer.get_mut() = i;
+        writer.swap();
+    }
+
+    drop(writer);
+    t.join().unwrap();
+}
+
+#[cfg(loom)]
+#[cfg(feature = "std")]
+#[test]
+fn loom() {
+    use std::sync::Arc;
+
+    use loom::cell::UnsafeCell;
+    use rc_box::ArcBox;
+
+    use crate::strategy::flash_park_token::AsyncParkToken;
+
+    type Buffer = DoubleBufferData<UnsafeCell<i32>, SpscStrategy<AsyncParkToken>>;
+
+    loom::model(|| {
+        let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+            UnsafeCell::new(0),
+            UnsafeCell::new(0),
+            SpscStrategy::new_async(),
+        )));
+        let mut writer = DelayWriter::from_writer(writer);
+        let mut reader = writer.reader();
+
+        let t = loom::thread::spawn(move || {
+            for _ in 0..2 {
+                reader.read().with(|_| loom::thread::yield_now());
+            }
+            reader
+        });
+
+        for _ in 0..2 {
+            writer.start_swap();
+            while !writer.is_swap_finished() {
+                loom::thread::yield_now();
+            }
+
+            let writer = writer.get_writer_mut().unwrap();
+            writer.get().with_mut(|_| loom::thread::yield_now());
+        }
+
+        t.join().unwrap();
+    });
+}
@@ -0,0 +1,421 @@
+//! A flashmap-style strategy which stores all reader slots inline
+//!
+//! This strategy uses the same algorithm as [`FlashStrategy`](crate::strategy::flashmap::FlashStrategy),
+//! but instead of keeping a growable list of readers behind a lock, it keeps a fixed
+//! array of `N` reader slots inside the strategy. So it doesn't need to allocate, and
+//! can be used in `no_std` environments.
+//!
+//! Each reader id claims a free slot when it's created, and gives it back when it's dropped.
+//! If all `N` slots are taken when a reader id is created, then that reader id
+//! will try to claim a slot every time it starts a read, and panics if there are still no
+//! free slots.
+//!
+//! NOTE: reader ids point into the strategy, so the strategy must not be moved while
+//! there are any reader ids created from it. This is always the case when the strategy
+//! is used through a [`raw::Writer`](crate::raw::Writer) and [`raw::Reader`](crate::raw::Reader).
+
+use core::{
+    ptr::NonNull,
+    sync::atomic::{AtomicIsize, AtomicUsize, Ordering},
+    task::Poll,
+};
+
+use crate::interface::{AsyncStrategy, Strategy};
+
+#[cfg(feature = "std")]
+use super::flash_park_token::{AdaptiveParkToken, ThreadParkToken};
+use super::flash_park_token::{AsyncParkToken, Parker};
+
+#[cfg(test)]
+mod test;
+
+pub struct StaticStrategy<const N: usize, P> {
+    swap_state: AtomicUsize,
+    slots: [AtomicUsize; N],
+    residual: AtomicIsize,
+    parker: P,
+}
+
+const _: () = {
+    const fn send_sync<T: Send + Sync>() {}
+
+    let _ = send_sync::<StaticStrategy<4, AsyncParkToken>>;
+    #[cfg(feature = "std")]
+    let _ = send_sync::<StaticStrategy<4, AdaptiveParkToken>>;
+    #[cfg(feature = "std")]
+    let _ = send_sync::<ReaderId>;
+};
+
+const NOT_SWAPPED: usize = 0;
+const SWAPPED: usize = 1;
+const READER_ACTIVE: usize = 2;
+const OCCUPIED: usize = 4;
+
+pub struct WriterId(());
+pub struct ReaderId {
+    slot: Option<NonNull<AtomicUsize>>,
+}
+
+// SAFETY: the slot is only accessed through atomic operations
+unsafe impl Send for ReaderId {}
+// SAFETY: the slot is only accessed through atomic operations
+unsafe impl Sync for ReaderId {}
+
+pub struct ReadGuard {
+    swap_state: usize,
+}
+
+#[non_exhaustive]
+pub struct Swap;
+
+impl<const N: usize> StaticStrategy<N, AsyncParkToken> {
+    pub const fn new_async() -> Self {
+        Self::with_parker()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize> StaticStrategy<N, ThreadParkToken> {
+    pub const fn new_blocking() -> Self {
+        Self::with_parker()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize> StaticStrategy<N, AdaptiveParkToken> {
+    pub const fn new() -> Self {
+        Self::with_parker()
+    }
+}
+
+impl<const N: usize, P: Parker> StaticStrategy<N, P> {
+    const fn with_parker() -> Self {
+        const FREE_SLOT: AtomicUsize = AtomicUsize::new(NOT_SWAPPED);
+
+        Self {
+            swap_state: AtomicUsize::new(NOT_SWAPPED),
+            slots: [FREE_SLOT; N],
+            residual: AtomicIsize::new(0),
+            parker: P::NEW,
+        }
+    }
+}
+
+impl<const N: usize> Default for StaticStrategy<N, AsyncParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_parker()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize> Default for StaticStrategy<N, ThreadParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_parker()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize> Default for StaticStrategy<N, AdaptiveParkToken> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_parker()
+    }
+}
+
+impl<const N: usize, P> StaticStrategy<N, P> {
+    /// The number of reader slots that are not claimed by any reader id
+    pub fn free_slots(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.load(Ordering::Relaxed) & OCCUPIED == 0)
+            .count()
+    }
+
+    fn claim_slot(&self) -> Option<NonNull<AtomicUsize>> {
+        // free slots are swapped along with the occupied slots, so they always
+        // hold the current swap state, and can be claimed by just marking them
+        self.slots
+            .iter()
+            .find(|slot| {
+                slot.load(Ordering::Relaxed) & OCCUPIED == 0
+                    && slot.fetch_or(OCCUPIED, Ordering::Acquire) & OCCUPIED == 0
+            })
+            .map(NonNull::from)
+    }
+
+    fn create_reader_id(&self) -> ReaderId {
+        ReaderId {
+            slot: self.claim_slot(),
+        }
+    }
+}
+
+impl Drop for ReaderId {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            // SAFETY: The reader is is only created in create_reader_id_from_* which require the
+            // id is dropped before the strategy, so if we have reached this point then
+            // the slot is still alive
+            let slot = unsafe { slot.as_ref() };
+            slot.fetch_and(!OCCUPIED, Ordering::Release);
+        }
+    }
+}
+
+// SAFETY: StaticStrategy when used as a strategy for a double buffer is thread safe
+// because finish_swap doesn't return while there are any readers in the
+// buffer that the writer (even if the readers are on other threads). see the
+// flashmap module docs for more information on the particular algorithm.
+unsafe impl<const N: usize, P: Parker> Strategy for StaticStrategy<N, P> {
+    type WriterId = WriterId;
+    type ReaderId = ReaderId;
+
+    type Swap = Swap;
+    type SwapError = core::convert::Infallible;
+
+    type ReadGuard = ReadGuard;
+
+    #[inline]
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        WriterId(())
+    }
+
+    #[inline]
+    unsafe fn create_reader_id_from_writer(&self, _writer: &Self::WriterId) -> Self::ReaderId {
+        self.create_reader_id()
+    }
+
+    #[inline]
+    unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {
+        self.create_reader_id()
+    }
+
+    #[inline]
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        ReaderId { slot: None }
+    }
+
+    unsafe fn is_swapped_writer(&self, _writer: &Self::WriterId) -> bool {
+        // SAFETY: The only write to self.swap_state happens in try_start_swap
+        // which needs a &mut Self::WriterId, but we current hold a &Self::WriterId.
+        //
+        // There are at most 1 Self::WriterId's associated with a given strategy at a time.
+        //
+        // So there must be some synchronization between this and `try_start_swap`.
+        // So there can be no race between that write and this read.
+        //
+        // And it is fine to race two (non-atomic) reads
+        let swap_state = unsafe { core::ptr::read(&self.swap_state).into_inner() };
+        swap_state != NOT_SWAPPED
+    }
+
+    unsafe fn is_swapped(&self, _reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        guard.swap_state & SWAPPED != NOT_SWAPPED
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        _writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        let old_swap_state = self.swap_state.fetch_xor(SWAPPED, Ordering::Release);
+
+        let residual_swap_state = old_swap_state | READER_ACTIVE;
+        let mut residual = 0;
+
+        // swap every slot, even the free ones, so that newly claimed slots
+        // start out with the current swap state
+        for slot in &self.slots {
+            let slot_swap_state = slot.fetch_xor(SWAPPED, Ordering::AcqRel);
+
+            // This increment is bounded by N, which can never exceed
+            // isize::MAX (because of the max size of the slot array)
+            // so this increment can never overflow
+            #[allow(clippy::arithmetic_side_effects)]
+            if slot_swap_state & !OCCUPIED == residual_swap_state {
+                residual += 1;
+            }
+        }
+
+        self.residual.fetch_add(residual, Ordering::Release);
+
+        Ok(Swap)
+    }
+
+    unsafe fn is_swap_finished(&self, _writer: &mut Self::WriterId, Swap: &mut Self::Swap) -> bool {
+        self.residual.load(Ordering::Acquire) == 0
+    }
+
+    unsafe fn is_swap_finished_shared(&self, _writer: &Self::WriterId) -> Option<bool> {
+        // the swap doesn't hold any state, so this is the same as `is_swap_finished`
+        Some(self.residual.load(Ordering::Acquire) == 0)
+    }
+
+    unsafe fn residual_readers(
+        &self,
+        _writer: &Self::WriterId,
+        Swap: &Self::Swap,
+    ) -> Option<usize> {
+        // the residual may be temporarily negative if a reader finished before
+        // the writer registered it as a residual reader
+        Some(usize::try_from(self.residual.load(Ordering::Acquire)).unwrap_or(0))
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // SAFETY: guaranteed by caller
+        unsafe { self.try_acquire_read_guard(reader) }.expect("Detected a leaked read guard")
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        let slot = match reader.slot {
+            Some(slot) => slot,
+            None => *reader.slot.insert(
+                self.claim_slot()
+                    .expect("All reader slots of the StaticStrategy are taken"),
+            ),
+        };
+
+        // SAFETY: the reader id is valid, so the slot points into this strategy
+        let slot = unsafe { slot.as_ref() };
+
+        if slot.load(Ordering::Relaxed) & READER_ACTIVE != 0 {
+            return None;
+        }
+
+        let id = slot.fetch_or(READER_ACTIVE, Ordering::Release);
+        Some(ReadGuard { swap_state: id })
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        // SAFETY: the reader was previously acquired, so this must be Some
+        let slot = unsafe { reader.slot.unwrap_unchecked() };
+        // SAFETY: the reader id is valid, so the slot points into this strategy
+        let slot = unsafe { slot.as_ref() };
+        let slot_swap_state = slot.fetch_and(!READER_ACTIVE, Ordering::Release);
+
+        // if there wasn't any intervening swap then just return
+        if guard.swap_state & SWAPPED == slot_swap_state & SWAPPED {
+            return;
+        }
+
+        // if was an intervening swap, then this is a residual reader
+        // from the last swap. So we should register it as such
+
+        let residual = self.residual.fetch_sub(1, Ordering::AcqRel);
+
+        // if there are more residual readers, then someone else will wake up the writer
+        if residual != 1 {
+            return;
+        }
+
+        // if this is the last residual reader, then wake up the writer
+
+        // SAFETY: residual is non-zero
+        unsafe { self.parker.wake() }
+    }
+}
+
+// SAFETY: we check if is_swap_finished would return true before returning Poll::Ready
+unsafe impl<const N: usize> AsyncStrategy for StaticStrategy<N, AsyncParkToken> {
+    unsafe fn register_context(
+        &self,
+        _writer: &mut Self::WriterId,
+        Swap: &mut Self::Swap,
+        ctx: &mut core::task::Context<'_>,
+    ) -> Poll<()> {
+        self.poll(|should_set| {
+            if should_set {
+                self.parker.set(ctx)
+            } else {
+                self.parker.clear();
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning
+unsafe impl<const N: usize> crate::interface::BlockingStrategy
+    for StaticStrategy<N, ThreadParkToken>
+{
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, Swap: Self::Swap) {
+        if self
+            .poll(|should_set| {
+                if should_set {
+                    self.parker.set()
+                } else {
+                    self.parker.clear();
+                }
+            })
+            .is_pending()
+        {
+            while self.residual.load(Ordering::Relaxed) != 0 {
+                std::thread::park();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning Poll::Ready
+unsafe impl<const N: usize> AsyncStrategy for StaticStrategy<N, AdaptiveParkToken> {
+    unsafe fn register_context(
+        &self,
+        _writer: &mut Self::WriterId,
+        Swap: &mut Self::Swap,
+        ctx: &mut core::task::Context<'_>,
+    ) -> Poll<()> {
+        self.poll(|should_set| {
+            if should_set {
+                self.parker.async_token.set(ctx)
+            } else {
+                self.parker.async_token.clear();
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: we check if is_swap_finished would return true before returning
+unsafe impl<const N: usize> crate::interface::BlockingStrategy
+    for StaticStrategy<N, AdaptiveParkToken>
+{
+    unsafe fn finish_swap(&self, _writer: &mut Self::WriterId, Swap: Self::Swap) {
+        if self
+            .poll(|should_set| {
+                if should_set {
+                    self.parker.thread_token.set()
+                } else {
+                    self.parker.thread_token.clear();
+                }
+            })
+            .is_pending()
+        {
+            while self.residual.load(Ordering::Relaxed) != 0 {
+                std::thread::park();
+            }
+        }
+    }
+}
+
+impl<const N: usize, P> StaticStrategy<N, P> {
+    fn poll(&self, mut setup: impl FnMut(bool)) -> Poll<()> {
+        if self.residual.load(Ordering::Acquire) == 0 {
+            return Poll::Ready(());
+        }
+
+        setup(true);
+        let residual = self.residual.load(Ordering::Acquire);
+        // if all residual readers finished already
+        if residual == 0 {
+            setup(false);
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+}
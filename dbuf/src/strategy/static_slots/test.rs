@@ -0,0 +1,93 @@
+#![allow(unused)]
+
+use super::StaticStrategy;
+
+use crate::{
+    delay::DelayWriter,
+    raw::{DoubleBufferData, Writer},
+    strategy::flash_park_token::AsyncParkToken,
+};
+
+use pollster::test as async_test;
+
+#[test]
+fn smoke() {
+    let mut state = DoubleBufferData::new(0, 1, StaticStrategy::<4, AsyncParkToken>::new_async());
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+
+    let x = reader.read();
+    assert_eq!(*x, *writer.split().read);
+
+    // SAFETY: finish_swap is called before split_mut/get_mut is called
+    let mut swap = unsafe { writer.try_start_swap().unwrap() };
+
+    // SAFETY: the swap is the latest swap
+    assert!(!unsafe { writer.is_swap_finished(&mut swap) });
+
+    assert_eq!(*x, *writer.split().write);
+
+    drop(x);
+
+    // SAFETY: the swap is the latest swap
+    assert!(unsafe { writer.is_swap_finished(&mut swap) });
+}
+
+#[test]
+fn slot_reclamation() {
+    let mut state = DoubleBufferData::new(0, 1, StaticStrategy::<1, AsyncParkToken>::new_async());
+    let mut writer = Writer::new(&mut state);
+
+    let mut reader = writer.reader();
+    assert_eq!(*reader.read(), 0);
+
+    // SAFETY: is_swap_finished is called before split_mut/get_mut is called
+    let mut swap = unsafe { writer.try_start_swap().unwrap() };
+    // SAFETY: the swap is the latest swap
+    assert!(unsafe { writer.is_swap_finished(&mut swap) });
+
+    drop(reader);
+
+    // the slot was given back, so the new reader can claim it
+    // and it must see the swapped buffer
+    let mut reader = writer.reader();
+    assert_eq!(*reader.read(), 1);
+}
+
+#[test]
+#[should_panic = "All reader slots of the StaticStrategy are taken"]
+fn at_capacity() {
+    let mut state = DoubleBufferData::new(0, 1, StaticStrategy::<1, AsyncParkToken>::new_async());
+    let writer = Writer::new(&mut state);
+
+    let _reader = writer.reader();
+    let mut other = writer.reader();
+    let _ = other.read();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn map_with_old_reader() {
+    use alloc::sync::Arc;
+
+    use rc_box::ArcBox;
+
+    type Buffer = DoubleBufferData<i32, StaticStrategy<1, AsyncParkToken>>;
+
+    let writer: Writer<Arc<Buffer>> = Writer::new(ArcBox::new(DoubleBufferData::new(
+        0,
+        1,
+        StaticStrategy::new_async(),
+    )));
+
+    // the old reader's id holds the only slot, so it can't be moved to the new strategy
+    let old = writer.reader();
+    let writer = writer.try_map(|x| x + 10).unwrap_err();
+    drop(old);
+
+    let writer = writer.try_map(|x| x + 10).unwrap();
+    let mut reader = writer.reader();
+    assert_eq!(*reader.read(), 10);
+    assert_eq!(*writer.get(), 11);
+}
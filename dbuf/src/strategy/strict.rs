@@ -0,0 +1,363 @@
+//! This is a thin wrapper around another strategy which checks that the strategy is used correctly
+//!
+//! In debug builds (with `debug_assertions`), [`StrictStrategy`] panics if
+//!
+//! * a reader id acquires a read guard while it still holds an unreleased guard
+//! * a reader id releases a read guard while it doesn't hold a guard
+//! * a swap is reported as finished while a reader is still reading the buffer that
+//!   the writer is about to write to (i.e. the inner strategy broke its contract)
+//!
+//! In release builds none of this is tracked, and all methods defer directly to the inner
+//! strategy. (except that [`StrictStrategy`] is a bit larger than the inner strategy)
+//!
+//! Note that [`Strategy::try_acquire_read_guard`] returns [`None`] instead of panicking if
+//! there is an unreleased guard, as the trait requires.
+
+#[cfg(debug_assertions)]
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::interface::{
+    AsyncStrategy, BlockingStrategy, BufferOccupancy, BufferSide, NotifyReaders, ReaderLock,
+    Strategy,
+};
+
+#[cfg(test)]
+mod test;
+
+pub struct StrictStrategy<S> {
+    strategy: S,
+    // how many guards are reading each buffer, indexed by the same index as `DoubleBufferData`'s buffers
+    #[cfg(debug_assertions)]
+    active_readers: [AtomicUsize; 2],
+}
+
+pub struct StrictReaderId<R> {
+    id: R,
+    #[cfg(debug_assertions)]
+    reading: bool,
+}
+
+pub struct StrictReadGuard<G> {
+    guard: G,
+    #[cfg(debug_assertions)]
+    buffer: usize,
+}
+
+impl<S> StrictStrategy<S> {
+    pub const fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            #[cfg(debug_assertions)]
+            active_readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+        }
+    }
+
+    /// Get the inner strategy
+    pub const fn get_ref(&self) -> &S {
+        &self.strategy
+    }
+}
+
+impl<S: Strategy> StrictStrategy<S> {
+    /// Record a read guard which was just acquired from the inner strategy
+    ///
+    /// # Safety
+    ///
+    /// The reader id must be valid, and the guard must be the latest guard created from it
+    #[cfg(debug_assertions)]
+    unsafe fn track_guard(
+        &self,
+        reader: &mut StrictReaderId<S::ReaderId>,
+        guard: S::ReadGuard,
+    ) -> StrictReadGuard<S::ReadGuard> {
+        // SAFETY: guaranteed by caller
+        let swapped = unsafe { self.strategy.is_swapped(&mut reader.id, &guard) };
+        // readers read the buffer that the writer isn't writing to, see `DoubleBufferCell::get`
+        let buffer = (!swapped) as usize;
+        reader.reading = true;
+        self.active_readers[buffer].fetch_add(1, Ordering::AcqRel);
+        StrictReadGuard { guard, buffer }
+    }
+
+    #[cfg(not(debug_assertions))]
+    const unsafe fn track_guard(
+        &self,
+        _reader: &mut StrictReaderId<S::ReaderId>,
+        guard: S::ReadGuard,
+    ) -> StrictReadGuard<S::ReadGuard> {
+        StrictReadGuard { guard }
+    }
+
+    /// Check that no readers are reading the buffer that the writer is writing to
+    ///
+    /// # Safety
+    ///
+    /// The writer id must be valid
+    #[cfg(debug_assertions)]
+    unsafe fn check_swap_finished(&self, writer: &S::WriterId) {
+        // SAFETY: guaranteed by caller
+        let swapped = unsafe { self.strategy.is_swapped_writer(writer) };
+        let readers = self.active_readers[swapped as usize].load(Ordering::Acquire);
+        assert!(
+            readers == 0,
+            "the swap finished while {readers} reader(s) were still reading the write buffer, \
+             this is a bug in the strategy"
+        );
+    }
+
+    #[cfg(not(debug_assertions))]
+    const unsafe fn check_swap_finished(&self, _writer: &S::WriterId) {}
+}
+
+impl<S: Default> Default for StrictStrategy<S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<R> StrictReaderId<R> {
+    const fn new(id: R) -> Self {
+        Self {
+            id,
+            #[cfg(debug_assertions)]
+            reading: false,
+        }
+    }
+}
+
+/// SAFETY: defer to the safety of S, since all methods defer to `S`
+unsafe impl<S: Strategy> Strategy for StrictStrategy<S> {
+    type WriterId = S::WriterId;
+    type ReaderId = StrictReaderId<S::ReaderId>;
+    type Swap = S::Swap;
+    type SwapError = S::SwapError;
+    type ReadGuard = StrictReadGuard<S::ReadGuard>;
+
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {
+        // SAFETY: defer to S::create_writer_id
+        unsafe { self.strategy.create_writer_id() }
+    }
+
+    unsafe fn create_reader_id_from_writer(&self, writer: &Self::WriterId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_writer
+        StrictReaderId::new(unsafe { self.strategy.create_reader_id_from_writer(writer) })
+    }
+
+    unsafe fn create_reader_id_from_reader(&self, reader: &Self::ReaderId) -> Self::ReaderId {
+        // SAFETY: defer to S::create_reader_id_from_reader
+        StrictReaderId::new(unsafe { self.strategy.create_reader_id_from_reader(&reader.id) })
+    }
+
+    unsafe fn create_detached_reader_id(reader: &Self::ReaderId) -> Option<Self::ReaderId> {
+        // SAFETY: defer to S::create_detached_reader_id
+        unsafe { S::create_detached_reader_id(&reader.id) }.map(StrictReaderId::new)
+    }
+
+    fn create_invalid_reader_id() -> Self::ReaderId {
+        StrictReaderId::new(S::create_invalid_reader_id())
+    }
+
+    /// Reset the inner strategy
+    fn reset(&mut self) {
+        self.strategy.reset()
+    }
+
+    unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::is_swapped_writer
+        unsafe { self.strategy.is_swapped_writer(writer) }
+    }
+
+    unsafe fn is_swapped(&self, reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        // SAFETY: defer to S::is_swapped
+        unsafe { self.strategy.is_swapped(&mut reader.id, &guard.guard) }
+    }
+
+    unsafe fn generation(
+        &self,
+        reader: &mut Self::ReaderId,
+        guard: &Self::ReadGuard,
+    ) -> Option<u64> {
+        // SAFETY: defer to S::generation
+        unsafe { self.strategy.generation(&mut reader.id, &guard.guard) }
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        // SAFETY: defer to S::try_start_swap
+        unsafe { self.strategy.try_start_swap(writer) }
+    }
+
+    unsafe fn is_swap_finished(&self, writer: &mut Self::WriterId, swap: &mut Self::Swap) -> bool {
+        // SAFETY: defer to S::is_swap_finished
+        let finished = unsafe { self.strategy.is_swap_finished(writer, swap) };
+        if finished {
+            // SAFETY: the writer id is valid (guaranteed by caller)
+            unsafe { self.check_swap_finished(writer) }
+        }
+        finished
+    }
+
+    unsafe fn residual_readers(&self, writer: &Self::WriterId, swap: &Self::Swap) -> Option<usize> {
+        // SAFETY: defer to S::residual_readers
+        unsafe { self.strategy.residual_readers(writer, swap) }
+    }
+
+    unsafe fn is_swap_finished_shared(&self, writer: &Self::WriterId) -> Option<bool> {
+        // SAFETY: defer to S::is_swap_finished_shared
+        let finished = unsafe { self.strategy.is_swap_finished_shared(writer) };
+        if finished == Some(true) {
+            // SAFETY: the writer id is valid (guaranteed by caller)
+            unsafe { self.check_swap_finished(writer) }
+        }
+        finished
+    }
+
+    unsafe fn no_active_readers(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: defer to S::no_active_readers
+        unsafe { self.strategy.no_active_readers(writer) }
+    }
+
+    unsafe fn estimated_reader_count(&self, writer: &Self::WriterId) -> Option<usize> {
+        // SAFETY: defer to S::estimated_reader_count
+        unsafe { self.strategy.estimated_reader_count(writer) }
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        #[cfg(debug_assertions)]
+        assert!(
+            !reader.reading,
+            "tried to acquire a read guard while the reader already holds an unreleased guard"
+        );
+
+        // SAFETY: defer to S::acquire_read_guard
+        let guard = unsafe { self.strategy.acquire_read_guard(&mut reader.id) };
+        // SAFETY: the guard was just created from this reader id
+        unsafe { self.track_guard(reader, guard) }
+    }
+
+    unsafe fn try_acquire_read_guard(
+        &self,
+        reader: &mut Self::ReaderId,
+    ) -> Option<Self::ReadGuard> {
+        #[cfg(debug_assertions)]
+        if reader.reading {
+            return None;
+        }
+
+        // SAFETY: defer to S::try_acquire_read_guard
+        let guard = unsafe { self.strategy.try_acquire_read_guard(&mut reader.id) }?;
+        // SAFETY: the guard was just created from this reader id
+        Some(unsafe { self.track_guard(reader, guard) })
+    }
+
+    unsafe fn acquire_read_guard_timeout(
+        &self,
+        reader: &mut Self::ReaderId,
+        timeout: core::time::Duration,
+    ) -> Option<Self::ReadGuard> {
+        #[cfg(debug_assertions)]
+        assert!(
+            !reader.reading,
+            "tried to acquire a read guard while the reader already holds an unreleased guard"
+        );
+
+        // SAFETY: defer to S::acquire_read_guard_timeout
+        let guard = unsafe {
+            self.strategy
+                .acquire_read_guard_timeout(&mut reader.id, timeout)
+        }?;
+        // SAFETY: the guard was just created from this reader id
+        Some(unsafe { self.track_guard(reader, guard) })
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        #[cfg(debug_assertions)]
+        {
+            assert!(
+                reader.reading,
+                "tried to release a read guard while the reader doesn't hold a guard"
+            );
+            reader.reading = false;
+            self.active_readers[guard.buffer].fetch_sub(1, Ordering::AcqRel);
+        }
+
+        // SAFETY: defer to S::release_read_guard
+        unsafe {
+            self.strategy
+                .release_read_guard(&mut reader.id, guard.guard)
+        }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BlockingStrategy> BlockingStrategy for StrictStrategy<S> {
+    unsafe fn finish_swap(&self, writer: &mut Self::WriterId, swap: Self::Swap) {
+        // SAFETY: defer to S::finish_swap
+        unsafe { self.strategy.finish_swap(writer, swap) }
+        // SAFETY: the writer id is valid (guaranteed by caller)
+        unsafe { self.check_swap_finished(writer) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: AsyncStrategy> AsyncStrategy for StrictStrategy<S> {
+    unsafe fn register_context(
+        &self,
+        writer: &mut Self::WriterId,
+        swap: &mut Self::Swap,
+        ctx: &mut Context<'_>,
+    ) -> Poll<()> {
+        // SAFETY: defer to S::register_context
+        let poll = unsafe { self.strategy.register_context(writer, swap, ctx) };
+        if poll.is_ready() {
+            // SAFETY: the writer id is valid (guaranteed by caller)
+            unsafe { self.check_swap_finished(writer) }
+        }
+        poll
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: BufferOccupancy> BufferOccupancy for StrictStrategy<S> {
+    unsafe fn is_buffer_empty(&self, writer: &Self::WriterId, side: BufferSide) -> bool {
+        // SAFETY: defer to S::is_buffer_empty
+        unsafe { self.strategy.is_buffer_empty(writer, side) }
+    }
+}
+
+/// SAFETY: defer to the safety of S
+unsafe impl<S: ReaderLock> ReaderLock for StrictStrategy<S> {
+    unsafe fn try_lock_readers(&self, writer: &mut Self::WriterId) -> bool {
+        // SAFETY: defer to S::try_lock_readers
+        unsafe { self.strategy.try_lock_readers(writer) }
+    }
+
+    unsafe fn unlock_readers(&self, writer: &mut Self::WriterId) {
+        // SAFETY: defer to S::unlock_readers
+        unsafe { self.strategy.unlock_readers(writer) }
+    }
+}
+
+impl<S: NotifyReaders> NotifyReaders for StrictStrategy<S> {
+    unsafe fn wait_for_swap(&self, reader: &mut Self::ReaderId) {
+        // SAFETY: defer to S::wait_for_swap
+        unsafe { self.strategy.wait_for_swap(&mut reader.id) }
+    }
+
+    unsafe fn wait_for_swap_timeout(&self, reader: &mut Self::ReaderId, timeout: Duration) -> bool {
+        // SAFETY: defer to S::wait_for_swap_timeout
+        unsafe { self.strategy.wait_for_swap_timeout(&mut reader.id, timeout) }
+    }
+
+    unsafe fn poll_for_swap(&self, reader: &mut Self::ReaderId, ctx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: defer to S::poll_for_swap
+        unsafe { self.strategy.poll_for_swap(&mut reader.id, ctx) }
+    }
+}
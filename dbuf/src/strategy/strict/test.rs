@@ -0,0 +1,146 @@
+#![allow(clippy::let_unit_value)]
+// the panicking tests only run with debug assertions
+#![cfg_attr(not(debug_assertions), allow(dead_code, unused_imports))]
+
+use super::StrictStrategy;
+
+use crate::{
+    delay::DelayWriter,
+    interface::Strategy,
+    raw::{DoubleBufferData, Writer},
+    strategy::simple_async::SimpleAsyncStrategy,
+};
+
+/// A broken strategy which claims that every swap is finished immediately
+struct EagerStrategy(SimpleAsyncStrategy);
+
+// SAFETY: this isn't safe, is_swap_finished lies. But StrictStrategy panics before
+// the writer can access the buffer that is still being read
+unsafe impl Strategy for EagerStrategy {
+    type WriterId = ();
+    type ReaderId = ();
+    type Swap = ();
+    type SwapError = core::convert::Infallible;
+    type ReadGuard = bool;
+
+    unsafe fn create_writer_id(&mut self) -> Self::WriterId {}
+
+    unsafe fn create_reader_id_from_writer(&self, _writer: &Self::WriterId) -> Self::ReaderId {}
+
+    unsafe fn create_reader_id_from_reader(&self, _reader: &Self::ReaderId) -> Self::ReaderId {}
+
+    fn create_invalid_reader_id() -> Self::ReaderId {}
+
+    unsafe fn is_swapped_writer(&self, writer: &Self::WriterId) -> bool {
+        // SAFETY: guaranteed by caller
+        unsafe { self.0.is_swapped_writer(writer) }
+    }
+
+    unsafe fn is_swapped(&self, reader: &mut Self::ReaderId, guard: &Self::ReadGuard) -> bool {
+        // SAFETY: guaranteed by caller
+        unsafe { self.0.is_swapped(reader, guard) }
+    }
+
+    unsafe fn try_start_swap(
+        &self,
+        writer: &mut Self::WriterId,
+    ) -> Result<Self::Swap, Self::SwapError> {
+        // SAFETY: guaranteed by caller
+        unsafe { self.0.try_start_swap(writer) }
+    }
+
+    unsafe fn is_swap_finished(
+        &self,
+        _writer: &mut Self::WriterId,
+        _swap: &mut Self::Swap,
+    ) -> bool {
+        true
+    }
+
+    unsafe fn acquire_read_guard(&self, reader: &mut Self::ReaderId) -> Self::ReadGuard {
+        // SAFETY: guaranteed by caller
+        unsafe { self.0.acquire_read_guard(reader) }
+    }
+
+    unsafe fn release_read_guard(&self, reader: &mut Self::ReaderId, guard: Self::ReadGuard) {
+        // SAFETY: guaranteed by caller
+        unsafe { self.0.release_read_guard(reader, guard) }
+    }
+}
+
+#[test]
+fn smoke() {
+    let mut state = DoubleBufferData::new(0, 1, StrictStrategy::new(SimpleAsyncStrategy::new()));
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+
+    let mut reader = writer.reader();
+    let a = reader.read();
+
+    writer.start_swap();
+    assert!(!writer.is_swap_finished());
+    drop(a);
+    assert!(writer.is_swap_finished());
+    assert_eq!(*reader.read(), 1);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "reader(s) were still reading the write buffer"]
+fn swap_finished_while_reading() {
+    let mut state = DoubleBufferData::new(
+        0,
+        1,
+        StrictStrategy::new(EagerStrategy(SimpleAsyncStrategy::new())),
+    );
+    let mut writer = DelayWriter::from_writer(Writer::new(&mut state));
+
+    let mut reader = writer.reader();
+    let _guard = reader.read();
+
+    writer.start_swap();
+    writer.is_swap_finished();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "the reader already holds an unreleased guard"]
+fn double_acquire() {
+    let mut strategy = StrictStrategy::new(SimpleAsyncStrategy::new());
+    // SAFETY: the ids are only used with this strategy
+    unsafe {
+        let writer = strategy.create_writer_id();
+        let mut reader = strategy.create_reader_id_from_writer(&writer);
+        let _a = strategy.acquire_read_guard(&mut reader);
+        let _b = strategy.acquire_read_guard(&mut reader);
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn double_try_acquire() {
+    let mut strategy = StrictStrategy::new(SimpleAsyncStrategy::new());
+    // SAFETY: the ids are only used with this strategy
+    unsafe {
+        let writer = strategy.create_writer_id();
+        let mut reader = strategy.create_reader_id_from_writer(&writer);
+        let a = strategy.try_acquire_read_guard(&mut reader).unwrap();
+        assert!(strategy.try_acquire_read_guard(&mut reader).is_none());
+        strategy.release_read_guard(&mut reader, a);
+        assert!(strategy.try_acquire_read_guard(&mut reader).is_some());
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "the reader doesn't hold a guard"]
+fn release_foreign_guard() {
+    let mut strategy = StrictStrategy::new(SimpleAsyncStrategy::new());
+    // SAFETY: the ids are only used with this strategy
+    unsafe {
+        let writer = strategy.create_writer_id();
+        let mut reader = strategy.create_reader_id_from_writer(&writer);
+        let mut other = strategy.create_reader_id_from_reader(&reader);
+        let guard = strategy.acquire_read_guard(&mut reader);
+        strategy.release_read_guard(&mut other, guard);
+    }
+}
@@ -9,6 +9,10 @@ pub struct SplitArc<T: ?Sized> {
     ptr: NonNull<SplitArcInner<T>>,
 }
 
+pub struct UniqueSplitArc<T: ?Sized> {
+    arc: SplitArc<T>,
+}
+
 struct SplitArcInner<T: ?Sized> {
     is_split: AtomicBool,
     data: T,
@@ -17,6 +21,20 @@ struct SplitArcInner<T: ?Sized> {
 unsafe impl<T: Send + Sync> Send for SplitArc<T> {}
 unsafe impl<T: Send + Sync> Sync for SplitArc<T> {}
 
+impl<T> UniqueSplitArc<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            arc: SplitArc::new(data),
+        }
+    }
+}
+
+impl<T: ?Sized> UniqueSplitArc<T> {
+    pub fn shareable(self) -> SplitArc<T> {
+        self.arc
+    }
+}
+
 impl<T> SplitArc<T> {
     pub fn new(data: T) -> Self {
         Self {
@@ -46,6 +64,12 @@ impl<T: ?Sized> SplitArc<T> {
         }
     }
 
+    pub fn is_split(&self) -> bool {
+        let info = unsafe { self.ptr.as_ref() };
+
+        info.is_split.load(Ordering::Acquire)
+    }
+
     pub fn split(&self) -> Self {
         self.try_split().expect("This `SplitArc` is already split")
     }
@@ -67,6 +91,20 @@ impl<T> core::ops::Deref for SplitArc<T> {
     }
 }
 
+impl<T> core::ops::Deref for UniqueSplitArc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.arc
+    }
+}
+
+impl<T> core::ops::DerefMut for UniqueSplitArc<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut (*self.arc.ptr.as_ptr()).data }
+    }
+}
+
 impl<T: ?Sized> Drop for SplitArc<T> {
     fn drop(&mut self) {
         if self.drop_ref() {